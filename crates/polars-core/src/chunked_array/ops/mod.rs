@@ -367,6 +367,14 @@ pub struct SortOptions {
     pub nulls_last: bool,
     pub multithreaded: bool,
     pub maintain_order: bool,
+    /// Only sort in parallel if `multithreaded` is set and there are more values than this.
+    /// Defaults to 0, i.e. `multithreaded` alone decides.
+    #[cfg_attr(feature = "serde-lazy", serde(default = "default_parallel_threshold"))]
+    pub parallel_threshold: usize,
+}
+
+fn default_parallel_threshold() -> usize {
+    0
 }
 
 #[derive(Clone)]
@@ -384,10 +392,62 @@ impl Default for SortOptions {
             nulls_last: false,
             multithreaded: true,
             maintain_order: false,
+            parallel_threshold: default_parallel_threshold(),
+        }
+    }
+}
+
+/// Where the nulls end up in an arg sort.
+///
+/// `First` and `Last` are the SQL `NULLS FIRST` and `NULLS LAST`. `AsSmallest` and
+/// `AsLargest` treat a null as `-inf` or `+inf` in both directions, e.g. a descending sort
+/// with `AsSmallest` puts the nulls at the end.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde-lazy", derive(Serialize, Deserialize))]
+pub enum NullPlacement {
+    /// Put the nulls at the start, regardless of the sort direction.
+    First,
+    /// Put the nulls at the end, regardless of the sort direction.
+    Last,
+    /// Order nulls as if they were larger than any value.
+    AsLargest,
+    /// Order nulls as if they were smaller than any value.
+    AsSmallest,
+}
+
+impl NullPlacement {
+    pub fn from_nulls_last(nulls_last: bool) -> Self {
+        if nulls_last {
+            NullPlacement::Last
+        } else {
+            NullPlacement::First
+        }
+    }
+
+    /// Whether the nulls end up after the values when sorting in the given direction.
+    pub fn nulls_last(self, descending: bool) -> bool {
+        match self {
+            NullPlacement::First => false,
+            NullPlacement::Last => true,
+            NullPlacement::AsLargest => !descending,
+            NullPlacement::AsSmallest => descending,
         }
     }
 }
 
+/// Where the NaN values end up in an arg sort.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Default)]
+#[cfg_attr(feature = "serde-lazy", derive(Serialize, Deserialize))]
+pub enum NanPlacement {
+    /// Order NaN as if it were larger than any value.
+    #[default]
+    NanMax,
+    /// Order NaN as if it were smaller than any value.
+    NanMin,
+    /// Treat NaN as null.
+    NanAsNull,
+}
+
 /// Sort operations on `ChunkedArray`.
 pub trait ChunkSort<T: PolarsDataType> {
     #[allow(unused_variables)]
@@ -397,6 +457,11 @@ pub trait ChunkSort<T: PolarsDataType> {
     fn sort(&self, descending: bool) -> ChunkedArray<T>;
 
     /// Retrieve the indexes needed to sort this array.
+    ///
+    /// Sorting descending gives the reverse of the ascending indices with the opposite
+    /// `nulls_last`, so the nulls are in reverse order of appearance. Only equal valid values
+    /// differ, as they keep their order of appearance in both directions if `maintain_order`
+    /// is set.
     fn arg_sort(&self, options: SortOptions) -> IdxCa;
 
     /// Retrieve the indexes need to sort this and the other arrays.
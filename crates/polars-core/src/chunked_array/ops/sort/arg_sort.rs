@@ -1,15 +1,54 @@
+use std::collections::{BinaryHeap, VecDeque};
+
+use arrow::bitmap::Bitmap;
+use num_traits::{Signed, ToPrimitive};
+use polars_error::constants::LENGTH_LIMIT_MSG;
 use polars_utils::float::IsFloat;
 
 use super::*;
 
+/// Sort the `(index, value)` pairs. If `options.maintain_order` is set, equal values are
+/// guaranteed to keep the order of their index.
 #[inline]
-fn ascending_order<T: PartialOrd + IsFloat>(a: &(IdxSize, T), b: &(IdxSize, T)) -> Ordering {
-    compare_fn_nan_max(&a.1, &b.1)
+pub(super) fn sort_index_values<T>(
+    vals: &mut [(IdxSize, T)],
+    options: SortOptions,
+    nan_placement: NanPlacement,
+) where
+    T: PartialOrd + Send + Sync + IsFloat,
+{
+    match nan_placement {
+        NanPlacement::NanMin => sort_index_values_by(vals, options, compare_fn_nan_min::<T>),
+        NanPlacement::NanMax | NanPlacement::NanAsNull => {
+            sort_index_values_by(vals, options, compare_fn_nan_max::<T>)
+        },
+    }
 }
 
+/// Sort the `(index, value)` pairs by `compare` of the values. `compare` is the ascending
+/// order, `options.descending` flips it.
 #[inline]
-fn descending_order<T: PartialOrd + IsFloat>(a: &(IdxSize, T), b: &(IdxSize, T)) -> Ordering {
-    compare_fn_nan_max(&b.1, &a.1)
+fn sort_index_values_by<T, F>(vals: &mut [(IdxSize, T)], options: SortOptions, compare: F)
+where
+    T: Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Send + Sync,
+{
+    let stable = options.maintain_order;
+    let ties = |a: &(IdxSize, T), b: &(IdxSize, T)| {
+        if stable {
+            a.0.cmp(&b.0)
+        } else {
+            Ordering::Equal
+        }
+    };
+    arg_sort_branch(
+        vals,
+        options.descending,
+        |a: &(IdxSize, T), b: &(IdxSize, T)| compare(&a.1, &b.1).then_with(|| ties(a, b)),
+        |a: &(IdxSize, T), b: &(IdxSize, T)| compare(&b.1, &a.1).then_with(|| ties(a, b)),
+        options.multithreaded,
+        options.parallel_threshold,
+    )
 }
 
 pub(super) fn arg_sort<I, J, T>(
@@ -23,13 +62,1311 @@ where
     I: IntoIterator<Item = J>,
     J: IntoIterator<Item = Option<T>>,
     T: PartialOrd + Send + Sync + IsFloat,
+{
+    arg_sort_with_comparator(name, iters, options, NanMaxOrder, null_count, len)
+}
+
+/// Arg sort where the placement of the nulls is decided by `null_placement` instead of
+/// `options.nulls_last`.
+///
+/// [`NullPlacement::First`] and [`NullPlacement::Last`] put the nulls in reverse order of
+/// appearance when sorting descending. [`NullPlacement::AsLargest`] and
+/// [`NullPlacement::AsSmallest`] treat the nulls as equal values, so they keep their order.
+pub fn arg_sort_with_null_placement<I, J, T>(
+    name: &str,
+    iters: I,
+    options: SortOptions,
+    null_placement: NullPlacement,
+    null_count: usize,
+    len: usize,
+) -> IdxCa
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: PartialOrd + Send + Sync + IsFloat,
+{
+    arg_sort_with_nan_placement(
+        name,
+        iters,
+        options,
+        null_placement,
+        NanPlacement::NanMax,
+        null_count,
+        len,
+    )
+}
+
+/// Arg sort where the nulls sort as if they were `null_value`, so they are interleaved with
+/// the valid values instead of being placed first or last, e.g. as zeros.
+///
+/// Nulls that tie with valid values are ordered like equal values. Without a `null_value`,
+/// this is the arg sort that places the nulls by `options.nulls_last`.
+pub fn arg_sort_nulls_at<I, J, T>(
+    name: &str,
+    iters: I,
+    options: SortOptions,
+    null_value: Option<T>,
+    null_count: usize,
+    len: usize,
+) -> IdxCa
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: PartialOrd + Send + Sync + IsFloat + Copy,
+{
+    match null_value {
+        Some(null_value) => {
+            let iters = iters
+                .into_iter()
+                .map(|iter| iter.into_iter().map(move |v| Some(v.unwrap_or(null_value))));
+            arg_sort_with_comparator(name, iters, options, NanMaxOrder, 0, len)
+        },
+        None => arg_sort_with_comparator(name, iters, options, NanMaxOrder, null_count, len),
+    }
+}
+
+/// Arg sort where the placement of the NaN values is decided by `nan_placement`.
+///
+/// With [`NanPlacement::NanAsNull`] the NaN values are placed according to the
+/// `null_placement`, together with the nulls and in order of appearance.
+pub fn arg_sort_with_nan_placement<I, J, T>(
+    name: &str,
+    iters: I,
+    options: SortOptions,
+    null_placement: NullPlacement,
+    nan_placement: NanPlacement,
+    null_count: usize,
+    len: usize,
+) -> IdxCa
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: PartialOrd + Send + Sync + IsFloat,
+{
+    let (nulls_last, reverse_nulls) = resolve_null_placement(options.descending, null_placement);
+    if null_count == len {
+        return arg_sort_all_null(name, len, reverse_nulls);
+    }
+    let nan_as_null = matches!(nan_placement, NanPlacement::NanAsNull);
+    let (mut vals, nulls_idx) = partition_nulls(iters, nulls_last, nan_as_null, null_count, len);
+
+    sort_index_values(vals.as_mut_slice(), options, nan_placement);
+
+    let iter = vals.into_iter().map(|(idx, _v)| idx);
+    let idx = finish_idx(iter, nulls_idx, nulls_last, reverse_nulls, len);
+    ChunkedArray::with_chunk(name, IdxArr::from_data_default(Buffer::from(idx), None))
+}
+
+/// Arg sort of values with a separate validity bitmap, e.g. the values buffer of an arrow
+/// array. The values don't have to be wrapped in an `Option`, the nulls are looked up in
+/// `validity`, which must have the length of `values`.
+///
+/// The output is equal to [`arg_sort_with_null_placement`] with the placement of
+/// `options.nulls_last`.
+pub fn arg_sort_with_validity<I, T>(
+    name: &str,
+    values: I,
+    validity: &Bitmap,
+    options: SortOptions,
+) -> IdxCa
+where
+    I: IntoIterator<Item = T>,
+    T: PartialOrd + Send + Sync + IsFloat,
+{
+    let len = validity.len();
+    assert_idx_len(len);
+    let null_count = validity.unset_bits();
+    let null_placement = NullPlacement::from_nulls_last(options.nulls_last);
+    let (nulls_last, reverse_nulls) = resolve_null_placement(options.descending, null_placement);
+    if null_count == len {
+        return arg_sort_all_null(name, len, reverse_nulls);
+    }
+
+    let mut vals = Vec::with_capacity(len - null_count);
+    // If the nulls go first, the sorted indices are appended to them.
+    let mut nulls_idx = Vec::with_capacity(if nulls_last { null_count } else { len });
+    for (i, (v, valid)) in values.into_iter().zip(validity.iter()).enumerate() {
+        if valid {
+            vals.push((i as IdxSize, v));
+        } else {
+            nulls_idx.push(i as IdxSize);
+        }
+    }
+    debug_assert_eq!(vals.len() + nulls_idx.len(), len);
+
+    sort_index_values(vals.as_mut_slice(), options, NanPlacement::NanMax);
+
+    let iter = vals.into_iter().map(|(idx, _v)| idx);
+    let idx = finish_idx(iter, nulls_idx, nulls_last, reverse_nulls, len);
+    ChunkedArray::with_chunk(name, IdxArr::from_data_default(Buffer::from(idx), None))
+}
+
+/// Arg sort of a contiguous slice without nulls. Unlike [`arg_sort_with_null_placement`] the
+/// values are not wrapped in an `Option`, so there is no null bookkeeping at all.
+pub fn arg_sort_slice<T>(name: &str, values: &[T], options: SortOptions) -> IdxCa
+where
+    T: PartialOrd + Send + Sync + IsFloat + Copy,
+{
+    assert_idx_len(values.len());
+    let mut vals = Vec::with_capacity(values.len());
+    vals.extend_trusted_len(values.iter().enumerate().map(|(i, &v)| (i as IdxSize, v)));
+    arg_sort_no_null_values(name, vals, options)
+}
+
+/// Sort the `(index, value)` pairs of values without nulls and return the indices.
+pub(super) fn arg_sort_no_null_values<T>(
+    name: &str,
+    mut vals: Vec<(IdxSize, T)>,
+    options: SortOptions,
+) -> IdxCa
+where
+    T: PartialOrd + Send + Sync + IsFloat,
+{
+    if options.maintain_order {
+        sort_index_values(vals.as_mut_slice(), options, NanPlacement::NanMax);
+    } else {
+        arg_sort_branch(
+            vals.as_mut_slice(),
+            options.descending,
+            |(_, a), (_, b)| compare_fn_nan_max(a, b),
+            |(_, a), (_, b)| compare_fn_nan_max(b, a),
+            options.multithreaded,
+            options.parallel_threshold,
+        );
+    }
+
+    let out: NoNull<IdxCa> = vals.into_iter().map(|(idx, _v)| idx).collect_trusted();
+    let mut out = out.into_inner();
+    out.rename(name);
+    out
+}
+
+/// Reusable buffers of [`arg_sort_into`], so that repeated arg sorts of values of type `T`
+/// don't allocate once the buffers are large enough.
+#[derive(Clone, Debug)]
+pub struct ArgSortScratch<T> {
+    vals: Vec<(IdxSize, T)>,
+    nulls_idx: Vec<IdxSize>,
+    idx: Vec<IdxSize>,
+}
+
+impl<T> Default for ArgSortScratch<T> {
+    fn default() -> Self {
+        Self {
+            vals: vec![],
+            nulls_idx: vec![],
+            idx: vec![],
+        }
+    }
+}
+
+impl<T> ArgSortScratch<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Arg sort that uses the buffers of `scratch` instead of allocating new ones and returns
+/// the sorted indices, which are stored in `scratch` until the next call.
+///
+/// The output is equal to [`arg_sort_with_null_placement`] with the placement of
+/// `options.nulls_last`.
+pub fn arg_sort_into<'a, I, J, T>(
+    iters: I,
+    options: SortOptions,
+    scratch: &'a mut ArgSortScratch<T>,
+) -> &'a [IdxSize]
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: PartialOrd + Send + Sync + IsFloat,
+{
+    let null_placement = NullPlacement::from_nulls_last(options.nulls_last);
+    let (nulls_last, reverse_nulls) = resolve_null_placement(options.descending, null_placement);
+    let ArgSortScratch {
+        vals,
+        nulls_idx,
+        idx,
+    } = scratch;
+    vals.clear();
+    nulls_idx.clear();
+    idx.clear();
+
+    let mut count: IdxSize = 0;
+    for arr_iter in iters {
+        for v in arr_iter {
+            let i = count;
+            count = count.checked_add(1).expect(LENGTH_LIMIT_MSG);
+            match v {
+                Some(v) => vals.push((i, v)),
+                None => nulls_idx.push(i),
+            }
+        }
+    }
+    if reverse_nulls {
+        nulls_idx.reverse();
+    }
+
+    sort_index_values(vals.as_mut_slice(), options, NanPlacement::NanMax);
+
+    if !nulls_last {
+        idx.extend_from_slice(nulls_idx);
+    }
+    // Move the values out, so that the buffer keeps its capacity.
+    idx.extend(vals.drain(..).map(|(i, _v)| i));
+    if nulls_last {
+        idx.extend_from_slice(nulls_idx);
+    }
+    idx
+}
+
+/// A total order of the values of an arg sort, see [`arg_sort_with_comparator`].
+///
+/// It is implemented for closures `Fn(&T, &T) -> Ordering`.
+pub trait ArgSortOrder<T>: Send + Sync {
+    /// The ascending order of `a` and `b`.
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+impl<T, F> ArgSortOrder<T> for F
+where
+    F: Fn(&T, &T) -> Ordering + Send + Sync,
+{
+    #[inline]
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// The default order of an arg sort, where NaN is larger than any other value.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NanMaxOrder;
+
+impl<T: PartialOrd + IsFloat> ArgSortOrder<T> for NanMaxOrder {
+    #[inline]
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        compare_fn_nan_max(a, b)
+    }
+}
+
+/// The order of the absolute values, e.g. to rank by magnitude. NaN is larger than any
+/// other value.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct AbsOrder;
+
+impl<T: PartialOrd + IsFloat + Signed> ArgSortOrder<T> for AbsOrder {
+    #[inline]
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        compare_fn_nan_max(&a.abs(), &b.abs())
+    }
+}
+
+/// Arg sort by the absolute values, without materializing them. Equal to
+/// [`arg_sort_with_comparator`] with [`AbsOrder`].
+pub fn arg_sort_by_abs<I, J, T>(
+    name: &str,
+    iters: I,
+    options: SortOptions,
+    null_count: usize,
+    len: usize,
+) -> IdxCa
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: PartialOrd + Send + Sync + IsFloat + Signed,
+{
+    arg_sort_with_comparator(name, iters, options, AbsOrder, null_count, len)
+}
+
+/// Arg sort with a custom total order of the values.
+///
+/// `order` is the ascending order, `options.descending` flips it, so callers don't have
+/// to invert the values or the comparator. The values don't have to implement `PartialOrd`,
+/// e.g. a wrapper type can be sorted by a domain-specific order without converting it into
+/// a column. [`arg_sort_with_null_placement`] uses [`NanMaxOrder`].
+pub fn arg_sort_with_comparator<I, J, T, O>(
+    name: &str,
+    iters: I,
+    options: SortOptions,
+    order: O,
+    null_count: usize,
+    len: usize,
+) -> IdxCa
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: Send + Sync,
+    O: ArgSortOrder<T>,
+{
+    let null_placement = NullPlacement::from_nulls_last(options.nulls_last);
+    let (nulls_last, reverse_nulls) = resolve_null_placement(options.descending, null_placement);
+    if null_count == len {
+        return arg_sort_all_null(name, len, reverse_nulls);
+    }
+    let (mut vals, nulls_idx) = partition_nulls_by(iters, nulls_last, None, null_count, len);
+
+    sort_index_values_by(vals.as_mut_slice(), options, |a, b| order.compare(a, b));
+
+    let iter = vals.into_iter().map(|(idx, _v)| idx);
+    let idx = finish_idx(iter, nulls_idx, nulls_last, reverse_nulls, len);
+    ChunkedArray::with_chunk(name, IdxArr::from_data_default(Buffer::from(idx), None))
+}
+
+/// Reverse the direction of an arg sort, i.e. turn the ascending indices into the descending
+/// ones and vice versa, without sorting again.
+///
+/// The `null_count` indices of the nulls stay at the start, or at the end if `nulls_last` is
+/// set, and are reversed as well, like in a descending arg sort. Errors if there are fewer
+/// than `null_count` indices.
+///
+/// Only the indices are reversed, so equal values end up in the reverse of their order in
+/// `idx`: the output is a valid arg sort in the other direction, but it doesn't maintain the
+/// order of equal values. It is only equal to an arg sort with `maintain_order` in the other
+/// direction if the values are unique.
+pub fn reverse_arg_sort(idx: &IdxCa, null_count: usize, nulls_last: bool) -> PolarsResult<IdxCa> {
+    let mut out = idx.into_no_null_iter().collect::<Vec<_>>();
+    polars_ensure!(
+        null_count <= out.len(),
+        ComputeError: "cannot reverse an arg sort of {} indices with {} nulls", out.len(), null_count
+    );
+    let split = if nulls_last {
+        out.len() - null_count
+    } else {
+        null_count
+    };
+    let (head, tail) = out.split_at_mut(split);
+    head.reverse();
+    tail.reverse();
+    Ok(ChunkedArray::with_chunk(
+        idx.name(),
+        IdxArr::from_data_default(Buffer::from(out), None),
+    ))
+}
+
+/// Gather the values of `ca` in the order of `idx`, the output of an arg sort, e.g. to apply
+/// the order of one column to several sibling columns.
+///
+/// Unlike [`ChunkTake::take`] the indices are not bounds checked and their validity is
+/// ignored.
+///
+/// # Safety
+/// `idx` must not contain nulls and every index must be smaller than `ca.len()`. This holds
+/// for the output of an arg sort of a column of the same length.
+pub unsafe fn sort_by_indices<T>(ca: &ChunkedArray<T>, idx: &IdxCa) -> ChunkedArray<T>
+where
+    T: PolarsDataType,
+{
+    debug_assert_eq!(idx.null_count(), 0);
+    let idx = idx.rechunk();
+    let idx = idx.downcast_iter().next().unwrap().values();
+    ca.take_unchecked(idx.as_slice())
+}
+
+/// Arg sort that also returns the sorted values, so they don't have to be gathered with
+/// the indices afterwards.
+///
+/// The nulls of the values are placed at the same positions as their indices.
+pub fn arg_sort_with_values<I, J, T>(
+    name: &str,
+    iters: I,
+    options: SortOptions,
+    null_placement: NullPlacement,
+    null_count: usize,
+    len: usize,
+) -> (IdxCa, ChunkedArray<T>)
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T::Native>>,
+    T: PolarsNumericType,
+{
+    let (nulls_last, reverse_nulls) = resolve_null_placement(options.descending, null_placement);
+    let (mut vals, nulls_idx) = partition_nulls(iters, nulls_last, false, null_count, len);
+
+    sort_index_values(vals.as_mut_slice(), options, NanPlacement::NanMax);
+
+    let sorted = vals.iter().map(|(_, v)| Some(*v));
+    let nulls = std::iter::repeat(None).take(nulls_idx.len());
+    let mut values: ChunkedArray<T> = if nulls_last {
+        ChunkedArray::from_iter_options(name, sorted.chain(nulls))
+    } else {
+        ChunkedArray::from_iter_options(name, nulls.chain(sorted))
+    };
+    values.set_sorted_flag(if options.descending {
+        IsSorted::Descending
+    } else {
+        IsSorted::Ascending
+    });
+
+    let iter = vals.into_iter().map(|(idx, _v)| idx);
+    let idx = finish_idx(iter, nulls_idx, nulls_last, reverse_nulls, len);
+    let idx = ChunkedArray::with_chunk(name, IdxArr::from_data_default(Buffer::from(idx), None));
+    (idx, values)
+}
+
+/// The rank of every value: its position in the arg sort with the same arguments, i.e. the
+/// inverse permutation of [`arg_sort_with_null_placement`]. The ranks are written directly
+/// from the sorted values, without materializing the sorted indices first.
+///
+/// The nulls get the ranks of the positions they are placed at.
+pub fn arg_sort_to_rank<I, J, T>(
+    name: &str,
+    iters: I,
+    options: SortOptions,
+    null_placement: NullPlacement,
+    null_count: usize,
+    len: usize,
+) -> IdxCa
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: PartialOrd + Send + Sync + IsFloat,
+{
+    let (nulls_last, reverse_nulls) = resolve_null_placement(options.descending, null_placement);
+    let (mut vals, mut nulls_idx) = partition_nulls(iters, nulls_last, false, null_count, len);
+
+    sort_index_values(vals.as_mut_slice(), options, NanPlacement::NanMax);
+
+    let (valid_offset, null_offset) = if nulls_last {
+        (0, vals.len())
+    } else {
+        (nulls_idx.len(), 0)
+    };
+    if reverse_nulls {
+        nulls_idx.reverse();
+    }
+    let mut rank: Vec<IdxSize> = vec![0; len];
+    for (p, (i, _v)) in vals.into_iter().enumerate() {
+        rank[i as usize] = (valid_offset + p) as IdxSize;
+    }
+    for (p, i) in nulls_idx.into_iter().enumerate() {
+        rank[i as usize] = (null_offset + p) as IdxSize;
+    }
+    ChunkedArray::with_chunk(name, IdxArr::from_data_default(Buffer::from(rank), None))
+}
+
+/// Arg sort with `u32` indices, independent of the width of [`IdxSize`].
+///
+/// Errors if `len` is larger than `u32::MAX`.
+pub fn arg_sort_u32<I, J, T>(
+    name: &str,
+    iters: I,
+    options: SortOptions,
+    null_count: usize,
+    len: usize,
+) -> PolarsResult<UInt32Chunked>
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: PartialOrd + Send + Sync + IsFloat,
+{
+    polars_ensure!(
+        u32::try_from(len).is_ok(),
+        ComputeError: "cannot arg sort {} values with u32 indices", len
+    );
+    idx_as(arg_sort(name, iters, options, null_count, len))
+}
+
+/// Arg sort with `u64` indices, independent of the width of [`IdxSize`].
+///
+/// The indices are computed as [`IdxSize`], so this errors if `len` doesn't fit in it.
+pub fn arg_sort_u64<I, J, T>(
+    name: &str,
+    iters: I,
+    options: SortOptions,
+    null_count: usize,
+    len: usize,
+) -> PolarsResult<UInt64Chunked>
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: PartialOrd + Send + Sync + IsFloat,
+{
+    check_idx_len(len)?;
+    idx_as(arg_sort(name, iters, options, null_count, len))
+}
+
+/// Convert the indices to `T`. This is free if `T` is the [`IdxType`].
+fn idx_as<T: PolarsIntegerType>(idx: IdxCa) -> PolarsResult<ChunkedArray<T>> {
+    let idx = idx.into_series();
+    let idx = if idx.dtype() == &T::get_dtype() {
+        idx
+    } else {
+        idx.cast(&T::get_dtype())?
+    };
+    Ok(idx.unpack::<T>()?.clone())
+}
+
+/// Arg sort by the values of `a`, where equal values are ordered by the values of `b`.
+///
+/// Both columns are sorted in the direction and with the null placement of their own
+/// options. The nulls of `a` are equal to each other, so they are ordered by `b` as well.
+/// `a_options` decides whether the sort is stable and multithreaded. Errors if `a` and `b`
+/// don't have the same length.
+pub fn arg_sort_two<I, J, T, K, L, U>(
+    name: &str,
+    a_iters: I,
+    b_iters: K,
+    a_options: SortOptions,
+    b_options: SortOptions,
+) -> PolarsResult<IdxCa>
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: PartialOrd + Send + Sync + IsFloat,
+    K: IntoIterator<Item = L>,
+    L: IntoIterator<Item = Option<U>>,
+    U: PartialOrd + Send + Sync + IsFloat,
+{
+    let mut vals = a_iters
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, a)| (i as IdxSize, (a, None)))
+        .collect::<Vec<_>>();
+    check_idx_len(vals.len())?;
+    let mut b_len = 0;
+    for b in b_iters.into_iter().flatten() {
+        if let Some(val) = vals.get_mut(b_len) {
+            val.1 .1 = b;
+        }
+        b_len += 1;
+    }
+    polars_ensure!(
+        b_len == vals.len(),
+        ShapeMismatch: "arg_sort_two: `a` has length {} but `b` has length {}", vals.len(), b_len
+    );
+
+    let options = SortOptions {
+        descending: false,
+        ..a_options
+    };
+    sort_index_values_by(vals.as_mut_slice(), options, |(a1, b1), (a2, b2)| {
+        compare_nullable(a1, a2, a_options).then_with(|| compare_nullable(b1, b2, b_options))
+    });
+
+    let idx = vals.into_iter().map(|(idx, _v)| idx).collect::<Vec<_>>();
+    Ok(ChunkedArray::with_chunk(
+        name,
+        IdxArr::from_data_default(Buffer::from(idx), None),
+    ))
+}
+
+/// Compare two optional values in the sort order of `options`. The nulls are placed by
+/// `options.nulls_last`, independent of the direction.
+fn compare_nullable<T: PartialOrd + IsFloat>(
+    a: &Option<T>,
+    b: &Option<T>,
+    options: SortOptions,
+) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if options.descending => compare_fn_nan_max(b, a),
+        (Some(a), Some(b)) => compare_fn_nan_max(a, b),
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) if options.nulls_last => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (Some(_), None) if options.nulls_last => Ordering::Less,
+        (Some(_), None) => Ordering::Greater,
+    }
+}
+
+/// Arg sort of a list column by lexicographic comparison of the lists.
+///
+/// The lists are compared element by element, and a list that is a prefix of another list
+/// is smaller. Both the null lists and the null elements within a list are placed by
+/// `options.nulls_last`. Errors if the inner type is not numeric.
+pub fn arg_sort_list(ca: &ListChunked, options: SortOptions) -> PolarsResult<IdxCa> {
+    let inner = ca.inner_dtype().to_physical();
+    polars_ensure!(
+        inner.is_numeric(),
+        InvalidOperation: "`arg_sort_list` requires a list of numeric values, got {}", ca.dtype()
+    );
+    check_idx_len(ca.len())?;
+    let s = ca.cast(&DataType::List(Box::new(inner.clone())))?;
+    let ca = s.list()?;
+    Ok(with_match_physical_numeric_polars_type!(inner, |$T| {
+        arg_sort_list_numeric::<$T>(ca, options)
+    }))
+}
+
+fn arg_sort_list_numeric<T: PolarsNumericType>(ca: &ListChunked, options: SortOptions) -> IdxCa {
+    let iters = ca.downcast_iter().map(|arr| {
+        let values = arr
+            .values()
+            .as_any()
+            .downcast_ref::<PrimitiveArray<T::Native>>()
+            .unwrap();
+        let offsets = arr.offsets().as_slice();
+        (0..arr.len()).map(move |i| {
+            arr.is_valid(i).then(|| {
+                (offsets[i] as usize..offsets[i + 1] as usize)
+                    .map(|j| values.is_valid(j).then(|| values.value(j)))
+                    .collect::<Vec<_>>()
+            })
+        })
+    });
+    let null_placement = NullPlacement::from_nulls_last(options.nulls_last);
+    let (nulls_last, reverse_nulls) = resolve_null_placement(options.descending, null_placement);
+    let (mut vals, nulls_idx) =
+        partition_nulls_by(iters, nulls_last, None, ca.null_count(), ca.len());
+
+    // The direction is applied by the comparison, as the prefix rule flips with it but the
+    // placement of the null elements doesn't.
+    let ascending = SortOptions {
+        descending: false,
+        ..options
+    };
+    sort_index_values_by(vals.as_mut_slice(), ascending, |a, b| {
+        compare_list(a, b, options)
+    });
+
+    let iter = vals.into_iter().map(|(idx, _v)| idx);
+    let idx = finish_idx(iter, nulls_idx, nulls_last, reverse_nulls, ca.len());
+    ChunkedArray::with_chunk(
+        ca.name(),
+        IdxArr::from_data_default(Buffer::from(idx), None),
+    )
+}
+
+/// Compare two lists lexicographically in the sort order of `options`.
+fn compare_list<T: PartialOrd + IsFloat>(
+    a: &[Option<T>],
+    b: &[Option<T>],
+    options: SortOptions,
+) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| compare_nullable(a, b, options))
+        .find(|ord| ord.is_ne())
+        .unwrap_or_else(|| {
+            let ord = a.len().cmp(&b.len());
+            if options.descending {
+                ord.reverse()
+            } else {
+                ord
+            }
+        })
+}
+
+/// Arg sort that also returns a mask in sorted order that is `true` where the value is equal
+/// to the value at the previous position, e.g. to compute dense ranks in a single pass.
+///
+/// Nulls are equal to each other, as are NaNs.
+pub fn arg_sort_with_ties<I, J, T>(
+    name: &str,
+    iters: I,
+    options: SortOptions,
+    null_placement: NullPlacement,
+    null_count: usize,
+    len: usize,
+) -> (IdxCa, BooleanChunked)
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: PartialOrd + Send + Sync + IsFloat,
+{
+    let (nulls_last, reverse_nulls) = resolve_null_placement(options.descending, null_placement);
+    let (mut vals, nulls_idx) = partition_nulls(iters, nulls_last, false, null_count, len);
+
+    sort_index_values(vals.as_mut_slice(), options, NanPlacement::NanMax);
+
+    let value_ties = (0..vals.len())
+        .map(|p| p > 0 && compare_fn_nan_max(&vals[p - 1].1, &vals[p].1) == Ordering::Equal);
+    let null_ties = (0..nulls_idx.len()).map(|p| p > 0);
+    let ties = if nulls_last {
+        BooleanChunked::from_iter_values(name, value_ties.chain(null_ties))
+    } else {
+        BooleanChunked::from_iter_values(name, null_ties.chain(value_ties))
+    };
+
+    let iter = vals.into_iter().map(|(idx, _v)| idx);
+    let idx = finish_idx(iter, nulls_idx, nulls_last, reverse_nulls, len);
+    let idx = ChunkedArray::with_chunk(name, IdxArr::from_data_default(Buffer::from(idx), None));
+    (idx, ties)
+}
+
+/// Arg sort that also returns the number of inversions of the input: the pairs of valid
+/// values that are out of order in the sort direction. Equal values and nulls don't count.
+///
+/// The count is exact. It is computed from the (stable) sorted indices in `O(n log n)`,
+/// after the sort, so it doesn't slow down the multithreaded sort.
+pub fn arg_sort_counting<I, J, T>(
+    name: &str,
+    iters: I,
+    options: SortOptions,
+    null_count: usize,
+    len: usize,
+) -> (IdxCa, u64)
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: PartialOrd + Send + Sync + IsFloat,
+{
+    let null_placement = NullPlacement::from_nulls_last(options.nulls_last);
+    let (nulls_last, reverse_nulls) = resolve_null_placement(options.descending, null_placement);
+    let (mut vals, nulls_idx) = partition_nulls(iters, nulls_last, false, null_count, len);
+
+    // Ties must keep their order, otherwise they would count as inversions.
+    let stable = SortOptions {
+        maintain_order: true,
+        ..options
+    };
+    sort_index_values(vals.as_mut_slice(), stable, NanPlacement::NanMax);
+    let inversions = count_inversions(vals.iter().map(|(idx, _v)| *idx), len);
+
+    let iter = vals.into_iter().map(|(idx, _v)| idx);
+    let idx = finish_idx(iter, nulls_idx, nulls_last, reverse_nulls, len);
+    let idx = ChunkedArray::with_chunk(name, IdxArr::from_data_default(Buffer::from(idx), None));
+    (idx, inversions)
+}
+
+/// Arg sort of boolean values in `O(n)`, `false` before `true` and the nulls placed by
+/// `options.nulls_last`.
+///
+/// The indices of the falses, the trues and the nulls are collected in one pass instead of
+/// comparing the values. Equal values keep their order of appearance, as in a stable sort.
+pub fn arg_sort_bool<I, J>(
+    name: &str,
+    iters: I,
+    options: SortOptions,
+    null_count: usize,
+    len: usize,
+) -> IdxCa
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<bool>>,
+{
+    assert_idx_len(len);
+    let null_placement = NullPlacement::from_nulls_last(options.nulls_last);
+    let (nulls_last, reverse_nulls) = resolve_null_placement(options.descending, null_placement);
+    // If the nulls go first, the sorted indices are appended to them.
+    let mut nulls_idx = Vec::with_capacity(if nulls_last { null_count } else { len });
+    let mut falses = Vec::with_capacity(len - null_count);
+    let mut trues = vec![];
+    for (i, v) in iters.into_iter().flatten().enumerate() {
+        let i = i as IdxSize;
+        match v {
+            Some(false) => falses.push(i),
+            Some(true) => trues.push(i),
+            None => nulls_idx.push(i),
+        }
+    }
+    let (first, second) = if options.descending {
+        (trues, falses)
+    } else {
+        (falses, trues)
+    };
+    let iter = first.into_iter().chain(second);
+    let idx = finish_idx(iter, nulls_idx, nulls_last, reverse_nulls, len);
+    ChunkedArray::with_chunk(name, IdxArr::from_data_default(Buffer::from(idx), None))
+}
+
+/// Count the pairs in `idx` where a larger index comes before a smaller one.
+/// All indices must be smaller than `len`.
+fn count_inversions<I: Iterator<Item = IdxSize>>(idx: I, len: usize) -> u64 {
+    // Fenwick tree over the indices that are already seen.
+    let mut tree = vec![0u64; len + 1];
+    let mut inversions = 0;
+    for (seen, idx) in idx.enumerate() {
+        let mut smaller_seen = 0;
+        let mut i = idx as usize + 1;
+        while i > 0 {
+            smaller_seen += tree[i];
+            i &= i - 1;
+        }
+        inversions += seen as u64 - smaller_seen;
+
+        let mut i = idx as usize + 1;
+        while i <= len {
+            tree[i] += 1;
+            i += i & i.wrapping_neg();
+        }
+    }
+    inversions
+}
+
+/// A value in the heap of [`arg_sort_top_k`]. Ordered by its position in the sorted output,
+/// so the top of the heap is the value that is dropped first.
+struct TopKItem<T> {
+    idx: IdxSize,
+    value: T,
+    descending: bool,
+}
+
+impl<T: PartialOrd + IsFloat> Ord for TopKItem<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let order = if self.descending {
+            compare_fn_nan_max(&other.value, &self.value)
+        } else {
+            compare_fn_nan_max(&self.value, &other.value)
+        };
+        order.then_with(|| self.idx.cmp(&other.idx))
+    }
+}
+
+impl<T: PartialOrd + IsFloat> PartialOrd for TopKItem<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: PartialOrd + IsFloat> PartialEq for TopKItem<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: PartialOrd + IsFloat> Eq for TopKItem<T> {}
+
+/// Arg sort that only returns the indices of the first `k` elements of the sorted output.
+///
+/// Instead of sorting all values, a heap of at most `k` values is maintained while iterating
+/// the chunks. The output is equal to slicing the first `k` indices of a stable arg sort.
+pub fn arg_sort_top_k<I, J, T>(name: &str, iters: I, k: usize, options: SortOptions) -> IdxCa
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: PartialOrd + IsFloat,
+{
+    let null_placement = NullPlacement::from_nulls_last(options.nulls_last);
+    arg_sort_top_k_with_null_placement(name, iters, k, options, null_placement)
+}
+
+/// [`arg_sort_top_k`] where the placement of the nulls is decided by `null_placement`, see
+/// [`arg_sort_with_null_placement`]. At most `k` null indices are kept.
+pub fn arg_sort_top_k_with_null_placement<I, J, T>(
+    name: &str,
+    iters: I,
+    k: usize,
+    options: SortOptions,
+    null_placement: NullPlacement,
+) -> IdxCa
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: PartialOrd + IsFloat,
 {
     let descending = options.descending;
-    let nulls_last = options.nulls_last;
+    let (nulls_last, reverse_nulls) = resolve_null_placement(descending, null_placement);
+    // `k` may be far larger than the input, e.g. `usize::MAX` for all values.
+    let mut heap = BinaryHeap::new();
+    // In reverse order of appearance the output starts with the last nulls, so those are
+    // the ones that are kept.
+    let mut nulls_idx = VecDeque::new();
+    let mut count: IdxSize = 0;
+
+    for arr_iter in iters {
+        for v in arr_iter {
+            let idx = count;
+            count = count.checked_add(1).expect(LENGTH_LIMIT_MSG);
+            match v {
+                Some(value) => {
+                    heap.push(TopKItem {
+                        idx,
+                        value,
+                        descending,
+                    });
+                    if heap.len() > k {
+                        heap.pop();
+                    }
+                },
+                None if reverse_nulls => {
+                    nulls_idx.push_back(idx);
+                    if nulls_idx.len() > k {
+                        nulls_idx.pop_front();
+                    }
+                },
+                None if nulls_idx.len() < k => nulls_idx.push_back(idx),
+                None => {},
+            }
+        }
+    }
+
+    let nulls_idx: Vec<_> = if reverse_nulls {
+        nulls_idx.into_iter().rev().collect()
+    } else {
+        nulls_idx.into()
+    };
+    let values = heap.into_sorted_vec().into_iter().map(|item| item.idx);
+    let idx: Vec<_> = if nulls_last {
+        values.chain(nulls_idx).take(k).collect()
+    } else {
+        nulls_idx.into_iter().chain(values).take(k).collect()
+    };
+    ChunkedArray::with_chunk(name, IdxArr::from_data_default(Buffer::from(idx), None))
+}
+
+/// Arg sort that only orders the first `limit` positions of the output and only returns
+/// those indices, e.g. the 100 smallest of a large array.
+///
+/// The values are partitioned around the last value in the output first, so only the
+/// values in the output are sorted. The nulls are placed according to `options.nulls_last`
+/// and count towards the `limit`. With `options.maintain_order`, the output is equal to the
+/// first `limit` indices of the stable arg sort. Without a `limit` all indices are returned.
+pub fn arg_sort_limit<I, J, T>(
+    name: &str,
+    iters: I,
+    options: SortOptions,
+    limit: Option<usize>,
+    null_count: usize,
+    len: usize,
+) -> IdxCa
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: PartialOrd + Send + Sync + IsFloat,
+{
+    let null_placement = NullPlacement::from_nulls_last(options.nulls_last);
+    let (nulls_last, reverse_nulls) = resolve_null_placement(options.descending, null_placement);
+    let (mut vals, mut nulls_idx) = partition_nulls(iters, nulls_last, false, null_count, len);
+    if reverse_nulls {
+        nulls_idx.reverse();
+    }
+
+    let limit = limit.map_or(len, |limit| limit.min(len));
+    let n_vals = if nulls_last {
+        limit.min(vals.len())
+    } else {
+        limit.saturating_sub(nulls_idx.len())
+    };
+    if n_vals < vals.len() {
+        // Ties are broken by the index, so the selected values are those of a stable sort.
+        let descending = options.descending;
+        vals.select_nth_unstable_by(n_vals, |a, b| {
+            let order = compare_fn_nan_max(&a.1, &b.1);
+            let order = if descending { order.reverse() } else { order };
+            order.then_with(|| a.0.cmp(&b.0))
+        });
+        vals.truncate(n_vals);
+    }
+    sort_index_values(vals.as_mut_slice(), options, NanPlacement::NanMax);
+
+    let values = vals.into_iter().map(|(idx, _v)| idx);
+    let idx: Vec<_> = if nulls_last {
+        values.chain(nulls_idx).take(limit).collect()
+    } else {
+        nulls_idx.into_iter().chain(values).take(limit).collect()
+    };
+    ChunkedArray::with_chunk(name, IdxArr::from_data_default(Buffer::from(idx), None))
+}
+
+/// Arrays with nulls of at least this many chunks are partitioned in parallel by a
+/// multithreaded arg sort, see [`arg_sort_par_chunks`].
+pub(super) const PARALLEL_SCAN_MIN_CHUNKS: usize = 8;
+
+/// Arg sort of a numeric array with nulls, where the values and the nulls of the chunks are
+/// collected in parallel. Every chunk numbers its rows from the offset of the chunk, so the
+/// output is equal to [`arg_sort`].
+pub(super) fn arg_sort_par_chunks<T>(ca: &ChunkedArray<T>, options: SortOptions) -> IdxCa
+where
+    T: PolarsNumericType,
+{
+    let len = ca.len();
+    assert_idx_len(len);
+    let null_count = ca.null_count();
+    let null_placement = NullPlacement::from_nulls_last(options.nulls_last);
+    let (nulls_last, reverse_nulls) = resolve_null_placement(options.descending, null_placement);
+    if null_count == len {
+        return arg_sort_all_null(ca.name(), len, reverse_nulls);
+    }
+
+    let mut offset = 0;
+    let chunks = ca
+        .downcast_iter()
+        .map(|arr| {
+            let chunk_offset = offset as IdxSize;
+            offset += arr.len();
+            (chunk_offset, arr)
+        })
+        .collect::<Vec<_>>();
+    let partitions = POOL.install(|| {
+        chunks
+            .into_par_iter()
+            .map(|(offset, arr)| {
+                let mut vals = Vec::with_capacity(arr.len() - arr.null_count());
+                let mut nulls_idx = Vec::with_capacity(arr.null_count());
+                for (i, v) in arr.iter().enumerate() {
+                    let i = offset + i as IdxSize;
+                    match v {
+                        Some(v) => vals.push((i, *v)),
+                        None => nulls_idx.push(i),
+                    }
+                }
+                (vals, nulls_idx)
+            })
+            .collect::<Vec<_>>()
+    });
 
     let mut vals = Vec::with_capacity(len - null_count);
+    // If the nulls go first, the sorted indices are appended to them.
+    let mut nulls_idx = Vec::with_capacity(if nulls_last { null_count } else { len });
+    for (chunk_vals, chunk_nulls) in partitions {
+        vals.extend(chunk_vals);
+        nulls_idx.extend(chunk_nulls);
+    }
 
-    let null_cap = if nulls_last { null_count } else { len };
+    sort_index_values(vals.as_mut_slice(), options, NanPlacement::NanMax);
+
+    let iter = vals.into_iter().map(|(idx, _v)| idx);
+    let idx = finish_idx(iter, nulls_idx, nulls_last, reverse_nulls, len);
+    ChunkedArray::with_chunk(
+        ca.name(),
+        IdxArr::from_data_default(Buffer::from(idx), None),
+    )
+}
+
+/// Integer arrays of at least this length are arg sorted with a radix sort.
+pub(super) const RADIX_SORT_THRESHOLD: usize = 1 << 12;
+
+const RADIX: usize = 256;
+
+/// Arg sort of an integer array with a radix sort.
+///
+/// The radix sort is stable, so the output is equal to the (stable) comparison sort,
+/// also for the placement of the nulls. With `options.multithreaded`, the buckets of the
+/// most significant byte are sorted in parallel.
+pub(super) fn arg_sort_radix<T>(ca: &ChunkedArray<T>, options: SortOptions) -> IdxCa
+where
+    T: PolarsNumericType,
+{
+    debug_assert!(T::get_dtype().is_integer());
+    let signed = T::get_dtype().is_signed_integer();
+    let descending = options.descending;
+    let null_placement = NullPlacement::from_nulls_last(options.nulls_last);
+    let (nulls_last, reverse_nulls) = resolve_null_placement(descending, null_placement);
+
+    let len = ca.len();
+    assert_idx_len(len);
+    let mut vals = Vec::with_capacity(len - ca.null_count());
+    // If the nulls go first, the sorted indices are appended to them.
+    let mut nulls_idx = Vec::with_capacity(if nulls_last { ca.null_count() } else { len });
+    for (i, v) in ca.into_iter().enumerate() {
+        match v {
+            Some(v) => {
+                // Map the values onto `u64` keys with the same order.
+                let key = if signed {
+                    (v.to_i64().unwrap() as u64) ^ (1 << 63)
+                } else {
+                    v.to_u64().unwrap()
+                };
+                let key = if descending { !key } else { key };
+                vals.push((key, i as IdxSize));
+            },
+            None => nulls_idx.push(i as IdxSize),
+        }
+    }
+
+    radix_sort(
+        &mut vals,
+        options.multithreaded && len > options.parallel_threshold,
+    );
+
+    let iter = vals.into_iter().map(|(_key, idx)| idx);
+    let idx = finish_idx(iter, nulls_idx, nulls_last, reverse_nulls, len);
+    ChunkedArray::with_chunk(
+        ca.name(),
+        IdxArr::from_data_default(Buffer::from(idx), None),
+    )
+}
+
+/// Stable sort of the `(key, index)` pairs by their key.
+fn radix_sort(vals: &mut [(u64, IdxSize)], parallel: bool) {
+    let mut buf = vec![(0, 0); vals.len()];
+    let counts = byte_counts(vals, 8);
+    // Bytes that are equal for all keys don't have to be sorted on.
+    let Some(top) = (0..8)
+        .rev()
+        .find(|&byte| !is_constant(&counts[byte], vals.len()))
+    else {
+        return;
+    };
+    if !parallel {
+        lsd_radix_sort(vals, &mut buf, &counts[..=top]);
+        return;
+    }
+
+    // Scatter into the buckets of the top byte, then sort the buckets on the lower bytes.
+    scatter(vals, &mut buf, &counts[top], top);
+    vals.copy_from_slice(&buf);
+    let mut buckets = Vec::with_capacity(RADIX);
+    let (mut rest_vals, mut rest_buf) = (vals, buf.as_mut_slice());
+    for &count in counts[top].iter() {
+        let (bucket_vals, tail_vals) = std::mem::take(&mut rest_vals).split_at_mut(count);
+        let (bucket_buf, tail_buf) = std::mem::take(&mut rest_buf).split_at_mut(count);
+        if count > 1 {
+            buckets.push((bucket_vals, bucket_buf));
+        }
+        rest_vals = tail_vals;
+        rest_buf = tail_buf;
+    }
+    POOL.install(|| {
+        buckets.into_par_iter().for_each(|(vals, buf)| {
+            let counts = byte_counts(vals, top);
+            lsd_radix_sort(vals, buf, &counts);
+        })
+    });
+}
+
+/// The histogram of every byte below `n_bytes` of the keys.
+fn byte_counts(vals: &[(u64, IdxSize)], n_bytes: usize) -> Vec<[usize; RADIX]> {
+    let mut counts = vec![[0usize; RADIX]; n_bytes];
+    for (key, _) in vals {
+        for (byte, count) in counts.iter_mut().enumerate() {
+            count[((key >> (byte * 8)) & 0xff) as usize] += 1;
+        }
+    }
+    counts
+}
+
+fn is_constant(count: &[usize; RADIX], len: usize) -> bool {
+    count.iter().any(|&c| c == len)
+}
+
+/// Stably move the pairs from `src` to `dst` in the order of the given byte of their key.
+fn scatter(
+    src: &[(u64, IdxSize)],
+    dst: &mut [(u64, IdxSize)],
+    count: &[usize; RADIX],
+    byte: usize,
+) {
+    let mut offsets = [0usize; RADIX];
+    let mut sum = 0;
+    for (offset, count) in offsets.iter_mut().zip(count) {
+        *offset = sum;
+        sum += count;
+    }
+    for &(key, idx) in src {
+        let digit = ((key >> (byte * 8)) & 0xff) as usize;
+        dst[offsets[digit]] = (key, idx);
+        offsets[digit] += 1;
+    }
+}
+
+/// Stable least significant digit radix sort on the bytes of `counts`. `buf` must have the
+/// length of `vals`.
+fn lsd_radix_sort(
+    vals: &mut [(u64, IdxSize)],
+    buf: &mut [(u64, IdxSize)],
+    counts: &[[usize; RADIX]],
+) {
+    let mut in_vals = true;
+    for (byte, count) in counts.iter().enumerate() {
+        if is_constant(count, vals.len()) {
+            continue;
+        }
+        if in_vals {
+            scatter(vals, buf, count, byte);
+        } else {
+            scatter(buf, vals, count, byte);
+        }
+        in_vals = !in_vals;
+    }
+    if !in_vals {
+        vals.copy_from_slice(buf);
+    }
+}
+
+/// Errors if `len` values can't be indexed with `IdxSize`, e.g. a concatenation of more than
+/// `u32::MAX` rows without the `bigidx` feature. The indices would silently wrap otherwise.
+#[inline]
+pub(super) fn check_idx_len(len: usize) -> PolarsResult<()> {
+    check_len_fits::<IdxSize>(len)
+}
+
+/// [`check_idx_len`] for indices of type `Idx`, so that the check can be tested for a 32-bit
+/// index independent of the `bigidx` feature.
+#[inline]
+pub(super) fn check_len_fits<Idx: TryFrom<usize>>(len: usize) -> PolarsResult<()> {
+    polars_ensure!(Idx::try_from(len).is_ok(), ComputeError: "{}", LENGTH_LIMIT_MSG);
+    Ok(())
+}
+
+/// [`check_idx_len`] for the kernels behind [`ChunkSort::arg_sort`], which can't return an
+/// error. Panics with the error instead of producing corrupt indices.
+#[inline]
+pub(super) fn assert_idx_len(len: usize) {
+    if let Err(err) = check_idx_len(len) {
+        panic!("{err}")
+    }
+}
+
+/// The arg sort of `len` nulls, which doesn't need to look at the values.
+fn arg_sort_all_null(name: &str, len: usize, reverse_nulls: bool) -> IdxCa {
+    assert_idx_len(len);
+    let idx = if reverse_nulls {
+        (0..len as IdxSize).rev().collect::<Vec<_>>()
+    } else {
+        (0..len as IdxSize).collect()
+    };
+    ChunkedArray::with_chunk(name, IdxArr::from_data_default(Buffer::from(idx), None))
+}
+
+/// Returns whether the nulls go last and whether they are in reverse order of appearance.
+fn resolve_null_placement(descending: bool, null_placement: NullPlacement) -> (bool, bool) {
+    let nulls_last = null_placement.nulls_last(descending);
+    let reverse_nulls =
+        descending && matches!(null_placement, NullPlacement::First | NullPlacement::Last);
+    (nulls_last, reverse_nulls)
+}
+
+/// Split the values in the `(index, value)` pairs of the valid values and the indices of
+/// the nulls. If `nan_as_null` is set, the NaN values go with the nulls.
+fn partition_nulls<I, J, T>(
+    iters: I,
+    nulls_last: bool,
+    nan_as_null: bool,
+    null_count: usize,
+    len: usize,
+) -> (Vec<(IdxSize, T)>, Vec<IdxSize>)
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+    T: IsFloat,
+{
+    let is_nan = |v: &T| v.is_nan();
+    let as_null: Option<&dyn Fn(&T) -> bool> = if nan_as_null { Some(&is_nan) } else { None };
+    partition_nulls_by(iters, nulls_last, as_null, null_count, len)
+}
+
+/// [`partition_nulls`] where the valid values for which `as_null` returns `true` go with the
+/// nulls.
+fn partition_nulls_by<I, J, T>(
+    iters: I,
+    nulls_last: bool,
+    as_null: Option<&dyn Fn(&T) -> bool>,
+    null_count: usize,
+    len: usize,
+) -> (Vec<(IdxSize, T)>, Vec<IdxSize>)
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = Option<T>>,
+{
+    assert_idx_len(len);
+    if null_count == 0 && as_null.is_none() {
+        let mut vals = Vec::with_capacity(len);
+        let mut count: IdxSize = 0;
+        for arr_iter in iters {
+            vals.extend(arr_iter.into_iter().map(|v| {
+                let i = count;
+                count += 1;
+                (
+                    i,
+                    v.expect("`null_count` is 0, but the values contain a null"),
+                )
+            }));
+        }
+        // If the nulls go first, the sorted indices are appended to them.
+        let nulls_idx = Vec::with_capacity(if nulls_last { 0 } else { len });
+        return (vals, nulls_idx);
+    }
+    let mut vals = Vec::with_capacity(len.saturating_sub(null_count));
+
+    // If the nulls go first, the sorted indices are appended to them.
+    // If values go with the nulls, we don't know how many there are.
+    let null_cap = if nulls_last && as_null.is_none() {
+        null_count
+    } else {
+        len
+    };
     let mut nulls_idx = Vec::with_capacity(null_cap);
     let mut count: IdxSize = 0;
 
@@ -38,30 +1375,43 @@ where
             let i = count;
             count += 1;
             match v {
-                Some(v) => Some((i, v)),
-                None => {
-                    // SAFETY: we allocated enough.
-                    unsafe { nulls_idx.push_unchecked(i) };
+                Some(v) if !as_null.map_or(false, |as_null| as_null(&v)) => Some((i, v)),
+                _ => {
+                    // `null_count` is only a capacity hint, so don't trust it to be exact.
+                    nulls_idx.push(i);
                     None
                 },
             }
         });
         vals.extend(iter);
     }
+    (vals, nulls_idx)
+}
 
-    arg_sort_branch(
-        vals.as_mut_slice(),
-        descending,
-        ascending_order,
-        descending_order,
-        options.multithreaded,
-    );
-
-    let iter = vals.into_iter().map(|(idx, _v)| idx);
-    let idx = if nulls_last {
+/// Combine the sorted indices of the valid values with the indices of the nulls.
+///
+/// `nulls_idx` is in order of appearance. The nulls keep that order, unless `reverse_nulls`
+/// is set, which is the same for nulls first and nulls last:
+///
+/// - ascending, nulls first or last: the nulls are in order of appearance.
+/// - descending, nulls first or last: the nulls are in reverse order of appearance, so the
+///   output is the reverse of the stable ascending arg sort with the opposite null placement.
+///
+/// [`NullPlacement::AsSmallest`] and [`NullPlacement::AsLargest`] never reverse the nulls.
+fn finish_idx<I>(
+    iter: I,
+    mut nulls_idx: Vec<IdxSize>,
+    nulls_last: bool,
+    reverse_nulls: bool,
+    len: usize,
+) -> Vec<IdxSize>
+where
+    I: Iterator<Item = IdxSize>,
+{
+    if nulls_last {
         let mut idx = Vec::with_capacity(len);
         idx.extend(iter);
-        if descending {
+        if reverse_nulls {
             idx.extend(nulls_idx.into_iter().rev());
         } else {
             idx.extend(nulls_idx);
@@ -69,14 +1419,12 @@ where
         idx
     } else {
         let ptr = nulls_idx.as_ptr() as usize;
-        if descending {
+        if reverse_nulls {
             nulls_idx.reverse();
         }
         nulls_idx.extend(iter);
         // We had a realloc.
         debug_assert_eq!(nulls_idx.as_ptr() as usize, ptr);
         nulls_idx
-    };
-
-    ChunkedArray::with_chunk(name, IdxArr::from_data_default(Buffer::from(idx), None))
+    }
 }
@@ -0,0 +1,96 @@
+use super::*;
+
+/// Identifies the buffers and the sort options an arg sort was computed for.
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct ArgSortKey {
+    // The address and length of the values and of the validity of every chunk.
+    buffers: Vec<(usize, usize, Option<(usize, usize, usize)>)>,
+    options: SortOptions,
+}
+
+impl ArgSortKey {
+    fn new<T: PolarsNumericType>(ca: &ChunkedArray<T>, options: SortOptions) -> Self {
+        let buffers = ca
+            .downcast_iter()
+            .map(|arr| {
+                let validity = arr.validity().map(|validity| {
+                    let (bytes, offset, len) = validity.as_slice();
+                    (bytes.as_ptr() as usize, offset, len)
+                });
+                (arr.values().as_ptr() as usize, arr.len(), validity)
+            })
+            .collect();
+        ArgSortKey { buffers, options }
+    }
+}
+
+/// Memoizes the arg sort of a column, so sorting the same column again with the same options
+/// returns the indices of the previous sort.
+///
+/// The cache is keyed by the addresses of the buffers of the column and the [`SortOptions`],
+/// so a column whose buffers were replaced, e.g. by an arithmetic operation, an append or a
+/// slice, is sorted again. The cache keeps the cached buffers alive, so their addresses can't
+/// be reused by another column. Clones of a column share its buffers and hit the cache. Buffers
+/// that are mutated in place keep their address and are not detected; [`clear`] the cache
+/// after such a mutation.
+///
+/// [`clear`]: ArgSortCache::clear
+#[derive(Clone, Debug, Default)]
+pub struct ArgSortCache {
+    cached: Option<CachedArgSort>,
+}
+
+#[derive(Clone, Debug)]
+struct CachedArgSort {
+    key: ArgSortKey,
+    // Owns the buffers the key points to.
+    _chunks: Vec<ArrayRef>,
+    idx: IdxCa,
+}
+
+impl ArgSortCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The indices that sort `ca`, computed by [`ChunkSort::arg_sort`] on a cache miss.
+    pub fn arg_sort<T>(&mut self, ca: &ChunkedArray<T>, options: SortOptions) -> IdxCa
+    where
+        T: PolarsNumericType,
+        ChunkedArray<T>: ChunkSort<T>,
+    {
+        self.get_or_insert_with(ca, options, || ca.arg_sort(options))
+    }
+
+    /// The cached indices that sort `ca` with `options`, or the result of `arg_sort`, which
+    /// replaces the cached indices.
+    pub fn get_or_insert_with<T, F>(
+        &mut self,
+        ca: &ChunkedArray<T>,
+        options: SortOptions,
+        arg_sort: F,
+    ) -> IdxCa
+    where
+        T: PolarsNumericType,
+        F: FnOnce() -> IdxCa,
+    {
+        let key = ArgSortKey::new(ca, options);
+        match &self.cached {
+            Some(cached) if cached.key == key => cached.idx.clone(),
+            _ => {
+                let idx = arg_sort();
+                self.cached = Some(CachedArgSort {
+                    key,
+                    _chunks: ca.chunks().clone(),
+                    idx: idx.clone(),
+                });
+                idx
+            },
+        }
+    }
+
+    /// Drop the cached indices.
+    pub fn clear(&mut self) {
+        self.cached = None;
+    }
+}
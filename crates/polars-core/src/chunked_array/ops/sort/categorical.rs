@@ -42,6 +42,7 @@ impl CategoricalChunked {
                 |(_, a), (_, b)| order_ascending_null(a, b),
                 |(_, a), (_, b)| order_descending_null(a, b),
                 options.multithreaded,
+                options.parallel_threshold,
             );
             let cats: NoNull<UInt32Chunked> =
                 vals.into_iter().map(|(idx, _v)| idx).collect_trusted();
@@ -73,6 +74,7 @@ impl CategoricalChunked {
             descending,
             multithreaded: true,
             maintain_order: false,
+            ..Default::default()
         })
     }
 
@@ -1,6 +1,7 @@
 mod arg_sort;
 
 pub mod arg_sort_multiple;
+mod cache;
 #[cfg(feature = "dtype-categorical")]
 mod categorical;
 mod slice;
@@ -9,15 +10,24 @@ use std::cmp::Ordering;
 use std::hint::unreachable_unchecked;
 use std::iter::FromIterator;
 
+pub use arg_sort::{
+    arg_sort_bool, arg_sort_by_abs, arg_sort_counting, arg_sort_into, arg_sort_limit,
+    arg_sort_list, arg_sort_nulls_at, arg_sort_slice, arg_sort_to_rank, arg_sort_top_k,
+    arg_sort_top_k_with_null_placement, arg_sort_two, arg_sort_u32, arg_sort_u64,
+    arg_sort_with_comparator, arg_sort_with_nan_placement, arg_sort_with_null_placement,
+    arg_sort_with_ties, arg_sort_with_validity, arg_sort_with_values, reverse_arg_sort,
+    sort_by_indices, AbsOrder, ArgSortOrder, ArgSortScratch, NanMaxOrder,
+};
 pub(crate) use arg_sort_multiple::argsort_multiple_row_fmt;
 use arrow::array::ValueSize;
 use arrow::bitmap::MutableBitmap;
 use arrow::buffer::Buffer;
 use arrow::legacy::prelude::FromData;
 use arrow::legacy::trusted_len::TrustedLenPush;
+pub use cache::ArgSortCache;
 use num_traits::Float;
 use polars_utils::float::IsFloat;
-use polars_utils::ord::compare_fn_nan_max;
+use polars_utils::ord::{compare_fn_nan_max, compare_fn_nan_min};
 use rayon::prelude::*;
 pub use slice::*;
 
@@ -68,7 +78,7 @@ fn sort_branch<T, Fd, Fr>(
     descending_order_fn: Fr,
     parallel: bool,
 ) where
-    T: PartialOrd + Send,
+    T: Send,
     Fd: FnMut(&T, &T) -> Ordering + for<'r, 's> Fn(&'r T, &'s T) -> Ordering + Sync + Send,
     Fr: FnMut(&T, &T) -> Ordering + for<'r, 's> Fn(&'r T, &'s T) -> Ordering + Sync + Send,
 {
@@ -96,6 +106,7 @@ where
         |(_, a), (_, b)| compare_fn_nan_max(a, b),
         |(_, a), (_, b)| compare_fn_nan_max(b, a),
         parallel,
+        0,
     );
 }
 
@@ -105,12 +116,13 @@ pub(crate) fn arg_sort_branch<T, Fd, Fr>(
     ascending_order_fn: Fd,
     descending_order_fn: Fr,
     parallel: bool,
+    parallel_threshold: usize,
 ) where
-    T: PartialOrd + Send,
+    T: Send,
     Fd: FnMut(&T, &T) -> Ordering + for<'r, 's> Fn(&'r T, &'s T) -> Ordering + Sync + Send,
     Fr: FnMut(&T, &T) -> Ordering + for<'r, 's> Fn(&'r T, &'s T) -> Ordering + Sync + Send,
 {
-    if parallel {
+    if parallel && slice.len() > parallel_threshold {
         POOL.install(|| match descending {
             true => slice.par_sort_by(descending_order_fn),
             false => slice.par_sort_by(ascending_order_fn),
@@ -240,35 +252,125 @@ where
     }
 }
 
-fn arg_sort_numeric<T>(ca: &ChunkedArray<T>, options: SortOptions) -> IdxCa
+/// Arg sort a numeric [`ChunkedArray`].
+///
+/// If `ca` has a single chunk without nulls and is flagged as sorted, the indices are
+/// returned without sorting the values.
+pub fn arg_sort_ca<T>(ca: &ChunkedArray<T>, options: SortOptions) -> IdxCa
 where
     T: PolarsNumericType,
 {
-    let descending = options.descending;
-    if ca.null_count() == 0 {
-        let mut vals = Vec::with_capacity(ca.len());
-        let mut count: IdxSize = 0;
-        ca.downcast_iter().for_each(|arr| {
-            let values = arr.values();
-            let iter = values.iter().map(|&v| {
-                let i = count;
-                count += 1;
-                (i, v)
-            });
-            vals.extend_trusted_len(iter);
-        });
+    arg_sort_numeric(ca, options)
+}
 
-        arg_sort_no_nulls(vals.as_mut_slice(), descending, options.multithreaded);
+/// The arg sort of a single chunk [`ChunkedArray`] without nulls that is flagged as sorted.
+fn arg_sort_presorted<T>(ca: &ChunkedArray<T>, options: SortOptions) -> Option<IdxCa>
+where
+    T: PolarsNumericType,
+{
+    if ca.chunks().len() != 1 || ca.null_count() != 0 {
+        return None;
+    }
+    arg_sort::assert_idx_len(ca.len());
+    let len = ca.len() as IdxSize;
+    let idx: Vec<IdxSize> = match (ca.is_sorted_flag(), options.descending) {
+        (IsSorted::Ascending, false) | (IsSorted::Descending, true) => (0..len).collect(),
+        // Reversing would also reverse the order of equal values.
+        (IsSorted::Ascending, true) | (IsSorted::Descending, false) if !options.maintain_order => {
+            (0..len).rev().collect()
+        },
+        _ => return None,
+    };
+    Some(IdxCa::from_vec(ca.name(), idx))
+}
 
-        let out: NoNull<IdxCa> = vals.into_iter().map(|(idx, _v)| idx).collect_trusted();
-        let mut out = out.into_inner();
-        out.rename(ca.name());
-        out
-    } else {
-        let iter = ca
-            .downcast_iter()
-            .map(|arr| arr.iter().map(|opt| opt.copied()));
-        arg_sort::arg_sort(ca.name(), iter, options, ca.null_count(), ca.len())
+/// The kernel [`arg_sort_numeric`] sorts a column with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum NumericArgSort {
+    /// A single chunk without nulls, see [`arg_sort_slice`].
+    Slice,
+    /// Several chunks without nulls.
+    NoNulls,
+    /// A single chunk with nulls, see [`arg_sort_with_validity`].
+    Validity,
+    /// Many chunks with nulls, multithreaded.
+    ParChunks,
+    /// Many integers in several chunks with nulls, single threaded.
+    Radix,
+    /// The remaining columns.
+    Iter,
+}
+
+impl NumericArgSort {
+    fn of<T: PolarsNumericType>(ca: &ChunkedArray<T>, options: SortOptions) -> Self {
+        let dtype = T::get_dtype();
+        let single_chunk = ca.chunks().len() == 1;
+        if ca.null_count() == 0 {
+            if single_chunk {
+                NumericArgSort::Slice
+            } else {
+                NumericArgSort::NoNulls
+            }
+        } else if single_chunk {
+            NumericArgSort::Validity
+        } else if options.multithreaded
+            && ca.len() > options.parallel_threshold
+            && ca.chunks().len() >= arg_sort::PARALLEL_SCAN_MIN_CHUNKS
+        {
+            NumericArgSort::ParChunks
+        } else if (dtype.is_signed_integer() || dtype.is_unsigned_integer())
+            && ca.len() >= arg_sort::RADIX_SORT_THRESHOLD
+        {
+            // The radix sort needs a (key, index) pair and a scratch buffer per value, so
+            // it is only the fallback of the other kernels.
+            NumericArgSort::Radix
+        } else {
+            NumericArgSort::Iter
+        }
+    }
+}
+
+fn arg_sort_numeric<T>(ca: &ChunkedArray<T>, options: SortOptions) -> IdxCa
+where
+    T: PolarsNumericType,
+{
+    if let Some(idx) = arg_sort_presorted(ca, options) {
+        return idx;
+    }
+    match NumericArgSort::of(ca, options) {
+        NumericArgSort::Slice => {
+            let arr = ca.downcast_iter().next().unwrap();
+            arg_sort_slice(ca.name(), arr.values(), options)
+        },
+        NumericArgSort::NoNulls => {
+            arg_sort::assert_idx_len(ca.len());
+            let mut vals = Vec::with_capacity(ca.len());
+            let mut count: IdxSize = 0;
+            ca.downcast_iter().for_each(|arr| {
+                let values = arr.values();
+                let iter = values.iter().map(|&v| {
+                    let i = count;
+                    count += 1;
+                    (i, v)
+                });
+                vals.extend_trusted_len(iter);
+            });
+            arg_sort::arg_sort_no_null_values(ca.name(), vals, options)
+        },
+        NumericArgSort::Validity => {
+            let arr = ca.downcast_iter().next().unwrap();
+            // There are nulls, so there is a validity.
+            let validity = arr.validity().unwrap();
+            arg_sort_with_validity(ca.name(), arr.values().iter().copied(), validity, options)
+        },
+        NumericArgSort::ParChunks => arg_sort::arg_sort_par_chunks(ca, options),
+        NumericArgSort::Radix => arg_sort::arg_sort_radix(ca, options),
+        NumericArgSort::Iter => {
+            let iter = ca
+                .downcast_iter()
+                .map(|arr| arr.iter().map(|opt| opt.copied()));
+            arg_sort::arg_sort(ca.name(), iter, options, ca.null_count(), ca.len())
+        },
     }
 }
 
@@ -414,6 +516,7 @@ impl ChunkSort<Utf8Type> for Utf8Chunked {
             nulls_last: false,
             multithreaded: true,
             maintain_order: false,
+            ..Default::default()
         })
     }
 
@@ -434,6 +537,25 @@ impl ChunkSort<Utf8Type> for Utf8Chunked {
     }
 }
 
+impl Utf8Chunked {
+    /// Arg sort the strings by `compare` instead of their bytes, e.g. case insensitive or in
+    /// natural order. `compare` is the ascending order; the nulls are placed as in
+    /// [`ChunkSort::arg_sort`]. See [`arg_sort_with_comparator`].
+    pub fn arg_sort_with_comparator<F>(&self, options: SortOptions, compare: F) -> IdxCa
+    where
+        F: Fn(&str, &str) -> Ordering + Send + Sync,
+    {
+        arg_sort_with_comparator(
+            self.name(),
+            self.downcast_iter().map(|arr| arr.iter()),
+            options,
+            move |a: &&str, b: &&str| compare(a, b),
+            self.null_count(),
+            self.len(),
+        )
+    }
+}
+
 impl ChunkSort<BinaryType> for BinaryChunked {
     fn sort_with(&self, options: SortOptions) -> ChunkedArray<BinaryType> {
         sort_with_fast_path!(self, options);
@@ -531,6 +653,7 @@ impl ChunkSort<BinaryType> for BinaryChunked {
             nulls_last: false,
             multithreaded: true,
             maintain_order: false,
+            ..Default::default()
         })
     }
 
@@ -623,11 +746,12 @@ impl ChunkSort<BooleanType> for BooleanChunked {
             nulls_last: false,
             multithreaded: true,
             maintain_order: false,
+            ..Default::default()
         })
     }
 
     fn arg_sort(&self, options: SortOptions) -> IdxCa {
-        arg_sort::arg_sort(
+        arg_sort_bool(
             self.name(),
             self.downcast_iter().map(|arr| arr.iter()),
             options,
@@ -706,7 +830,20 @@ pub(crate) fn prepare_arg_sort(
 
 #[cfg(test)]
 mod test {
+    use std::cmp::Ordering;
+
+    use super::arg_sort::{check_idx_len, check_len_fits};
+    use super::{
+        arg_sort_bool, arg_sort_by_abs, arg_sort_ca, arg_sort_counting, arg_sort_into,
+        arg_sort_limit, arg_sort_list, arg_sort_nulls_at, arg_sort_slice, arg_sort_to_rank,
+        arg_sort_top_k, arg_sort_top_k_with_null_placement, arg_sort_two, arg_sort_u32,
+        arg_sort_u64, arg_sort_with_comparator, arg_sort_with_nan_placement,
+        arg_sort_with_null_placement, arg_sort_with_ties, arg_sort_with_validity,
+        arg_sort_with_values, reverse_arg_sort, sort_by_indices, ArgSortCache, ArgSortOrder,
+        ArgSortScratch, NanMaxOrder,
+    };
     use crate::prelude::*;
+    use crate::series::IsSorted;
 
     #[test]
     fn test_arg_sort() {
@@ -742,6 +879,1141 @@ mod test {
         assert_eq!(idx, expected);
     }
 
+    #[test]
+    fn test_arg_sort_null_placement() {
+        let a = Int32Chunked::new("a", &[Some(2), None, Some(1), None, Some(3)]);
+        let arg_sort = |descending, null_placement| {
+            let options = SortOptions {
+                descending,
+                ..Default::default()
+            };
+            let idx = arg_sort_with_null_placement(
+                a.name(),
+                a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+                options,
+                null_placement,
+                a.null_count(),
+                a.len(),
+            );
+            idx.cont_slice().unwrap().to_vec()
+        };
+
+        for (descending, null_placement, expected) in [
+            (false, NullPlacement::First, [1, 3, 2, 0, 4]),
+            (false, NullPlacement::Last, [2, 0, 4, 1, 3]),
+            (false, NullPlacement::AsLargest, [2, 0, 4, 1, 3]),
+            (false, NullPlacement::AsSmallest, [1, 3, 2, 0, 4]),
+            (true, NullPlacement::First, [3, 1, 4, 0, 2]),
+            (true, NullPlacement::Last, [4, 0, 2, 3, 1]),
+            (true, NullPlacement::AsLargest, [1, 3, 4, 0, 2]),
+            (true, NullPlacement::AsSmallest, [4, 0, 2, 1, 3]),
+        ] {
+            assert_eq!(
+                arg_sort(descending, null_placement),
+                expected,
+                "descending: {descending}, null_placement: {null_placement:?}"
+            );
+        }
+
+        // `nulls_last` maps onto `First` and `Last`
+        let idx = a.arg_sort(SortOptions {
+            descending: true,
+            nulls_last: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            idx.cont_slice().unwrap(),
+            arg_sort(true, NullPlacement::Last)
+        );
+    }
+
+    #[test]
+    fn test_arg_sort_maintain_order() {
+        let values = (0..10_000).map(|i| (i * 7) % 13).collect::<Vec<i32>>();
+        let mut with_nulls = values.iter().copied().map(Some).collect::<Vec<_>>();
+        with_nulls[3] = None;
+        let cas = [
+            Int32Chunked::from_vec("a", values.clone()),
+            Int32Chunked::new("a", &with_nulls),
+        ];
+        for ca in cas {
+            for descending in [false, true] {
+                let idx = ca.arg_sort(SortOptions {
+                    descending,
+                    multithreaded: true,
+                    maintain_order: true,
+                    ..Default::default()
+                });
+                let idx = idx.cont_slice().unwrap();
+                for w in idx.windows(2) {
+                    let (a, b) = (ca.get(w[0] as usize), ca.get(w[1] as usize));
+                    if a.is_some() && a == b {
+                        assert!(w[0] < w[1]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_arg_sort_with_values() {
+        let a = Float64Chunked::new("a", &[Some(2.0), None, Some(f64::NAN), Some(-1.0), None]);
+        for descending in [false, true] {
+            for null_placement in [NullPlacement::First, NullPlacement::Last] {
+                let options = SortOptions {
+                    descending,
+                    ..Default::default()
+                };
+                let (idx, values) = arg_sort_with_values(
+                    a.name(),
+                    a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+                    options,
+                    null_placement,
+                    a.null_count(),
+                    a.len(),
+                );
+                let expected = arg_sort_with_null_placement(
+                    a.name(),
+                    a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+                    options,
+                    null_placement,
+                    a.null_count(),
+                    a.len(),
+                );
+                assert_eq!(idx.cont_slice().unwrap(), expected.cont_slice().unwrap());
+                // Compare the bits, as NaN != NaN.
+                let taken = a.take(&idx).unwrap();
+                let bits = |ca: &Float64Chunked| -> Vec<_> {
+                    ca.into_iter().map(|v| v.map(f64::to_bits)).collect()
+                };
+                assert_eq!(bits(&values), bits(&taken));
+                let flag = if descending {
+                    IsSorted::Descending
+                } else {
+                    IsSorted::Ascending
+                };
+                assert_eq!(values.is_sorted_flag(), flag);
+            }
+        }
+    }
+
+    #[test]
+    fn test_arg_sort_counting() {
+        let n = 100;
+        let count = |values: &[Option<i32>], descending| {
+            let options = SortOptions {
+                descending,
+                ..Default::default()
+            };
+            let (idx, inversions) = arg_sort_counting(
+                "a",
+                [values.iter().copied()],
+                options,
+                values.iter().filter(|v| v.is_none()).count(),
+                values.len(),
+            );
+            let expected = Int32Chunked::new("a", values).arg_sort(options);
+            assert_eq!(idx.cont_slice().unwrap(), expected.cont_slice().unwrap());
+            inversions
+        };
+
+        let sorted = (0..n).map(Some).collect::<Vec<_>>();
+        let reversed = (0..n).rev().map(Some).collect::<Vec<_>>();
+        let n = n as u64;
+        assert_eq!(count(&sorted, false), 0);
+        assert_eq!(count(&reversed, false), n * (n - 1) / 2);
+        assert_eq!(count(&reversed, true), 0);
+        assert_eq!(count(&sorted, true), n * (n - 1) / 2);
+
+        // ties and nulls are no inversions
+        assert_eq!(count(&[Some(1), None, Some(1), Some(0), None], false), 2);
+    }
+
+    #[test]
+    fn test_arg_sort_nan_placement() {
+        let a = Float64Chunked::new(
+            "a",
+            &[Some(1.0), Some(f64::NAN), None, Some(-1.0), Some(f64::NAN)],
+        );
+        for (descending, null_placement, nan_placement, expected) in [
+            (
+                false,
+                NullPlacement::First,
+                NanPlacement::NanMax,
+                [2, 3, 0, 1, 4],
+            ),
+            (
+                false,
+                NullPlacement::First,
+                NanPlacement::NanMin,
+                [2, 1, 4, 3, 0],
+            ),
+            (
+                false,
+                NullPlacement::First,
+                NanPlacement::NanAsNull,
+                [1, 2, 4, 3, 0],
+            ),
+            (
+                true,
+                NullPlacement::First,
+                NanPlacement::NanAsNull,
+                [4, 2, 1, 0, 3],
+            ),
+            (
+                true,
+                NullPlacement::Last,
+                NanPlacement::NanMax,
+                [1, 4, 0, 3, 2],
+            ),
+            (
+                true,
+                NullPlacement::Last,
+                NanPlacement::NanMin,
+                [0, 3, 1, 4, 2],
+            ),
+            (
+                false,
+                NullPlacement::Last,
+                NanPlacement::NanAsNull,
+                [3, 0, 1, 2, 4],
+            ),
+        ] {
+            let options = SortOptions {
+                descending,
+                maintain_order: true,
+                ..Default::default()
+            };
+            let idx = arg_sort_with_nan_placement(
+                a.name(),
+                a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+                options,
+                null_placement,
+                nan_placement,
+                a.null_count(),
+                a.len(),
+            );
+            assert_eq!(
+                idx.cont_slice().unwrap(),
+                expected,
+                "descending: {descending}, {null_placement:?}, {nan_placement:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_arg_sort_limit() {
+        let values = (0..50)
+            .map(|i| {
+                if i % 7 == 0 {
+                    None
+                } else {
+                    Some(((i * 13) % 11) as f64)
+                }
+            })
+            .collect::<Vec<Option<f64>>>();
+        // multiple chunks
+        let mut a = Float64Chunked::new("a", &values[..20]);
+        a.append(&Float64Chunked::new("a", &values[20..]));
+
+        for descending in [false, true] {
+            for nulls_last in [false, true] {
+                let options = SortOptions {
+                    descending,
+                    nulls_last,
+                    maintain_order: true,
+                    ..Default::default()
+                };
+                let sorted = a.arg_sort(options);
+                // a `limit` within the nulls, within the values and larger than the length
+                for limit in [Some(0), Some(3), Some(20), Some(50), Some(60), None] {
+                    let idx = arg_sort_limit(
+                        a.name(),
+                        a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+                        options,
+                        limit,
+                        a.null_count(),
+                        a.len(),
+                    );
+                    let expected = sorted.slice(0, limit.unwrap_or(a.len()));
+                    assert_eq!(
+                        idx.cont_slice().unwrap(),
+                        expected.cont_slice().unwrap(),
+                        "descending: {descending}, nulls_last: {nulls_last}, limit: {limit:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_arg_sort_into() {
+        let mut scratch = ArgSortScratch::new();
+        // The buffers are reused for columns of different lengths.
+        for n in [50, 20, 50] {
+            let values = (0..n)
+                .map(|i| {
+                    if i % 7 == 0 {
+                        None
+                    } else {
+                        Some((i * 13) % 11)
+                    }
+                })
+                .collect::<Vec<Option<i32>>>();
+            let mut a = Int32Chunked::new("a", &values[..n / 2]);
+            a.append(&Int32Chunked::new("a", &values[n / 2..]));
+
+            for descending in [false, true] {
+                for nulls_last in [false, true] {
+                    let options = SortOptions {
+                        descending,
+                        nulls_last,
+                        maintain_order: true,
+                        ..Default::default()
+                    };
+                    let idx = arg_sort_into(
+                        a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+                        options,
+                        &mut scratch,
+                    );
+                    assert_eq!(
+                        idx,
+                        a.arg_sort(options).cont_slice().unwrap(),
+                        "n: {n}, descending: {descending}, nulls_last: {nulls_last}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_arg_sort_by_abs() {
+        let a = Float64Chunked::new(
+            "a",
+            &[
+                Some(-3.0),
+                Some(0.0),
+                None,
+                Some(2.0),
+                Some(-0.0),
+                Some(f64::NAN),
+                Some(-2.0),
+                Some(1.5),
+            ],
+        );
+        let abs = a.apply_values(|v| v.abs());
+        for descending in [false, true] {
+            for nulls_last in [false, true] {
+                let options = SortOptions {
+                    descending,
+                    nulls_last,
+                    maintain_order: true,
+                    ..Default::default()
+                };
+                let idx = arg_sort_by_abs(
+                    a.name(),
+                    a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+                    options,
+                    a.null_count(),
+                    a.len(),
+                );
+                assert_eq!(
+                    idx.cont_slice().unwrap(),
+                    abs.arg_sort(options).cont_slice().unwrap(),
+                    "descending: {descending}, nulls_last: {nulls_last}"
+                );
+            }
+        }
+        let idx = arg_sort_by_abs(
+            a.name(),
+            a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+            SortOptions {
+                maintain_order: true,
+                ..Default::default()
+            },
+            a.null_count(),
+            a.len(),
+        );
+        assert_eq!(idx.cont_slice().unwrap(), &[2, 1, 4, 7, 3, 6, 0, 5]);
+    }
+
+    #[test]
+    fn test_arg_sort_top_k() {
+        let values = (0..50)
+            .map(|i| {
+                if i % 7 == 0 {
+                    None
+                } else {
+                    Some((i * 13) % 11)
+                }
+            })
+            .collect::<Vec<Option<i32>>>();
+        // multiple chunks
+        let mut a = Int32Chunked::new("a", &values[..20]);
+        a.append(&Int32Chunked::new("a", &values[20..]));
+
+        for descending in [false, true] {
+            for nulls_last in [false, true] {
+                let options = SortOptions {
+                    descending,
+                    nulls_last,
+                    ..Default::default()
+                };
+                let sorted = a.arg_sort(options);
+                // `k` larger than the number of valid values, up to all values
+                for k in [0, 1, 5, 30, 60, usize::MAX] {
+                    let idx = arg_sort_top_k(
+                        a.name(),
+                        a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+                        k,
+                        options,
+                    );
+                    let expected = sorted.slice(0, k);
+                    assert_eq!(
+                        idx.cont_slice().unwrap(),
+                        expected.cont_slice().unwrap(),
+                        "descending: {descending}, nulls_last: {nulls_last}, k: {k}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_arg_sort_top_k_null_placement() {
+        // More nulls than `k`, so only some of them are in the output.
+        let a = Int32Chunked::new("a", &[None, Some(2), None, Some(1), None, None, Some(3)]);
+        let iters = || a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied()));
+        for descending in [false, true] {
+            let options = SortOptions {
+                descending,
+                ..Default::default()
+            };
+            for null_placement in [
+                NullPlacement::First,
+                NullPlacement::Last,
+                NullPlacement::AsLargest,
+                NullPlacement::AsSmallest,
+            ] {
+                let sorted = arg_sort_with_null_placement(
+                    a.name(),
+                    iters(),
+                    options,
+                    null_placement,
+                    a.null_count(),
+                    a.len(),
+                );
+                for k in [0, 2, 5, usize::MAX] {
+                    let idx = arg_sort_top_k_with_null_placement(
+                        a.name(),
+                        iters(),
+                        k,
+                        options,
+                        null_placement,
+                    );
+                    assert_eq!(
+                        Vec::from(&idx),
+                        Vec::from(&sorted.slice(0, k)),
+                        "descending: {descending}, {null_placement:?}, k: {k}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_arg_sort_presorted() {
+        let mut a = Int32Chunked::new("a", &[1, 2, 2, 3, 5]);
+        a.set_sorted_flag(IsSorted::Ascending);
+
+        let idx = arg_sort_ca(&a, SortOptions::default());
+        assert_eq!(idx.cont_slice().unwrap(), &[0, 1, 2, 3, 4]);
+        let descending = SortOptions {
+            descending: true,
+            ..Default::default()
+        };
+        let idx = arg_sort_ca(&a, descending);
+        assert_eq!(idx.cont_slice().unwrap(), &[4, 3, 2, 1, 0]);
+        // The equal values must keep their order.
+        let stable = SortOptions {
+            descending: true,
+            maintain_order: true,
+            ..Default::default()
+        };
+        let idx = arg_sort_ca(&a, stable);
+        assert_eq!(idx.cont_slice().unwrap(), &[4, 3, 1, 2, 0]);
+
+        // Multiple chunks are sorted as usual.
+        let mut b = Int32Chunked::new("b", &[1, 3]);
+        b.append(&Int32Chunked::new("b", &[2, 4]));
+        b.set_sorted_flag(IsSorted::Ascending);
+        let idx = arg_sort_ca(&b, SortOptions::default());
+        assert_eq!(idx.cont_slice().unwrap(), &[0, 2, 1, 3]);
+    }
+
+    #[test]
+    fn test_arg_sort_with_comparator() {
+        let a = Int32Chunked::new("a", &[Some(-3), None, Some(1), Some(-1), Some(2)]);
+        let by_abs = |a: &i32, b: &i32| a.abs().cmp(&b.abs());
+        let arg_sort = |options| {
+            arg_sort_with_comparator(
+                a.name(),
+                a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+                options,
+                by_abs,
+                a.null_count(),
+                a.len(),
+            )
+        };
+
+        let idx = arg_sort(SortOptions::default());
+        assert_eq!(idx.cont_slice().unwrap(), &[1, 2, 3, 4, 0]);
+        let idx = arg_sort(SortOptions {
+            descending: true,
+            nulls_last: true,
+            maintain_order: true,
+            ..Default::default()
+        });
+        assert_eq!(idx.cont_slice().unwrap(), &[0, 4, 2, 3, 1]);
+
+        // The default order is the same as the regular arg sort.
+        let idx = arg_sort_with_comparator(
+            a.name(),
+            a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+            SortOptions::default(),
+            |a: &i32, b: &i32| a.cmp(b),
+            a.null_count(),
+            a.len(),
+        );
+        assert_eq!(
+            idx.cont_slice().unwrap(),
+            a.arg_sort(SortOptions::default()).cont_slice().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_arg_sort_order() {
+        // A type without `PartialOrd`, where all negative values are sentinels that are
+        // equal to each other and smaller than any other value.
+        #[derive(Copy, Clone)]
+        struct Reading(i32);
+        struct SentinelOrder;
+        impl ArgSortOrder<Reading> for SentinelOrder {
+            fn compare(&self, a: &Reading, b: &Reading) -> Ordering {
+                a.0.max(-1).cmp(&b.0.max(-1))
+            }
+        }
+
+        let values = [Some(3), Some(-5), None, Some(1), Some(-2), Some(3)];
+        let arg_sort = |options| {
+            arg_sort_with_comparator(
+                "a",
+                [values.iter().map(|v| v.map(Reading))],
+                options,
+                SentinelOrder,
+                1,
+                values.len(),
+            )
+        };
+        let idx = arg_sort(SortOptions {
+            maintain_order: true,
+            ..Default::default()
+        });
+        assert_eq!(idx.cont_slice().unwrap(), &[2, 1, 4, 3, 0, 5]);
+        let idx = arg_sort(SortOptions {
+            descending: true,
+            nulls_last: true,
+            maintain_order: true,
+            ..Default::default()
+        });
+        assert_eq!(idx.cont_slice().unwrap(), &[0, 5, 3, 1, 4, 2]);
+
+        // The default strategy is the regular arg sort.
+        let a = Float64Chunked::new("a", &[Some(1.0), Some(f64::NAN), None, Some(-1.0)]);
+        let idx = arg_sort_with_comparator(
+            a.name(),
+            a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+            SortOptions::default(),
+            NanMaxOrder,
+            a.null_count(),
+            a.len(),
+        );
+        assert_eq!(
+            idx.cont_slice().unwrap(),
+            a.arg_sort(SortOptions::default()).cont_slice().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_arg_sort_all_null() {
+        let mut a = Int32Chunked::full_null("a", 3);
+        a.append(&Int32Chunked::full_null("a", 2));
+        for (descending, nulls_last) in [(false, false), (false, true), (true, false), (true, true)]
+        {
+            let options = SortOptions {
+                descending,
+                nulls_last,
+                ..Default::default()
+            };
+            let expected = if descending {
+                [4, 3, 2, 1, 0]
+            } else {
+                [0, 1, 2, 3, 4]
+            };
+            assert_eq!(a.arg_sort(options).cont_slice().unwrap(), &expected);
+            assert_eq!(
+                a.rechunk().arg_sort(options).cont_slice().unwrap(),
+                &expected
+            );
+
+            let idx = arg_sort_with_comparator(
+                a.name(),
+                a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+                options,
+                |_: &i32, _: &i32| -> Ordering { unreachable!("no values to compare") },
+                a.null_count(),
+                a.len(),
+            );
+            assert_eq!(idx.cont_slice().unwrap(), &expected);
+        }
+        // Nulls that are treated as values keep their order.
+        let idx = arg_sort_with_null_placement(
+            a.name(),
+            a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+            SortOptions {
+                descending: true,
+                ..Default::default()
+            },
+            NullPlacement::AsLargest,
+            a.null_count(),
+            a.len(),
+        );
+        assert_eq!(idx.cont_slice().unwrap(), &[0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_reverse_arg_sort() {
+        let mut a = Int32Chunked::new("a", &[Some(3), None, Some(1)]);
+        a.append(&Int32Chunked::new("a", &[None, Some(2), Some(5)]));
+        for nulls_last in [false, true] {
+            let arg_sort = |descending| {
+                a.arg_sort(SortOptions {
+                    descending,
+                    nulls_last,
+                    maintain_order: true,
+                    ..Default::default()
+                })
+            };
+            let (ascending, descending) = (arg_sort(false), arg_sort(true));
+            let null_count = a.null_count();
+            assert_eq!(
+                Vec::from(&reverse_arg_sort(&ascending, null_count, nulls_last).unwrap()),
+                Vec::from(&descending),
+                "nulls_last: {nulls_last}"
+            );
+            assert_eq!(
+                Vec::from(&reverse_arg_sort(&descending, null_count, nulls_last).unwrap()),
+                Vec::from(&ascending),
+                "nulls_last: {nulls_last}"
+            );
+        }
+        assert!(reverse_arg_sort(&IdxCa::from_vec("a", vec![0, 1]), 3, false).is_err());
+
+        // Ties are reversed as well, so the output is a descending arg sort that doesn't
+        // maintain the order of the equal values.
+        let a = Int32Chunked::new("a", &[Some(2), Some(1), None, Some(2), Some(1)]);
+        let options = SortOptions {
+            maintain_order: true,
+            ..Default::default()
+        };
+        let reversed = reverse_arg_sort(&a.arg_sort(options), 1, false).unwrap();
+        assert_eq!(
+            Vec::from(&reversed),
+            &[Some(2), Some(3), Some(0), Some(4), Some(1)]
+        );
+        let descending = a.arg_sort(SortOptions {
+            descending: true,
+            ..options
+        });
+        assert_eq!(
+            Vec::from(&descending),
+            &[Some(2), Some(0), Some(3), Some(1), Some(4)]
+        );
+        assert_eq!(
+            Vec::from(&a.take(&reversed).unwrap()),
+            Vec::from(&a.take(&descending).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_sort_by_indices() {
+        let mut a = Int32Chunked::new("a", &[Some(3), None, Some(1)]);
+        a.append(&Int32Chunked::new("a", &[Some(2), Some(1)]));
+        let b = Utf8Chunked::new("b", &[Some("c"), Some("n"), None, Some("b"), Some("a")]);
+        let idx = a.arg_sort(SortOptions {
+            maintain_order: true,
+            ..Default::default()
+        });
+
+        let sorted = unsafe { sort_by_indices(&a, &idx) };
+        assert_eq!(
+            Vec::from(&sorted),
+            &[None, Some(1), Some(1), Some(2), Some(3)]
+        );
+        assert_eq!(sorted.name(), "a");
+        let sorted = unsafe { sort_by_indices(&b, &idx) };
+        assert_eq!(Vec::from(&sorted), Vec::from(&b.take(&idx).unwrap()));
+        assert_eq!(
+            Vec::from(&sorted),
+            &[Some("n"), None, Some("a"), Some("b"), Some("c")]
+        );
+    }
+
+    #[test]
+    fn test_arg_sort_two() {
+        let a = Int32Chunked::new("a", &[Some(1), None, Some(1), Some(2), Some(1), None]);
+        let mut b = Float64Chunked::new("b", &[Some(3.0), Some(1.0), None]);
+        b.append(&Float64Chunked::new(
+            "b",
+            &[Some(0.0), Some(2.0), Some(0.0)],
+        ));
+        let arg_sort = |a_options, b_options| {
+            let a_iters = a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied()));
+            let b_iters = b.downcast_iter().map(|arr| arr.iter().map(|v| v.copied()));
+            let idx = arg_sort_two("a", a_iters, b_iters, a_options, b_options).unwrap();
+            assert_eq!(idx.name(), "a");
+            idx.cont_slice().unwrap().to_vec()
+        };
+        let options = |descending, nulls_last| SortOptions {
+            descending,
+            nulls_last,
+            maintain_order: true,
+            ..Default::default()
+        };
+
+        // The ties of `a`, including its nulls, are resolved by ascending `b`.
+        assert_eq!(
+            arg_sort(options(false, false), options(false, false)),
+            &[5, 1, 2, 4, 0, 3]
+        );
+        // And by descending `b`, with the nulls of `b` last.
+        assert_eq!(
+            arg_sort(options(false, false), options(true, true)),
+            &[1, 5, 0, 4, 2, 3]
+        );
+        assert_eq!(
+            arg_sort(options(true, true), options(false, false)),
+            &[3, 2, 4, 0, 5, 1]
+        );
+
+        let shorter = b.slice(0, 5);
+        let result = arg_sort_two(
+            "a",
+            a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+            shorter
+                .downcast_iter()
+                .map(|arr| arr.iter().map(|v| v.copied())),
+            options(false, false),
+            options(false, false),
+        );
+        assert!(matches!(result, Err(PolarsError::ShapeMismatch(_))));
+    }
+
+    #[test]
+    fn test_arg_sort_with_ties() {
+        let mut a = Float64Chunked::new("a", &[Some(2.0), None, Some(1.0), Some(f64::NAN)]);
+        a.append(&Float64Chunked::new(
+            "a",
+            &[None, Some(2.0), Some(1.0), Some(f64::NAN), Some(2.0)],
+        ));
+        let iters = || a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied()));
+        let options = SortOptions {
+            maintain_order: true,
+            ..Default::default()
+        };
+        let arg_sort = |options, null_placement| {
+            arg_sort_with_ties("a", iters(), options, null_placement, 2, a.len())
+        };
+
+        let (idx, ties) = arg_sort(options, NullPlacement::First);
+        assert_eq!(idx.cont_slice().unwrap(), &[1, 4, 2, 6, 0, 5, 8, 3, 7]);
+        assert_eq!(
+            Vec::from(&ties),
+            [false, true, false, true, false, true, true, false, true].map(Some)
+        );
+        assert_eq!(ties.name(), "a");
+
+        let (idx, ties) = arg_sort(options, NullPlacement::Last);
+        assert_eq!(idx.cont_slice().unwrap(), &[2, 6, 0, 5, 8, 3, 7, 1, 4]);
+        assert_eq!(
+            Vec::from(&ties),
+            [false, true, false, true, true, false, true, false, true].map(Some)
+        );
+
+        let descending = SortOptions {
+            descending: true,
+            ..options
+        };
+        let (idx, ties) = arg_sort(descending, NullPlacement::Last);
+        assert_eq!(
+            Vec::from(&idx),
+            Vec::from(&a.arg_sort(SortOptions {
+                nulls_last: true,
+                ..descending
+            }))
+        );
+        assert_eq!(
+            Vec::from(&ties),
+            [false, true, false, true, true, false, true, false, true].map(Some)
+        );
+    }
+
+    #[test]
+    fn test_arg_sort_zero_null_count() {
+        let mut a = Float32Chunked::new("a", &[2.0, f32::NAN, -1.0]);
+        a.append(&Float32Chunked::new("a", &[2.0, 0.5]));
+        let values = a.into_no_null_iter().collect::<Vec<_>>();
+        for (descending, nulls_last) in [(false, false), (false, true), (true, false), (true, true)]
+        {
+            let options = SortOptions {
+                descending,
+                nulls_last,
+                maintain_order: true,
+                ..Default::default()
+            };
+            let iters = a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied()));
+            let idx = arg_sort_with_null_placement(
+                "a",
+                iters,
+                options,
+                NullPlacement::from_nulls_last(nulls_last),
+                0,
+                a.len(),
+            );
+            assert_eq!(
+                Vec::from(&idx),
+                Vec::from(&arg_sort_slice("a", &values, options)),
+                "descending: {descending}, nulls_last: {nulls_last}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_arg_sort_slice() {
+        let values = [3.0, f64::NAN, 1.0, 2.0, 1.0, -0.5];
+        let wrapped = || values.iter().map(|&v| Some(v));
+        for descending in [false, true] {
+            let options = SortOptions {
+                descending,
+                maintain_order: true,
+                ..Default::default()
+            };
+            let idx = arg_sort_slice("a", &values, options);
+            let expected = arg_sort_with_null_placement(
+                "a",
+                [wrapped()],
+                options,
+                NullPlacement::First,
+                0,
+                values.len(),
+            );
+            assert_eq!(
+                Vec::from(&idx),
+                Vec::from(&expected),
+                "descending: {descending}"
+            );
+            assert_eq!(idx.name(), "a");
+        }
+
+        let idx = arg_sort_slice("a", &[5u32, 2, 9, 0], SortOptions::default());
+        assert_eq!(idx.cont_slice().unwrap(), &[3, 1, 0, 2]);
+        assert!(arg_sort_slice::<i32>("a", &[], SortOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_numeric_arg_sort_kernel() {
+        use super::NumericArgSort;
+
+        let len = 2 * super::arg_sort::RADIX_SORT_THRESHOLD;
+        let values = (0..len)
+            .map(|i| (i % 5 != 0).then_some(i as i64))
+            .collect::<Vec<_>>();
+        let mut chunked = Int64Chunked::new("a", &values[..len / 16]);
+        for chunk in values[len / 16..].chunks(len / 16) {
+            chunked.append(&Int64Chunked::new("a", chunk));
+        }
+        let options = SortOptions {
+            maintain_order: true,
+            ..Default::default()
+        };
+        let single_threaded = SortOptions {
+            multithreaded: false,
+            ..options
+        };
+        // Many integers with nulls are only radix sorted if the chunks aren't sorted in
+        // parallel.
+        assert_eq!(
+            NumericArgSort::of(&chunked, options),
+            NumericArgSort::ParChunks
+        );
+        assert_eq!(
+            NumericArgSort::of(&chunked, single_threaded),
+            NumericArgSort::Radix
+        );
+        let no_nulls = Int64Chunked::from_vec("a", (0..len as i64).collect());
+        assert_eq!(
+            NumericArgSort::of(&no_nulls, options),
+            NumericArgSort::Slice
+        );
+        assert_eq!(chunked.arg_sort(options), chunked.arg_sort(single_threaded));
+    }
+
+    #[test]
+    fn test_arg_sort_par_chunks() {
+        let chunk = |i: usize| {
+            let values = (0..50)
+                .map(|j| {
+                    let v = (i * 50 + j) * 7919 % 101;
+                    match v % 13 {
+                        0 => None,
+                        1 => Some(f64::NAN),
+                        _ => Some(v as f64),
+                    }
+                })
+                .collect::<Vec<_>>();
+            Float64Chunked::new("a", &values)
+        };
+        let mut a = chunk(0);
+        for i in 1..200 {
+            a.append(&chunk(i));
+        }
+        assert_eq!(a.chunks().len(), 200);
+
+        for descending in [false, true] {
+            for nulls_last in [false, true] {
+                let options = SortOptions {
+                    descending,
+                    nulls_last,
+                    maintain_order: true,
+                    ..Default::default()
+                };
+                let serial = a.arg_sort(SortOptions {
+                    multithreaded: false,
+                    ..options
+                });
+                let parallel = a.arg_sort(SortOptions {
+                    multithreaded: true,
+                    ..options
+                });
+                assert_eq!(
+                    parallel.cont_slice().unwrap(),
+                    serial.cont_slice().unwrap(),
+                    "descending: {descending}, nulls_last: {nulls_last}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_arg_sort_idx_len() {
+        // The largest length that can be indexed with a 32-bit `IdxSize`.
+        assert!(check_len_fits::<u32>(u32::MAX as usize).is_ok());
+        assert!(matches!(
+            check_len_fits::<u32>(u32::MAX as usize + 1),
+            Err(PolarsError::ComputeError(_))
+        ));
+        #[cfg(not(feature = "bigidx"))]
+        assert!(check_idx_len(u32::MAX as usize + 1).is_err());
+        #[cfg(feature = "bigidx")]
+        assert!(check_idx_len(u32::MAX as usize + 1).is_ok());
+    }
+
+    #[test]
+    fn test_arg_sort_u32_u64() -> PolarsResult<()> {
+        let a = Int32Chunked::new("a", &[Some(3), None, Some(1), Some(2)]);
+        let iters = || a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied()));
+        let options = SortOptions::default();
+        let expected = a.arg_sort(options);
+        let expected = expected.cont_slice().unwrap();
+
+        let idx = arg_sort_u32("a", iters(), options, 1, a.len())?;
+        assert_eq!(idx.name(), "a");
+        assert!(idx
+            .cont_slice()?
+            .iter()
+            .map(|&i| i as IdxSize)
+            .eq(expected.iter().copied()));
+        let idx = arg_sort_u64("a", iters(), options, 1, a.len())?;
+        assert!(idx
+            .cont_slice()?
+            .iter()
+            .map(|&i| i as IdxSize)
+            .eq(expected.iter().copied()));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_arg_sort_u32_overflow() {
+        // The length is checked before any value is read.
+        let len = u32::MAX as usize + 1;
+        let iters = std::iter::empty::<std::iter::Empty<Option<i32>>>();
+        let err = arg_sort_u32("a", iters, SortOptions::default(), 0, len).unwrap_err();
+        assert!(matches!(err, PolarsError::ComputeError(_)));
+    }
+
+    #[test]
+    fn test_arg_sort_radix() {
+        // A simple LCG, so the test is deterministic.
+        let mut state = 42u64;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            state
+        };
+        let len = 3 * super::arg_sort::RADIX_SORT_THRESHOLD;
+        let signed = (0..len)
+            .map(|i| match next() % 8 {
+                0 => None,
+                1 => Some(i64::MIN),
+                2 => Some(i64::MAX),
+                3 => Some((next() % 100) as i64 - 50),
+                _ => Some(next() as i64),
+            })
+            .collect::<Vec<_>>();
+        let unsigned = (0..len)
+            .map(|_| match next() % 8 {
+                0 => None,
+                1 => Some(u32::MAX),
+                2 => Some((next() % 100) as u32),
+                _ => Some(next() as u32),
+            })
+            .collect::<Vec<_>>();
+        let a = Int64Chunked::new("a", &signed);
+        let b = UInt32Chunked::new("b", &unsigned);
+
+        for descending in [false, true] {
+            for nulls_last in [false, true] {
+                for multithreaded in [false, true] {
+                    let options = SortOptions {
+                        descending,
+                        nulls_last,
+                        multithreaded,
+                        maintain_order: false,
+                        ..Default::default()
+                    };
+                    let radix = super::arg_sort::arg_sort_radix(&a, options);
+                    let comparison = super::arg_sort::arg_sort(
+                        a.name(),
+                        a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+                        options,
+                        a.null_count(),
+                        a.len(),
+                    );
+                    assert_eq!(
+                        radix.cont_slice().unwrap(),
+                        comparison.cont_slice().unwrap()
+                    );
+
+                    let radix = super::arg_sort::arg_sort_radix(&b, options);
+                    let comparison = super::arg_sort::arg_sort(
+                        b.name(),
+                        b.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+                        options,
+                        b.null_count(),
+                        b.len(),
+                    );
+                    assert_eq!(
+                        radix.cont_slice().unwrap(),
+                        comparison.cont_slice().unwrap()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_arg_sort_with_validity() {
+        let values = (0..100)
+            .map(|i| match i % 10 {
+                0 => None,
+                3 => Some(f64::NAN),
+                _ => Some(((i * 37) % 17) as f64 - 8.0),
+            })
+            .collect::<Vec<_>>();
+        let a = Float64Chunked::new("a", &values);
+        let arr = a.downcast_iter().next().unwrap();
+
+        for descending in [false, true] {
+            for nulls_last in [false, true] {
+                let options = SortOptions {
+                    descending,
+                    nulls_last,
+                    ..Default::default()
+                };
+                let idx = arg_sort_with_validity(
+                    a.name(),
+                    arr.values().iter().copied(),
+                    arr.validity().unwrap(),
+                    options,
+                );
+                let expected = arg_sort_with_null_placement(
+                    a.name(),
+                    a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied())),
+                    options,
+                    NullPlacement::from_nulls_last(nulls_last),
+                    a.null_count(),
+                    a.len(),
+                );
+                assert_eq!(idx.cont_slice().unwrap(), expected.cont_slice().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_arg_sort_to_rank() {
+        let a = Int32Chunked::new(
+            "a",
+            &[Some(3), None, Some(1), Some(3), None, Some(2), Some(1)],
+        );
+        for descending in [false, true] {
+            for null_placement in [
+                NullPlacement::First,
+                NullPlacement::Last,
+                NullPlacement::AsSmallest,
+            ] {
+                let options = SortOptions {
+                    descending,
+                    maintain_order: true,
+                    ..Default::default()
+                };
+                let iters = || a.downcast_iter().map(|arr| arr.iter().map(|v| v.copied()));
+                let idx = arg_sort_with_null_placement(
+                    a.name(),
+                    iters(),
+                    options,
+                    null_placement,
+                    a.null_count(),
+                    a.len(),
+                );
+                let rank = arg_sort_to_rank(
+                    a.name(),
+                    iters(),
+                    options,
+                    null_placement,
+                    a.null_count(),
+                    a.len(),
+                );
+                let rank = rank.cont_slice().unwrap();
+                for (p, i) in idx.into_no_null_iter().enumerate() {
+                    assert_eq!(rank[i as usize] as usize, p);
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_sort() {
         let a = Int32Chunked::new(
@@ -762,6 +2034,7 @@ mod test {
             nulls_last: false,
             multithreaded: true,
             maintain_order: false,
+            ..Default::default()
         });
         assert_eq!(
             Vec::from(&out),
@@ -781,6 +2054,7 @@ mod test {
             nulls_last: true,
             multithreaded: true,
             maintain_order: false,
+            ..Default::default()
         });
         assert_eq!(
             Vec::from(&out),
@@ -862,6 +2136,7 @@ mod test {
             nulls_last: false,
             multithreaded: true,
             maintain_order: false,
+            ..Default::default()
         });
         let expected = &[None, None, Some("a"), Some("b"), Some("c")];
         assert_eq!(Vec::from(&out), expected);
@@ -871,6 +2146,7 @@ mod test {
             nulls_last: false,
             multithreaded: true,
             maintain_order: false,
+            ..Default::default()
         });
 
         let expected = &[None, None, Some("c"), Some("b"), Some("a")];
@@ -881,6 +2157,7 @@ mod test {
             nulls_last: true,
             multithreaded: true,
             maintain_order: false,
+            ..Default::default()
         });
         let expected = &[Some("a"), Some("b"), Some("c"), None, None];
         assert_eq!(Vec::from(&out), expected);
@@ -890,6 +2167,7 @@ mod test {
             nulls_last: true,
             multithreaded: true,
             maintain_order: false,
+            ..Default::default()
         });
         let expected = &[Some("c"), Some("b"), Some("a"), None, None];
         assert_eq!(Vec::from(&out), expected);
@@ -904,4 +2182,329 @@ mod test {
         let expected = &[Some("c"), Some("b"), Some("a")];
         assert_eq!(Vec::from(&out), expected);
     }
+
+    #[test]
+    fn test_arg_sort_parallel_threshold() {
+        let values = (0..10_000)
+            .map(|i| (i % 7 != 0).then_some(i * 7919 % 1000))
+            .collect::<Vec<_>>();
+        let mut ca = Int32Chunked::new("a", &values[..5_000]);
+        ca.append(&Int32Chunked::new("a", &values[5_000..]));
+        for descending in [false, true] {
+            let options = |parallel_threshold| SortOptions {
+                descending,
+                maintain_order: true,
+                parallel_threshold,
+                ..Default::default()
+            };
+            let expected = Vec::from(&ca.arg_sort(options(usize::MAX)));
+            assert_eq!(Vec::from(&ca.arg_sort(options(0))), expected);
+            assert_eq!(Vec::from(&ca.arg_sort(options(5_000))), expected);
+        }
+
+        // Also for the unstable sort of values without nulls, which are distinct here.
+        let values = (0..10_000).map(|i| i * 7919 % 10_007).collect::<Vec<i32>>();
+        let options = |parallel_threshold| SortOptions {
+            parallel_threshold,
+            ..Default::default()
+        };
+        let expected = arg_sort_slice("a", &values, options(usize::MAX));
+        assert_eq!(arg_sort_slice("a", &values, options(0)), expected);
+    }
+
+    #[test]
+    fn test_arg_sort_list() -> PolarsResult<()> {
+        let mut builder = ListPrimitiveChunkedBuilder::<Int64Type>::new("a", 6, 8, DataType::Int64);
+        builder.append_slice(&[1, 3]);
+        builder.append_slice(&[1]);
+        builder.append_null();
+        builder.append_slice(&[1, 2]);
+        builder.append_slice(&[]);
+        builder.append_iter([Some(1), None].into_iter());
+        let ca = builder.finish();
+
+        let arg_sort = |descending, nulls_last| -> PolarsResult<Vec<IdxSize>> {
+            let options = SortOptions {
+                descending,
+                nulls_last,
+                ..Default::default()
+            };
+            Ok(arg_sort_list(&ca, options)?.into_no_null_iter().collect())
+        };
+        assert_eq!(arg_sort(false, false)?, &[2, 4, 1, 5, 3, 0]);
+        assert_eq!(arg_sort(false, true)?, &[4, 1, 3, 0, 5, 2]);
+        assert_eq!(arg_sort(true, true)?, &[0, 3, 5, 1, 4, 2]);
+
+        let ca = ListChunked::full_null_with_dtype("a", 2, &DataType::Utf8);
+        assert!(arg_sort_list(&ca, SortOptions::default()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_arg_sort_null_order() {
+        let values = [None, Some(3), None, Some(1), None, Some(2)];
+        // (descending, nulls_last) => indices
+        let cases: [(bool, bool, [IdxSize; 6]); 4] = [
+            (false, false, [0, 2, 4, 3, 5, 1]),
+            (false, true, [3, 5, 1, 0, 2, 4]),
+            (true, false, [4, 2, 0, 1, 5, 3]),
+            (true, true, [1, 5, 3, 4, 2, 0]),
+        ];
+        let single = Int32Chunked::new("a", &values);
+        let mut chunked = Int32Chunked::new("a", &values[..1]);
+        for i in 1..values.len() {
+            chunked.append(&Int32Chunked::new("a", &values[i..i + 1]));
+        }
+        let utf8 = single.cast(&DataType::Utf8).unwrap();
+
+        for (descending, nulls_last, expected) in cases {
+            let options = SortOptions {
+                descending,
+                nulls_last,
+                ..Default::default()
+            };
+            let msg = format!("descending: {descending}, nulls_last: {nulls_last}");
+            for ca in [&single, &chunked] {
+                assert_eq!(
+                    Vec::from(&ca.arg_sort(options)),
+                    expected.map(Some),
+                    "{msg}"
+                );
+            }
+            let par = super::arg_sort::arg_sort_par_chunks(&chunked, options);
+            assert_eq!(Vec::from(&par), expected.map(Some), "{msg}");
+            let radix = super::arg_sort::arg_sort_radix(&single, options);
+            assert_eq!(Vec::from(&radix), expected.map(Some), "{msg}");
+            let idx = utf8.utf8().unwrap().arg_sort(options);
+            assert_eq!(Vec::from(&idx), expected.map(Some), "{msg}");
+
+            // All null: the nulls are reversed exactly when descending.
+            let all_null = Int32Chunked::full_null("a", 3);
+            let expected = if descending { [2, 1, 0] } else { [0, 1, 2] };
+            assert_eq!(
+                Vec::from(&all_null.arg_sort(options)),
+                expected.map(Some),
+                "{msg}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_arg_sort_utf8_with_comparator() {
+        let ca = Utf8Chunked::new("a", &[Some("Banana"), None, Some("apple"), Some("Cherry")]);
+        let case_insensitive = |a: &str, b: &str| {
+            a.chars()
+                .flat_map(char::to_lowercase)
+                .cmp(b.chars().flat_map(char::to_lowercase))
+        };
+        let arg_sort = |options| -> Vec<IdxSize> {
+            ca.arg_sort_with_comparator(options, case_insensitive)
+                .into_no_null_iter()
+                .collect()
+        };
+        assert_eq!(arg_sort(SortOptions::default()), &[1, 2, 0, 3]);
+        let options = SortOptions {
+            descending: true,
+            nulls_last: true,
+            ..Default::default()
+        };
+        assert_eq!(arg_sort(options), &[3, 0, 2, 1]);
+        // The bytes put the upper case letters first.
+        let idx = ca.arg_sort(SortOptions::default());
+        assert_eq!(Vec::from(&idx), &[Some(1), Some(0), Some(3), Some(2)]);
+    }
+
+    #[test]
+    fn test_arg_sort_cache() {
+        use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+        let compared = AtomicUsize::new(0);
+        let counting_arg_sort = |ca: &Int32Chunked, options: SortOptions| {
+            arg_sort_with_comparator(
+                ca.name(),
+                ca.downcast_iter().map(|arr| arr.iter()),
+                options,
+                |a: &&i32, b: &&i32| {
+                    compared.fetch_add(1, AtomicOrdering::Relaxed);
+                    a.cmp(b)
+                },
+                ca.null_count(),
+                ca.len(),
+            )
+        };
+        let ca = Int32Chunked::new("a", &[Some(3), None, Some(1), Some(2)]);
+        let options = SortOptions::default();
+        let mut cache = ArgSortCache::new();
+
+        let idx = cache.get_or_insert_with(&ca, options, || counting_arg_sort(&ca, options));
+        assert_eq!(Vec::from(&idx), &[Some(1), Some(2), Some(3), Some(0)]);
+        let sorted = compared.load(AtomicOrdering::Relaxed);
+        assert!(sorted > 0);
+
+        // A repeated sort of the column, or of a clone sharing its buffers, is not sorted again.
+        let cloned = ca.clone();
+        for ca in [&ca, &cloned] {
+            let again = cache.get_or_insert_with(ca, options, || counting_arg_sort(ca, options));
+            assert_eq!(again, idx);
+        }
+        assert_eq!(compared.load(AtomicOrdering::Relaxed), sorted);
+
+        // Other options or other buffers are sorted again.
+        let descending = SortOptions {
+            descending: true,
+            ..Default::default()
+        };
+        let idx = cache.get_or_insert_with(&ca, descending, || counting_arg_sort(&ca, descending));
+        assert_eq!(Vec::from(&idx), &[Some(0), Some(3), Some(2), Some(1)]);
+        let sorted_descending = compared.load(AtomicOrdering::Relaxed);
+        assert!(sorted_descending > sorted);
+        let changed = &ca * 2;
+        cache.get_or_insert_with(&changed, descending, || {
+            counting_arg_sort(&changed, descending)
+        });
+        assert!(compared.load(AtomicOrdering::Relaxed) > sorted_descending);
+
+        assert_eq!(cache.arg_sort(&changed, descending), idx);
+        cache.clear();
+        assert_eq!(cache.arg_sort(&ca, options), ca.arg_sort(options));
+    }
+
+    #[test]
+    fn test_arg_sort_cache_dropped_column() {
+        let mut cache = ArgSortCache::new();
+        let options = SortOptions::default();
+        let ca = Int32Chunked::from_vec("a", vec![3, 1, 2]);
+        cache.arg_sort(&ca, options);
+        drop(ca);
+        // A new column of the same length could be allocated at the address of the dropped
+        // one, if the cache didn't keep that alive.
+        for _ in 0..16 {
+            let ca = Int32Chunked::from_vec("a", vec![1, 2, 3]);
+            assert_eq!(
+                Vec::from(&cache.arg_sort(&ca, options)),
+                &[Some(0), Some(1), Some(2)]
+            );
+            cache.arg_sort(&Int32Chunked::from_vec("a", vec![3, 1, 2]), options);
+        }
+    }
+
+    #[test]
+    fn test_arg_sort_bool() {
+        let mut ca = BooleanChunked::new("a", &[Some(true), None, Some(false), Some(true)]);
+        ca.append(&BooleanChunked::new(
+            "a",
+            &[None, Some(false), Some(true), Some(false)],
+        ));
+        for descending in [false, true] {
+            for nulls_last in [false, true] {
+                let options = SortOptions {
+                    descending,
+                    nulls_last,
+                    maintain_order: true,
+                    ..Default::default()
+                };
+                let iters = || ca.downcast_iter().map(|arr| arr.iter());
+                let out = arg_sort_bool("a", iters(), options, ca.null_count(), ca.len());
+                let expected = arg_sort_with_null_placement(
+                    "a",
+                    iters(),
+                    options,
+                    NullPlacement::from_nulls_last(nulls_last),
+                    ca.null_count(),
+                    ca.len(),
+                );
+                assert_eq!(
+                    out, expected,
+                    "descending: {descending}, nulls_last: {nulls_last}"
+                );
+                assert_eq!(ca.arg_sort(options), out);
+            }
+        }
+        let out = ca.arg_sort(SortOptions::default());
+        assert_eq!(Vec::from(&out), [1, 4, 2, 5, 7, 0, 3, 6].map(Some));
+    }
+
+    #[test]
+    fn test_arg_sort_nulls_at() {
+        let ca = Float64Chunked::new(
+            "a",
+            &[Some(2.0), None, Some(-1.0), Some(0.0), None, Some(-3.0)],
+        );
+        let iters = || ca.downcast_iter().map(|arr| arr.iter());
+        let arg_sort = |options, null_value| {
+            let idx =
+                arg_sort_nulls_at("a", iters(), options, null_value, ca.null_count(), ca.len());
+            Vec::from(&idx)
+        };
+        let stable = SortOptions {
+            maintain_order: true,
+            ..Default::default()
+        };
+        // The nulls sort as zeros, between the negative and the positive values.
+        assert_eq!(arg_sort(stable, Some(0.0)), [5, 2, 1, 3, 4, 0].map(Some));
+        let descending = SortOptions {
+            descending: true,
+            ..stable
+        };
+        assert_eq!(
+            arg_sort(descending, Some(0.0)),
+            [0, 1, 3, 4, 2, 5].map(Some)
+        );
+        // Without a null value, the nulls are placed by the options.
+        assert_eq!(arg_sort(stable, None), Vec::from(&ca.arg_sort(stable)));
+    }
+
+    fn distinct_values() -> impl proptest::strategy::Strategy<Value = (Vec<Option<i32>>, usize)> {
+        use proptest::prelude::*;
+        (
+            prop::collection::vec(prop::option::of(any::<i16>()), 0..64),
+            any::<usize>(),
+        )
+            .prop_map(|(values, split)| {
+                // Make the valid values distinct, as equal values keep their order.
+                let values = values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, v)| v.map(|v| v as i32 * 64 + i as i32))
+                    .collect::<Vec<_>>();
+                let split = split % (values.len() + 1);
+                (values, split)
+            })
+    }
+
+    proptest::proptest! {
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn test_arg_sort_descending_is_reversed_ascending((values, split) in distinct_values()) {
+            let mut ints = Int32Chunked::new("a", &values[..split]);
+            ints.append(&Int32Chunked::new("a", &values[split..]));
+            let floats = ints.cast(&DataType::Float64).unwrap();
+            let strings = ints.cast(&DataType::Utf8).unwrap();
+            for s in [ints.into_series(), floats, strings] {
+                for (nulls_last, maintain_order, multithreaded) in
+                    (0..8).map(|i| (i & 1 == 1, i & 2 == 2, i & 4 == 4))
+                {
+                    let options = SortOptions {
+                        nulls_last,
+                        maintain_order,
+                        multithreaded,
+                        // Also cover the parallel sort.
+                        parallel_threshold: 16,
+                        ..Default::default()
+                    };
+                    let descending = s.arg_sort(SortOptions {
+                        descending: true,
+                        ..options
+                    });
+                    let ascending = s.arg_sort(SortOptions {
+                        nulls_last: !nulls_last,
+                        ..options
+                    });
+                    let mut reversed = Vec::from(&ascending);
+                    reversed.reverse();
+                    proptest::prop_assert_eq!(Vec::from(&descending), reversed, "{:?}", options);
+                }
+            }
+        }
+    }
 }
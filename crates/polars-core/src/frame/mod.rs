@@ -1847,6 +1847,7 @@ impl DataFrame {
                     nulls_last,
                     multithreaded: parallel,
                     maintain_order,
+                    ..Default::default()
                 };
                 // fast path for a frame with a single series
                 // no need to compute the sort indices and then take by these indices
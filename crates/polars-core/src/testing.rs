@@ -2,6 +2,7 @@
 use std::ops::Deref;
 
 use crate::prelude::*;
+use crate::series::IsSorted;
 
 impl Series {
     /// Check if series are equal. Note that `None == None` evaluates to `false`
@@ -151,6 +152,17 @@ impl DataFrame {
             .zip(other.columns.iter())
             .all(|(a, b)| a.get_data_ptr() == b.get_data_ptr())
     }
+
+    /// Flag the column `name` as sorted in the direction of `sorted`, e.g. for the input of
+    /// an operation that requires a sorted column. The values are not checked.
+    pub fn with_sorted_flag(mut self, name: &str, sorted: IsSorted) -> PolarsResult<Self> {
+        self.apply(name, |s| {
+            let mut s = s.clone();
+            s.set_sorted_flag(sorted);
+            s
+        })?;
+        Ok(self)
+    }
 }
 
 impl PartialEq for DataFrame {
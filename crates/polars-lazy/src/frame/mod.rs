@@ -1518,6 +1518,76 @@ impl LazyFrame {
         Self::from_logical_plan(lp, opt_state)
     }
 
+    /// Upsample the DataFrame at a regular frequency per group.
+    ///
+    /// See [`PolarsUpsample::upsample_with_options`](polars_time::PolarsUpsample::upsample_with_options)
+    /// for the meaning of the arguments. Predicates on the `by` columns are pushed down through
+    /// the upsample and value columns that are not used are not read.
+    #[cfg(feature = "upsample")]
+    pub fn upsample<I: IntoVec<String>>(
+        self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        options: UpsampleOptions,
+    ) -> LazyFrame {
+        let args = UpsampleArgs {
+            by: by.into_vec(),
+            time_column: time_column.to_string(),
+            every,
+            offset,
+            options,
+        };
+        let opt_state = self.get_opt_state();
+        let lp = self.get_plan_builder().upsample(Arc::new(args)).build();
+        Self::from_logical_plan(lp, opt_state)
+    }
+
+    /// Upsample the DataFrame at a regular frequency per group, where the groups are given by
+    /// expressions, e.g. `col("time").dt().date()`, instead of column names.
+    ///
+    /// If `include_keys` is set, the keys are added to the output, named after the output
+    /// name of their expression. Alias a key whose name is already taken, e.g. by the
+    /// `time_column` it is derived from.
+    #[cfg(feature = "upsample")]
+    pub fn upsample_by_exprs<E: AsRef<[Expr]>>(
+        self,
+        by: E,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        options: UpsampleOptions,
+        include_keys: bool,
+    ) -> LazyFrame {
+        let by = by.as_ref();
+        let key_names = (0..by.len())
+            .map(|i| format!("__POLARS_UPSAMPLE_KEY_{i}"))
+            .collect::<Vec<_>>();
+        let keys = by
+            .iter()
+            .zip(&key_names)
+            .map(|(expr, name)| expr.clone().alias(name))
+            .collect::<Vec<_>>();
+        let lf = self.with_columns(keys).upsample(
+            key_names.clone(),
+            time_column,
+            every,
+            offset,
+            options,
+        );
+        if include_keys {
+            let (existing, new): (Vec<_>, Vec<_>) = by
+                .iter()
+                .zip(key_names)
+                .filter_map(|(expr, name)| Some((name, expr_output_name(expr).ok()?.to_string())))
+                .unzip();
+            lf.rename(existing, new)
+        } else {
+            lf.drop_columns(key_names)
+        }
+    }
+
     /// Limit the DataFrame to the first `n` rows.
     ///
     /// Note if you don't want the rows to be scanned, use [`fetch`](LazyFrame::fetch).
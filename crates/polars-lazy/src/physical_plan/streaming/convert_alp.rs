@@ -182,6 +182,16 @@ pub(crate) fn insert_streaming_nodes(
                 state.streamable = true;
                 stack.push((*input, state, current_idx))
             },
+            // The upsample needs all rows of a group, it collects its input in a sink
+            #[cfg(feature = "upsample")]
+            MapFunction {
+                input,
+                function: FunctionNode::Upsample { .. },
+            } => {
+                state.streamable = true;
+                state.operators_sinks.push(PipelineNode::Sink(root));
+                stack.push((*input, state, current_idx))
+            },
             // Streamable functions will be converted
             lp @ MapFunction { input, function } => {
                 if function.is_streamable() {
@@ -14,9 +14,15 @@ pub use polars_plan::prelude::JsonWriterOptions;
 pub use polars_plan::prelude::ParquetWriteOptions;
 pub(crate) use polars_plan::prelude::*;
 #[cfg(feature = "rolling_window")]
-pub use polars_time::{prelude::RollingOptions, Duration};
+pub use polars_time::prelude::RollingOptions;
+#[cfg(any(feature = "rolling_window", feature = "upsample"))]
+pub use polars_time::Duration;
 #[cfg(feature = "dynamic_group_by")]
 pub use polars_time::{DynamicGroupOptions, PolarsTemporalGroupby, RollingGroupOptions};
+#[cfg(feature = "upsample")]
+pub use polars_time::{
+    UpsampleFillOptions, UpsampleGridOptions, UpsampleJoinOptions, UpsampleOptions,
+};
 pub(crate) use polars_utils::arena::{Arena, Node};
 
 pub use crate::dsl::*;
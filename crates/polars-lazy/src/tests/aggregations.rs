@@ -464,6 +464,7 @@ fn take_aggregations() -> PolarsResult<()> {
                             nulls_last: false,
                             multithreaded: true,
                             maintain_order: false,
+                            ..Default::default()
                         })
                         .head(Some(2)),
                 )
@@ -503,6 +504,7 @@ fn test_take_consistency() -> PolarsResult<()> {
                 nulls_last: false,
                 multithreaded: true,
                 maintain_order: false,
+                ..Default::default()
             })
             .get(lit(0))])
         .collect()?;
@@ -521,6 +523,7 @@ fn test_take_consistency() -> PolarsResult<()> {
                 nulls_last: false,
                 multithreaded: true,
                 maintain_order: false,
+                ..Default::default()
             })
             .get(lit(0))])
         .collect()?;
@@ -540,6 +543,7 @@ fn test_take_consistency() -> PolarsResult<()> {
                     nulls_last: false,
                     multithreaded: true,
                     maintain_order: false,
+                    ..Default::default()
                 })
                 .get(lit(0))
                 .alias("1"),
@@ -551,6 +555,7 @@ fn test_take_consistency() -> PolarsResult<()> {
                             nulls_last: false,
                             multithreaded: true,
                             maintain_order: false,
+                            ..Default::default()
                         })
                         .get(lit(0)),
                 )
@@ -552,3 +552,132 @@ fn test_flatten_unions() -> PolarsResult<()> {
     }
     Ok(())
 }
+
+#[test]
+#[cfg(feature = "upsample")]
+fn test_upsample_pushdown() -> PolarsResult<()> {
+    use polars_time::PolarsUpsample;
+
+    let df = df![
+        "time" => [0i64, 2, 4, 1, 3],
+        "groups" => ["a", "a", "a", "b", "b"],
+        "a" => [1, 2, 3, 4, 5],
+        "b" => ["x", "y", "z", "u", "v"],
+    ]?
+    .with_sorted_flag("time", IsSorted::Ascending)?;
+    let every = Duration::parse("1i");
+    let offset = Duration::parse("0");
+    let q = df
+        .clone()
+        .lazy()
+        .upsample(["groups"], "time", every, offset, Default::default())
+        .filter(col("groups").eq(lit("a")))
+        .select([col("time"), col("a")]);
+
+    // the unused value column is not read and the predicate on `by` is done at the scan
+    let (mut expr_arena, mut lp_arena) = get_arenas();
+    let lp = q.clone().optimize(&mut lp_arena, &mut expr_arena)?;
+    assert!((&lp_arena).iter(lp).any(|(_, lp)| {
+        use ALogicalPlan::*;
+        match lp {
+            DataFrameScan {
+                projection: Some(projection),
+                selection,
+                ..
+            } => {
+                assert!(selection.is_some());
+                let mut projection = projection.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+                projection.sort_unstable();
+                assert_eq!(projection, &["a", "groups", "time"]);
+                true
+            },
+            _ => false,
+        }
+    }));
+
+    let out = q.collect()?;
+    let expected = df
+        .upsample(["groups"], "time", every, offset)?
+        .lazy()
+        .filter(col("groups").eq(lit("a")))
+        .select([col("time"), col("a")])
+        .collect()?;
+    assert!(out.equals_missing(&expected));
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "upsample")]
+fn test_upsample_shared_grid_predicate() -> PolarsResult<()> {
+    use polars_time::PolarsUpsample;
+
+    let df = df![
+        "time" => [0i64, 2, 4, 1, 3],
+        "groups" => ["a", "a", "a", "b", "b"],
+        "a" => [1, 2, 3, 4, 5],
+    ]?
+    .with_sorted_flag("time", IsSorted::Ascending)?;
+    let every = Duration::parse("1i");
+    let offset = Duration::parse("0");
+    let options = UpsampleOptions {
+        grid: UpsampleGridOptions {
+            shared: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    // The grid of group "b" spans the times of group "a", so the predicate on `by` must
+    // not be done before the upsample.
+    let out = df
+        .clone()
+        .lazy()
+        .upsample(["groups"], "time", every, offset, options.clone())
+        .filter(col("groups").eq(lit("b")))
+        .collect()?;
+    let expected = df
+        .upsample_with_options(["groups"], "time", every, offset, &options)?
+        .lazy()
+        .filter(col("groups").eq(lit("b")))
+        .collect()?;
+    assert_eq!(out.height(), 5);
+    assert!(out.equals_missing(&expected));
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "upsample")]
+fn test_upsample_drop_empty_projection() -> PolarsResult<()> {
+    use polars_time::{PolarsUpsample, UpsampleFill};
+
+    let df = df![
+        "time" => [0i64, 3],
+        "a" => [1, 2],
+        "b" => [3, 4],
+    ]?
+    .with_sorted_flag("time", IsSorted::Ascending)?;
+    let every = Duration::parse("1i");
+    let offset = Duration::parse("0");
+    let options = UpsampleOptions {
+        fill: UpsampleFillOptions {
+            columns: [("b".to_string(), UpsampleFill::Forward)]
+                .into_iter()
+                .collect(),
+            drop_empty: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    // The inserted rows are kept for their filled `b`, even if only `a` is selected.
+    let out = df
+        .clone()
+        .lazy()
+        .upsample::<[String; 0]>([], "time", every, offset, options.clone())
+        .select([col("time"), col("a")])
+        .collect()?;
+    let expected = df
+        .upsample_with_options::<[String; 0]>([], "time", every, offset, &options)?
+        .select(["time", "a"])?;
+    assert_eq!(out.height(), 4);
+    assert!(out.equals_missing(&expected));
+    Ok(())
+}
@@ -906,6 +906,7 @@ fn test_lazy_group_by_filter() -> PolarsResult<()> {
                 nulls_last: false,
                 multithreaded: true,
                 maintain_order: false,
+                ..Default::default()
             },
         )
         .collect()?;
@@ -1632,6 +1633,7 @@ fn test_single_group_result() -> PolarsResult<()> {
                 nulls_last: false,
                 multithreaded: true,
                 maintain_order: false,
+                ..Default::default()
             })
             .over([col("a")])])
         .collect()?;
@@ -1908,3 +1910,51 @@ fn test_sort_maintain_order_true() -> PolarsResult<()> {
     ]?));
     Ok(())
 }
+
+#[test]
+#[cfg(all(feature = "upsample", feature = "temporal"))]
+fn test_upsample_by_exprs() -> PolarsResult<()> {
+    let at = |day, hour| {
+        NaiveDate::from_ymd_opt(2021, 12, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    };
+    let df = df![
+        "time" => [at(16, 10), at(16, 12), at(17, 9), at(17, 10)],
+        "values" => [1, 2, 3, 4],
+    ]?
+    .with_sorted_flag("time", IsSorted::Ascending)?;
+    let upsample = |include_keys| {
+        df.clone()
+            .lazy()
+            .upsample_by_exprs(
+                [col("time").dt().date().alias("date")],
+                "time",
+                Duration::parse("1h"),
+                Duration::parse("0"),
+                UpsampleOptions {
+                    maintain_order: true,
+                    ..Default::default()
+                },
+                include_keys,
+            )
+            .collect()
+    };
+
+    let out = upsample(false)?;
+    let expected = df![
+        "time" => [at(16, 10), at(16, 11), at(16, 12), at(17, 9), at(17, 10)],
+        "values" => [Some(1), None, Some(2), Some(3), Some(4)],
+    ]?;
+    assert!(out.equals_missing(&expected));
+
+    let out = upsample(true)?;
+    let date = NaiveDate::from_ymd_opt(2021, 12, 16).unwrap();
+    let next = NaiveDate::from_ymd_opt(2021, 12, 17).unwrap();
+    assert_eq!(out.get_column_names(), &["time", "values", "date"]);
+    assert!(out
+        .column("date")?
+        .equals(&Series::new("date", [date, date, date, next, next])));
+    Ok(())
+}
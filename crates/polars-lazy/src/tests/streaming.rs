@@ -388,3 +388,56 @@ fn test_sort_maintain_order_streaming() -> PolarsResult<()> {
     ]?));
     Ok(())
 }
+
+#[test]
+#[cfg(feature = "upsample")]
+fn test_upsample_streaming() -> PolarsResult<()> {
+    // The time column is only sorted within the groups.
+    let n = 100_000i64;
+    let df = df![
+        "time" => (0..n).map(|i| 2 * (i % (n / 2))).collect::<Vec<_>>(),
+        "groups" => (0..n).map(|i| if i < n / 2 { "a" } else { "b" }).collect::<Vec<_>>(),
+        "values" => (0..n).collect::<Vec<_>>(),
+    ]?
+    .with_sorted_flag("time", IsSorted::Ascending)?;
+    let options = UpsampleOptions {
+        maintain_order: true,
+        ..Default::default()
+    };
+    let q = df.lazy().upsample(
+        ["groups"],
+        "time",
+        Duration::parse("1i"),
+        Duration::parse("0"),
+        options,
+    );
+
+    let out = q.clone().with_streaming(true).collect()?;
+    assert_eq!(out.height(), 2 * (n as usize - 1));
+    assert_streaming_with_default(q, true, false);
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "upsample")]
+fn test_upsample_streaming_unsorted_chunks() -> PolarsResult<()> {
+    // Both inputs are sorted, but the second one starts before the first one ends.
+    let input = |time: Vec<i64>| -> PolarsResult<LazyFrame> {
+        let df = df!["time" => time, "values" => [1, 2, 3]]?
+            .with_sorted_flag("time", IsSorted::Ascending)?;
+        Ok(df.lazy())
+    };
+    let q = concat(
+        [input(vec![0, 2, 4])?, input(vec![1, 3, 5])?],
+        Default::default(),
+    )?
+    .upsample::<[String; 0]>(
+        [],
+        "time",
+        Duration::parse("1i"),
+        Duration::parse("0"),
+        Default::default(),
+    );
+    assert!(q.with_streaming(true).collect().is_err());
+    Ok(())
+}
@@ -225,6 +225,7 @@ pub fn _sort_or_hash_inner(
                 nulls_last: false,
                 multithreaded: true,
                 maintain_order: false,
+                ..Default::default()
             });
             let s_right = unsafe { s_right.take_unchecked(&sort_idx) };
             let ids = par_sorted_merge_inner_no_nulls(s_left, &s_right);
@@ -252,6 +253,7 @@ pub fn _sort_or_hash_inner(
                 nulls_last: false,
                 multithreaded: true,
                 maintain_order: false,
+                ..Default::default()
             });
             let s_left = unsafe { s_left.take_unchecked(&sort_idx) };
             let ids = par_sorted_merge_inner_no_nulls(&s_left, s_right);
@@ -321,6 +323,7 @@ pub(super) fn sort_or_hash_left(
                 nulls_last: false,
                 multithreaded: true,
                 maintain_order: false,
+                ..Default::default()
             });
             let s_right = unsafe { s_right.take_unchecked(&sort_idx) };
 
@@ -13,6 +13,8 @@ mod ordered;
 mod reproject;
 mod slice;
 mod sort;
+#[cfg(feature = "upsample")]
+mod upsample;
 mod utils;
 
 #[cfg(any(
@@ -27,6 +29,8 @@ pub(crate) use ordered::*;
 pub(crate) use reproject::*;
 pub(crate) use slice::*;
 pub(crate) use sort::*;
+#[cfg(feature = "upsample")]
+pub(crate) use upsample::*;
 
 // We must strike a balance between cache coherence and resizing costs.
 // Overallocation seems a lot more expensive than resizing so we start reasonable small.
@@ -179,6 +179,7 @@ impl Sink for SortSink {
                 nulls_last: self.sort_args.nulls_last,
                 multithreaded: true,
                 maintain_order: self.sort_args.maintain_order,
+                ..Default::default()
             });
 
             block_thread_until_io_thread_done(io_thread);
@@ -0,0 +1,115 @@
+use std::any::Any;
+
+use polars_core::datatypes::DataType;
+use polars_core::error::PolarsResult;
+use polars_core::frame::DataFrame;
+use polars_core::schema::SchemaRef;
+use polars_core::series::IsSorted;
+use polars_plan::prelude::FunctionNode;
+
+use crate::operators::{
+    chunks_to_df_unchecked, DataChunk, FinalizedSink, PExecutionContext, Sink, SinkResult,
+};
+
+// The upsample needs all rows of a group to know its range, so the chunks are collected
+// in their streamed order and upsampled at once when the sink is finalized. The whole
+// input is buffered, so the memory isn't bounded per group.
+#[derive(Clone)]
+pub struct UpsampleSink {
+    chunks: Vec<DataChunk>,
+    function: FunctionNode,
+    input_schema: SchemaRef,
+}
+
+impl UpsampleSink {
+    pub fn new(function: FunctionNode, input_schema: SchemaRef) -> Self {
+        UpsampleSink {
+            chunks: vec![],
+            function,
+            input_schema,
+        }
+    }
+
+    fn sort(&mut self) {
+        self.chunks.sort_unstable_by_key(|chunk| chunk.chunk_index);
+    }
+
+    /// The sorted flag of the concatenated time column, which the concatenation doesn't
+    /// keep. All chunks must have the flag. Without `by` the chunks must also be in order
+    /// at their boundaries. With `by` the time column only has to be sorted within the
+    /// groups, which the upsample checks for every group.
+    fn time_column_flag(&self) -> PolarsResult<Option<IsSorted>> {
+        let FunctionNode::Upsample { args } = &self.function else {
+            return Ok(None);
+        };
+        let mut flags = vec![];
+        let mut bounds = vec![];
+        for chunk in &self.chunks {
+            let s = chunk.data.column(&args.time_column)?;
+            flags.push(s.is_sorted_flag());
+            let s = s.to_physical_repr().cast(&DataType::Int64)?;
+            let mut values = s.i64()?.into_iter().flatten();
+            if let Some(first) = values.next() {
+                bounds.push((first, values.next_back().unwrap_or(first)));
+            }
+        }
+        flags.dedup();
+        let flag = match flags.as_slice() {
+            [flag] if *flag != IsSorted::Not => *flag,
+            _ => return Ok(None),
+        };
+        let in_order = bounds.windows(2).all(|w| {
+            let ((_, last), (first, _)) = (w[0], w[1]);
+            match flag {
+                IsSorted::Ascending => last <= first,
+                _ => last >= first,
+            }
+        });
+        Ok((in_order || !args.by.is_empty()).then_some(flag))
+    }
+}
+
+impl Sink for UpsampleSink {
+    fn sink(&mut self, _context: &PExecutionContext, chunk: DataChunk) -> PolarsResult<SinkResult> {
+        if chunk.data.height() > 0 {
+            self.chunks.push(chunk);
+        }
+        Ok(SinkResult::CanHaveMoreInput)
+    }
+
+    fn combine(&mut self, other: &mut dyn Sink) {
+        let other = other.as_any().downcast_ref::<UpsampleSink>().unwrap();
+        self.chunks.extend_from_slice(&other.chunks);
+        self.sort();
+    }
+
+    fn split(&self, _thread_no: usize) -> Box<dyn Sink> {
+        let mut new = self.clone();
+        new.chunks.clear();
+        Box::new(new)
+    }
+    fn finalize(&mut self, _context: &PExecutionContext) -> PolarsResult<FinalizedSink> {
+        let df = if self.chunks.is_empty() {
+            DataFrame::from(self.input_schema.as_ref())
+        } else {
+            self.sort();
+            let flag = self.time_column_flag()?;
+            let mut df = chunks_to_df_unchecked(std::mem::take(&mut self.chunks));
+            if let (Some(flag), FunctionNode::Upsample { args }) = (flag, &self.function) {
+                df.apply(&args.time_column, |s| {
+                    let mut s = s.clone();
+                    s.set_sorted_flag(flag);
+                    s
+                })?;
+            }
+            df
+        };
+        Ok(FinalizedSink::Finished(self.function.evaluate(df)?))
+    }
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn fmt(&self) -> &str {
+        "upsample_sink"
+    }
+}
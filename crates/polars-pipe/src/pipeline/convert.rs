@@ -474,6 +474,14 @@ where
                 }
             }
         },
+        #[cfg(feature = "upsample")]
+        MapFunction {
+            input,
+            function: function @ FunctionNode::Upsample { .. },
+        } => {
+            let input_schema = lp_arena.get(*input).schema(lp_arena).into_owned();
+            Box::new(UpsampleSink::new(function.clone(), input_schema)) as Box<dyn SinkTrait>
+        },
         lp => {
             panic!("{lp:?} not implemented")
         },
@@ -830,6 +830,15 @@ impl LogicalPlanBuilder {
         .into()
     }
 
+    #[cfg(feature = "upsample")]
+    pub fn upsample(self, args: Arc<UpsampleArgs>) -> Self {
+        LogicalPlan::MapFunction {
+            input: Box::new(self.0),
+            function: FunctionNode::Upsample { args },
+        }
+        .into()
+    }
+
     pub fn row_count(self, name: &str, offset: Option<IdxSize>) -> Self {
         let mut schema = try_delayed!(self.0.schema(), &self.0, into).into_owned();
         let schema_mut = Arc::make_mut(&mut schema);
@@ -3,6 +3,8 @@ mod merge_sorted;
 #[cfg(feature = "python")]
 mod python_udf;
 mod rename;
+#[cfg(feature = "upsample")]
+mod upsample;
 
 use std::borrow::Cow;
 use std::fmt::{Debug, Display, Formatter};
@@ -15,6 +17,8 @@ use polars_core::StringCacheHolder;
 use serde::{Deserialize, Serialize};
 use smartstring::alias::String as SmartString;
 
+#[cfg(feature = "upsample")]
+pub use self::upsample::UpsampleArgs;
 #[cfg(feature = "python")]
 use crate::dsl::python_udf::PythonFunction;
 #[cfg(feature = "merge_sorted")]
@@ -93,6 +97,10 @@ pub enum FunctionNode {
         schema: SchemaRef,
         offset: Option<IdxSize>,
     },
+    #[cfg(feature = "upsample")]
+    Upsample {
+        args: Arc<UpsampleArgs>,
+    },
 }
 
 impl PartialEq for FunctionNode {
@@ -126,6 +134,8 @@ impl PartialEq for FunctionNode {
             (Explode { columns: l, .. }, Explode { columns: r, .. }) => l == r,
             (Melt { args: l, .. }, Melt { args: r, .. }) => l == r,
             (RowCount { name: l, .. }, RowCount { name: r, .. }) => l == r,
+            #[cfg(feature = "upsample")]
+            (Upsample { args: l }, Upsample { args: r }) => l == r,
             _ => false,
         }
     }
@@ -149,6 +159,8 @@ impl FunctionNode {
             #[cfg(feature = "python")]
             OpaquePython { streamable, .. } => *streamable,
             RowCount { .. } => false,
+            #[cfg(feature = "upsample")]
+            Upsample { .. } => false,
         }
     }
 
@@ -159,6 +171,8 @@ impl FunctionNode {
             #[cfg(feature = "merge_sorted")]
             MergeSorted { .. } => true,
             Explode { .. } | Melt { .. } => true,
+            #[cfg(feature = "upsample")]
+            Upsample { .. } => true,
             _ => false,
         }
     }
@@ -236,6 +250,8 @@ impl FunctionNode {
             Explode { schema, .. } | RowCount { schema, .. } | Melt { schema, .. } => {
                 Ok(Cow::Owned(schema.clone()))
             },
+            #[cfg(feature = "upsample")]
+            Upsample { args } => upsample::upsample_plan_schema(input_schema, args),
         }
     }
 
@@ -255,6 +271,9 @@ impl FunctionNode {
             #[cfg(feature = "merge_sorted")]
             MergeSorted { .. } => true,
             RowCount { .. } => false,
+            // Only the predicates on the `by` columns are pushed down.
+            #[cfg(feature = "upsample")]
+            Upsample { .. } => true,
             Pipeline { .. } => unimplemented!(),
         }
     }
@@ -275,6 +294,8 @@ impl FunctionNode {
             #[cfg(feature = "merge_sorted")]
             MergeSorted { .. } => true,
             RowCount { .. } => true,
+            #[cfg(feature = "upsample")]
+            Upsample { .. } => true,
             Pipeline { .. } => unimplemented!(),
         }
     }
@@ -348,6 +369,8 @@ impl FunctionNode {
                 df.melt2(args)
             },
             RowCount { name, offset, .. } => df.with_row_count(name.as_ref(), *offset),
+            #[cfg(feature = "upsample")]
+            Upsample { args } => upsample::upsample_impl(&df, args),
         }
     }
 }
@@ -397,6 +420,11 @@ impl Display for FunctionNode {
             Explode { .. } => write!(f, "EXPLODE"),
             Melt { .. } => write!(f, "MELT"),
             RowCount { .. } => write!(f, "WITH ROW COUNT"),
+            #[cfg(feature = "upsample")]
+            Upsample { args } => {
+                write!(f, "UPSAMPLE {} by: ", args.time_column)?;
+                fmt_column_delimited(f, &args.by, "[", "]")
+            },
         }
     }
 }
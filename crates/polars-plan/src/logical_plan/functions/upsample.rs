@@ -0,0 +1,39 @@
+use polars_time::{upsample_schema, Duration, PolarsUpsample, UpsampleOptions};
+
+use super::*;
+
+/// Arguments of a [`FunctionNode::Upsample`], see [`PolarsUpsample::upsample_with_options`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UpsampleArgs {
+    pub by: Vec<String>,
+    pub time_column: String,
+    pub every: Duration,
+    pub offset: Duration,
+    pub options: UpsampleOptions,
+}
+
+impl UpsampleArgs {
+    /// The columns the upsample needs from its input, besides the projected value columns.
+    pub(crate) fn key_columns(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.time_column.as_str()).chain(self.by.iter().map(|s| s.as_str()))
+    }
+}
+
+pub(super) fn upsample_impl(df: &DataFrame, args: &UpsampleArgs) -> PolarsResult<DataFrame> {
+    df.upsample_with_options(
+        args.by.clone(),
+        &args.time_column,
+        args.every,
+        args.offset,
+        &args.options,
+    )
+}
+
+pub(super) fn upsample_plan_schema<'a>(
+    input_schema: &'a SchemaRef,
+    args: &UpsampleArgs,
+) -> PolarsResult<Cow<'a, SchemaRef>> {
+    let schema = upsample_schema(input_schema, &args.by, &args.time_column, &args.options)?;
+    Ok(Cow::Owned(Arc::new(schema)))
+}
@@ -477,6 +477,21 @@ impl<'a> PredicatePushDown<'a> {
                             Ok(self.optional_apply_predicate(lp, local_predicates, lp_arena, expr_arena))
 
                         }
+                        #[cfg(feature = "upsample")]
+                        FunctionNode::Upsample { args } => {
+                            // The inserted rows get the key of their group, so only the
+                            // predicates on the `by` columns can be done before the upsample.
+                            // A shared grid spans all groups, so filtering groups would change
+                            // the grid of the others.
+                            let condition = |name: Arc<str>| {
+                                args.options.grid.shared || !args.by.iter().any(|s| s.as_str() == &*name)
+                            };
+                            let local_predicates =
+                                transfer_to_local_by_name(expr_arena, &mut acc_predicates, condition);
+
+                            let lp = self.pushdown_and_continue(lp, acc_predicates, lp_arena, expr_arena, false)?;
+                            Ok(self.optional_apply_predicate(lp, local_predicates, lp_arena, expr_arena))
+                        }
                         _ => {
                             self.pushdown_and_continue(lp, acc_predicates, lp_arena, expr_arena, false)
                         }
@@ -1,6 +1,10 @@
 mod melt;
+#[cfg(feature = "upsample")]
+mod upsample;
 
 use melt::process_melt;
+#[cfg(feature = "upsample")]
+use upsample::process_upsample;
 
 use super::*;
 
@@ -78,6 +82,24 @@ pub(super) fn process_functions(
                 expr_arena,
             )
         },
+        #[cfg(feature = "upsample")]
+        Upsample { args } => {
+            let lp = ALogicalPlan::MapFunction {
+                input,
+                function: function.clone(),
+            };
+
+            process_upsample(
+                proj_pd,
+                lp,
+                args,
+                input,
+                acc_projections,
+                projections_seen,
+                lp_arena,
+                expr_arena,
+            )
+        },
         _ => {
             let lp = ALogicalPlan::MapFunction {
                 input,
@@ -0,0 +1,53 @@
+use super::*;
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn process_upsample(
+    proj_pd: &mut ProjectionPushDown,
+    lp: ALogicalPlan,
+    args: &UpsampleArgs,
+    input: Node,
+    acc_projections: Vec<Node>,
+    projections_seen: usize,
+    lp_arena: &mut Arena<ALogicalPlan>,
+    expr_arena: &mut Arena<AExpr>,
+) -> PolarsResult<ALogicalPlan> {
+    // The indicator column is created by the upsample, so it is projected locally.
+    let (mut acc_projections, mut local_projections, mut projected_names) = split_acc_projections(
+        acc_projections,
+        lp_arena.get(input).schema(lp_arena).as_ref(),
+        expr_arena,
+        false,
+    );
+
+    if args.options.fill.drop_empty {
+        // Which inserted rows are dropped depends on all value columns, so none of them is
+        // pruned and the projection is done above the upsample.
+        local_projections.extend_from_slice(&acc_projections);
+        acc_projections.clear();
+        projected_names.clear();
+    } else if !local_projections.is_empty() {
+        local_projections.extend_from_slice(&acc_projections);
+    }
+
+    // The range is created from the time column of every group.
+    args.key_columns().for_each(|name| {
+        add_str_to_accumulated(name, &mut acc_projections, &mut projected_names, expr_arena)
+    });
+
+    proj_pd.pushdown_and_assign(
+        input,
+        acc_projections,
+        projected_names,
+        projections_seen,
+        lp_arena,
+        expr_arena,
+    )?;
+
+    if local_projections.is_empty() {
+        Ok(lp)
+    } else {
+        Ok(ALogicalPlanBuilder::from_lp(lp, expr_arena, lp_arena)
+            .project(local_projections, Default::default())
+            .build())
+    }
+}
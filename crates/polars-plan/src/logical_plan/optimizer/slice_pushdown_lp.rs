@@ -303,6 +303,12 @@ impl SlicePushDown {
                 let (lp, state) = m;
                 self.no_pushdown_restart_opt(lp, state, lp_arena, expr_arena)
             }
+            // the upsample inserts rows
+            #[cfg(feature = "upsample")]
+            m @ (MapFunction {function: FunctionNode::Upsample {..}, ..}, _) => {
+                let (lp, state) = m;
+                self.no_pushdown_restart_opt(lp, state, lp_arena, expr_arena)
+            }
             // [Pushdown]
             (MapFunction {input, function}, _) if function.allow_predicate_pd() => {
                 let lp = MapFunction {input, function};
@@ -0,0 +1,407 @@
+use polars_core::prelude::*;
+use polars_core::utils::{accumulate_dataframes_vertical, ensure_sorted_arg};
+use polars_ops::prelude::*;
+
+use crate::prelude::*;
+use crate::upsample::{ensure_group_sorted, regular_range};
+
+pub trait PolarsDownsample {
+    /// Downsample a [`DataFrame`] onto a regular, coarser grid.
+    ///
+    /// Every row is routed into the bucket `[t, t + every)` of the grid point `t` it
+    /// falls in, after which `agg` is called with the rows of that bucket. `agg` must
+    /// return a [`DataFrame`] with a single row. Buckets without any rows are still
+    /// emitted, with nulls for the aggregated columns.
+    ///
+    /// # Arguments
+    /// * `by` - First group by these columns and then downsample for every group
+    /// * `time_column` - Will be used to determine the bucket edges.
+    ///                   Note that this column has to be sorted for the output to make sense.
+    /// * `every` - a bucket will start 'every' duration
+    /// * `offset` - change the start of the bucket edges by this offset.
+    /// * `agg` - aggregation applied to the rows of every bucket. It does not receive the
+    ///           `time_column` nor the `by` columns.
+    ///
+    /// See [`PolarsUpsample::upsample`] for the string language of `every` and `offset`.
+    /// An integer `time_column` is downsampled with an index count, e.g. `"2i"`.
+    fn downsample<I, F>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        agg: F,
+    ) -> PolarsResult<DataFrame>
+    where
+        I: IntoVec<String>,
+        F: Fn(DataFrame) -> PolarsResult<DataFrame> + Send + Sync;
+
+    /// Downsample a [`DataFrame`] onto a regular, coarser grid and maintain the order
+    /// of the groups.
+    ///
+    /// See [`PolarsDownsample::downsample`].
+    fn downsample_stable<I, F>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        agg: F,
+    ) -> PolarsResult<DataFrame>
+    where
+        I: IntoVec<String>,
+        F: Fn(DataFrame) -> PolarsResult<DataFrame> + Send + Sync;
+}
+
+impl PolarsDownsample for DataFrame {
+    fn downsample<I, F>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        agg: F,
+    ) -> PolarsResult<DataFrame>
+    where
+        I: IntoVec<String>,
+        F: Fn(DataFrame) -> PolarsResult<DataFrame> + Send + Sync,
+    {
+        let by = by.into_vec();
+        downsample_impl(self, by, time_column, every, offset, &agg, false)
+    }
+
+    fn downsample_stable<I, F>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        agg: F,
+    ) -> PolarsResult<DataFrame>
+    where
+        I: IntoVec<String>,
+        F: Fn(DataFrame) -> PolarsResult<DataFrame> + Send + Sync,
+    {
+        let by = by.into_vec();
+        downsample_impl(self, by, time_column, every, offset, &agg, true)
+    }
+}
+
+pub(crate) fn downsample_impl<F>(
+    source: &DataFrame,
+    by: Vec<String>,
+    index_column: &str,
+    every: Duration,
+    offset: Duration,
+    agg: &F,
+    stable: bool,
+) -> PolarsResult<DataFrame>
+where
+    F: Fn(DataFrame) -> PolarsResult<DataFrame> + Send + Sync,
+{
+    let s = source.column(index_column)?;
+    ensure_sorted_arg(s, "downsample")?;
+    if by.is_empty() {
+        let index_column = source.column(index_column)?;
+        let values = source.drop(index_column.name())?;
+        downsample_single_impl(&values, index_column, every, offset, agg)
+    } else {
+        let gb = if stable {
+            source.group_by_stable(&by)
+        } else {
+            source.group_by(&by)
+        };
+        gb?.apply(|df| {
+            let index_column = df.column(index_column)?;
+            ensure_group_sorted(&df, index_column, &by, "downsample")?;
+            let mut values = df.drop(index_column.name())?;
+            values = values.drop_many(&by);
+            let mut downsampled_df =
+                downsample_single_impl(&values, index_column, every, offset, agg)?;
+            // Every bucket belongs to this group, also the empty ones.
+            let height = downsampled_df.height();
+            for (i, column) in by.iter().enumerate() {
+                let key = df.column(column)?.new_from_index(0, height);
+                downsampled_df.insert_column(i + 1, key)?;
+            }
+            Ok(downsampled_df)
+        })
+    }
+}
+
+/// Determine the bucket edges of the index column.
+///
+/// The edges are the grid of [`PolarsUpsample::upsample`], so a Date index column is
+/// bucketed in whole days and a negative `offset` moves the first edge back in whole steps
+/// of `every`.
+fn bucket_edges(index_column: &Series, every: Duration, offset: Duration) -> PolarsResult<Series> {
+    use DataType::*;
+    match index_column.dtype() {
+        Datetime(_, _) | Date => polars_ensure!(
+            !every.parsed_int,
+            ComputeError: "downsample with an index count `every` requires an integer index column"
+        ),
+        Int32 | Int64 | UInt64 => polars_ensure!(
+            every.parsed_int,
+            ComputeError: "downsample of an integer index column requires an index count `every`, e.g. '2i'"
+        ),
+        dt => polars_bail!(
+            ComputeError: "downsample not allowed for index column of dtype {}", dt,
+        ),
+    }
+    let options = UpsampleOptions {
+        grid: UpsampleGridOptions {
+            closed: ClosedWindow::Both,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    regular_range(index_column, every, offset, &options)
+}
+
+fn downsample_single_impl<F>(
+    values: &DataFrame,
+    index_column: &Series,
+    every: Duration,
+    offset: Duration,
+    agg: &F,
+) -> PolarsResult<DataFrame>
+where
+    F: Fn(DataFrame) -> PolarsResult<DataFrame>,
+{
+    let index_col_name = index_column.name();
+    let edges_s = bucket_edges(index_column, every, offset)?;
+    let edges = edges_s.to_physical_repr().cast(&DataType::Int64)?;
+    let edges = edges.i64()?.into_no_null_iter().collect::<Vec<_>>();
+
+    // The index is sorted, so the rows of a bucket are contiguous.
+    // (bucket, offset, len)
+    let mut buckets: Vec<(usize, IdxSize, IdxSize)> = Vec::with_capacity(edges.len());
+    let ts = index_column
+        .to_physical_repr()
+        .strict_cast(&DataType::Int64)?;
+    for (i, t) in ts.i64()?.into_iter().enumerate() {
+        let Some(t) = t else { continue };
+        let bucket = edges.partition_point(|&edge| edge <= t);
+        // Rows before the first edge don't belong to any bucket.
+        if bucket == 0 {
+            continue;
+        }
+        let bucket = bucket - 1;
+        match buckets.last_mut() {
+            Some((b, _, len)) if *b == bucket => *len += 1,
+            _ => buckets.push((bucket, i as IdxSize, 1)),
+        }
+    }
+
+    let aggregated = if buckets.is_empty() {
+        agg(values.clear())?.clear()
+    } else {
+        let dfs = buckets
+            .iter()
+            .map(|&(_, offset, len)| {
+                let out = agg(values.slice(offset as i64, len as usize))?;
+                polars_ensure!(
+                    out.height() == 1,
+                    ComputeError: "downsample aggregation must return a single row per bucket, got {} rows",
+                    out.height()
+                );
+                Ok(out)
+            })
+            .collect::<PolarsResult<Vec<_>>>()?;
+        accumulate_dataframes_vertical(dfs)?
+    };
+
+    let labels = IdxCa::from_vec(
+        index_col_name,
+        buckets
+            .iter()
+            .map(|&(bucket, _, _)| bucket as IdxSize)
+            .collect(),
+    );
+    let labels = edges_s.take(&labels)?;
+    let mut aggregated = aggregated;
+    aggregated.insert_column(0, labels)?;
+
+    edges_s.into_frame().join(
+        &aggregated,
+        &[index_col_name],
+        &[index_col_name],
+        JoinArgs::new(JoinType::Left),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use polars_core::series::IsSorted;
+
+    use super::*;
+
+    fn sum_values(df: DataFrame) -> PolarsResult<DataFrame> {
+        Ok(df.sum())
+    }
+
+    #[test]
+    fn test_downsample() -> PolarsResult<()> {
+        let time = date_range(
+            "time",
+            NaiveDate::from_ymd_opt(2021, 12, 16)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+            NaiveDate::from_ymd_opt(2021, 12, 16)
+                .unwrap()
+                .and_hms_opt(2, 0, 0)
+                .unwrap(),
+            Duration::parse("15m"),
+            ClosedWindow::Both,
+            TimeUnit::Milliseconds,
+            None,
+        )?
+        .into_series();
+        // Leave a gap from 00:30 up to and including 01:15.
+        let time = time.filter(&BooleanChunked::new(
+            "",
+            &[true, true, false, false, false, false, true, true, true],
+        ))?;
+        let df = df!(
+            "time" => time,
+            "values" => &[1.0, 2.0, 3.0, 4.0, 5.0],
+        )?
+        .with_sorted_flag("time", IsSorted::Ascending)?;
+
+        let out = df.downsample::<[String; 0], _>(
+            [],
+            "time",
+            Duration::parse("30m"),
+            Duration::parse("0"),
+            sum_values,
+        )?;
+        assert_eq!(out.height(), 5);
+        assert_eq!(
+            Vec::from(out.column("values")?.f64()?),
+            &[Some(3.0), None, None, Some(7.0), Some(5.0)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_downsample_group_by() -> PolarsResult<()> {
+        let df = df!(
+            "idx" => &[0i64, 1, 2, 3, 5, 6, 7, 12],
+            "groups" => &["a", "a", "a", "a", "a", "b", "b", "b"],
+            "values" => &[1, 2, 3, 4, 5, 6, 7, 8],
+        )?
+        .with_sorted_flag("idx", IsSorted::Ascending)?;
+
+        let out = df.downsample_stable::<[String; 1], _>(
+            [String::from("groups")],
+            "idx",
+            Duration::parse("2i"),
+            Duration::parse("0i"),
+            sum_values,
+        )?;
+        let expected = df!(
+            "idx" => &[0i64, 2, 4, 6, 8, 10, 12],
+            "groups" => &["a", "a", "a", "b", "b", "b", "b"],
+            "values" => &[Some(3), Some(7), Some(5), Some(13), None, None, Some(8)],
+        )?;
+        assert!(out.equals_missing(&expected));
+        Ok(())
+    }
+
+    #[test]
+    fn test_downsample_group_sorted() -> PolarsResult<()> {
+        // The flag is wrong for group "b".
+        let df = df!(
+            "idx" => &[0u64, 1, 2, 1, 0],
+            "groups" => &["a", "a", "a", "b", "b"],
+            "values" => &[1, 2, 3, 4, 5],
+        )?
+        .with_sorted_flag("idx", IsSorted::Ascending)?;
+        let downsample = |df: &DataFrame| {
+            df.downsample_stable::<[String; 1], _>(
+                [String::from("groups")],
+                "idx",
+                Duration::parse("2i"),
+                Duration::parse("0i"),
+                sum_values,
+            )
+        };
+        assert!(downsample(&df).is_err());
+
+        let df = df
+            .slice(0, 3)
+            .with_sorted_flag("idx", IsSorted::Ascending)?;
+        let out = downsample(&df)?;
+        assert_eq!(Vec::from(out.column("idx")?.u64()?), &[Some(0), Some(2)]);
+        assert_eq!(Vec::from(out.column("values")?.i32()?), &[Some(3), Some(3)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_downsample_negative_offset() -> PolarsResult<()> {
+        // A negative offset moves the first edge back in whole steps, as in upsample.
+        let df = df!(
+            "idx" => &[0i64, 1, 2, 3, 5],
+            "values" => &[1, 2, 3, 4, 5],
+        )?
+        .with_sorted_flag("idx", IsSorted::Ascending)?;
+        let out = df.downsample::<[String; 0], _>(
+            [],
+            "idx",
+            Duration::parse("2i"),
+            Duration::parse("-3i"),
+            sum_values,
+        )?;
+        let expected = df!(
+            "idx" => &[-4i64, -2, 0, 2, 4],
+            "values" => &[None, None, Some(3), Some(7), Some(5)],
+        )?;
+        assert!(out.equals_missing(&expected));
+
+        // A Date index column is bucketed in days, without a round trip through Datetime.
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let df = df!(
+            "date" => &[date(2021, 1, 1), date(2021, 1, 2), date(2021, 1, 4)],
+            "values" => &[1, 2, 3],
+        )?
+        .with_sorted_flag("date", IsSorted::Ascending)?;
+        let out = df.downsample::<[String; 0], _>(
+            [],
+            "date",
+            Duration::parse("2d"),
+            Duration::parse("-1d"),
+            sum_values,
+        )?;
+        let expected = df!(
+            "date" => &[date(2020, 12, 30), date(2021, 1, 1), date(2021, 1, 3)],
+            "values" => &[None, Some(3), Some(3)],
+        )?;
+        assert_eq!(out.column("date")?.dtype(), &DataType::Date);
+        assert!(out.equals_missing(&expected));
+        Ok(())
+    }
+
+    #[test]
+    fn test_downsample_u64_above_i64_max() -> PolarsResult<()> {
+        let df = df!(
+            "idx" => &[u64::MAX - 2, u64::MAX - 1, u64::MAX],
+            "values" => &[1, 2, 3],
+        )?
+        .with_sorted_flag("idx", IsSorted::Ascending)?;
+        let err = df
+            .downsample::<[String; 0], _>(
+                [],
+                "idx",
+                Duration::parse("2i"),
+                Duration::parse("0i"),
+                sum_values,
+            )
+            .unwrap_err();
+        // The values are rejected instead of being dropped as nulls.
+        assert!(matches!(err, PolarsError::ComputeError(_)));
+        Ok(())
+    }
+}
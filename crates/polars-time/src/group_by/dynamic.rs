@@ -87,8 +87,8 @@ fn check_sortedness_slice(v: &[i64]) -> PolarsResult<()> {
     Ok(())
 }
 
-const LB_NAME: &str = "_lower_boundary";
-const UP_NAME: &str = "_upper_boundary";
+pub(crate) const LB_NAME: &str = "_lower_boundary";
+pub(crate) const UP_NAME: &str = "_upper_boundary";
 
 pub trait PolarsTemporalGroupby {
     fn group_by_rolling(
@@ -2,11 +2,13 @@
 mod base_utc_offset;
 pub mod chunkedarray;
 mod date_range;
+mod downsample;
 mod dst_offset;
 mod group_by;
 mod month_end;
 mod month_start;
 pub mod prelude;
+mod resample;
 mod round;
 pub mod series;
 mod truncate;
@@ -17,12 +19,14 @@ mod windows;
 #[cfg(feature = "timezones")]
 pub use base_utc_offset::*;
 pub use date_range::*;
+pub use downsample::*;
 #[cfg(feature = "timezones")]
 pub use dst_offset::*;
 #[cfg(any(feature = "dtype-date", feature = "dtype-datetime"))]
 pub use group_by::dynamic::*;
 pub use month_end::*;
 pub use month_start::*;
+pub use resample::*;
 pub use round::*;
 pub use truncate::*;
 pub use upsample::*;
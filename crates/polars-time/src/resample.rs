@@ -0,0 +1,283 @@
+use arrow::temporal_conversions::MILLISECONDS_IN_DAY;
+use polars_core::frame::group_by::GroupsIndicator;
+use polars_core::prelude::*;
+
+use crate::downsample::downsample_impl;
+use crate::prelude::*;
+use crate::upsample::upsample_impl;
+
+/// Whether a [`PolarsResample::resample`] upsamples or downsamples.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ResampleDirection {
+    /// Upsample onto a finer grid and fill the inserted rows.
+    Upsample,
+    /// Downsample onto a coarser grid and aggregate the rows of every bucket.
+    Downsample,
+}
+
+/// How a [`DataFrame`] is resampled.
+pub struct ResampleHow<F> {
+    /// Resample in this direction instead of detecting it from the native frequency of
+    /// the time column.
+    pub direction: Option<ResampleDirection>,
+    /// Options of the upsample, e.g. how the inserted rows are filled.
+    pub upsample_options: UpsampleOptions,
+    /// Aggregation of the downsample, see [`PolarsDownsample::downsample`].
+    pub agg: F,
+}
+
+impl<F> ResampleHow<F> {
+    /// Detect the direction and upsample with the default [`UpsampleOptions`].
+    pub fn new(agg: F) -> Self {
+        Self {
+            direction: None,
+            upsample_options: Default::default(),
+            agg,
+        }
+    }
+}
+
+pub trait PolarsResample {
+    /// Resample a [`DataFrame`] at a regular frequency.
+    ///
+    /// Upsamples if `every` is at least as fine as the native frequency of `time_column`, and
+    /// downsamples otherwise. The native frequency is the median of the deltas between
+    /// consecutive non-null values of `time_column`, computed within the groups if `by` is
+    /// given. Deltas of zero, i.e. duplicated timestamps, are ignored. Calendar durations
+    /// are estimated to compare them to the native frequency, e.g. a month counts as 28 days.
+    /// If every group has fewer than two distinct timestamps, the frequency can't be
+    /// detected and the frame is upsampled. Set `how.direction` to skip the detection.
+    ///
+    /// See [`PolarsUpsample::upsample_with_options`] and [`PolarsDownsample::downsample`]
+    /// for the arguments.
+    fn resample<I, F>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        how: &ResampleHow<F>,
+    ) -> PolarsResult<DataFrame>
+    where
+        I: IntoVec<String>,
+        F: Fn(DataFrame) -> PolarsResult<DataFrame> + Send + Sync;
+}
+
+impl PolarsResample for DataFrame {
+    fn resample<I, F>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        how: &ResampleHow<F>,
+    ) -> PolarsResult<DataFrame>
+    where
+        I: IntoVec<String>,
+        F: Fn(DataFrame) -> PolarsResult<DataFrame> + Send + Sync,
+    {
+        let by = by.into_vec();
+        let direction = match how.direction {
+            Some(direction) => direction,
+            None => detect_direction(self, &by, time_column, every)?,
+        };
+        match direction {
+            ResampleDirection::Upsample => {
+                upsample_impl(self, by, time_column, every, offset, &how.upsample_options)
+                    .map(|(out, _)| out)
+            },
+            ResampleDirection::Downsample => downsample_impl(
+                self,
+                by,
+                time_column,
+                every,
+                offset,
+                &how.agg,
+                how.upsample_options.maintain_order,
+            ),
+        }
+    }
+}
+
+fn detect_direction(
+    df: &DataFrame,
+    by: &[String],
+    time_column: &str,
+    every: Duration,
+) -> PolarsResult<ResampleDirection> {
+    let index_column = df.column(time_column)?;
+    let Some(native) = native_frequency(df, by, index_column)? else {
+        return Ok(ResampleDirection::Upsample);
+    };
+    use DataType::*;
+    let (native, every) = match index_column.dtype() {
+        Datetime(TimeUnit::Nanoseconds, _) => (native, every.duration_ns()),
+        Datetime(TimeUnit::Microseconds, _) => (native, every.duration_us()),
+        Datetime(TimeUnit::Milliseconds, _) => (native, every.duration_ms()),
+        Date => (
+            native.saturating_mul(MILLISECONDS_IN_DAY),
+            every.duration_ms(),
+        ),
+        _ => (native, every.nanoseconds()),
+    };
+    if every <= native {
+        Ok(ResampleDirection::Upsample)
+    } else {
+        Ok(ResampleDirection::Downsample)
+    }
+}
+
+/// Median of the positive deltas of the index column in its physical representation.
+fn native_frequency(
+    df: &DataFrame,
+    by: &[String],
+    index_column: &Series,
+) -> PolarsResult<Option<i64>> {
+    let mut deltas = index_deltas(df, by, index_column)?;
+    if deltas.is_empty() {
+        return Ok(None);
+    }
+    let mid = (deltas.len() - 1) / 2;
+    Ok(Some(*deltas.select_nth_unstable(mid).1))
+}
+
+/// The positive deltas between consecutive non-null values of the index column in its
+/// physical representation, within the groups if `by` is given.
+pub(crate) fn index_deltas(
+    df: &DataFrame,
+    by: &[String],
+    index_column: &Series,
+) -> PolarsResult<Vec<i64>> {
+    let s = index_column.to_physical_repr().cast(&DataType::Int64)?;
+    let values = s.i64()?.into_iter().collect::<Vec<_>>();
+
+    let mut deltas = vec![];
+    let mut push_deltas = |rows: &mut dyn Iterator<Item = usize>| {
+        let mut prev = None;
+        for v in rows.filter_map(|i| values[i]) {
+            if let Some(prev) = prev {
+                if v > prev {
+                    deltas.push(v - prev)
+                }
+            }
+            prev = Some(v);
+        }
+    };
+    if by.is_empty() {
+        push_deltas(&mut (0..values.len()));
+    } else {
+        let gb = df.group_by(by)?;
+        for g in gb.get_groups().iter() {
+            match g {
+                GroupsIndicator::Idx((_, idx)) => push_deltas(&mut idx.iter().map(|i| *i as usize)),
+                GroupsIndicator::Slice([first, len]) => {
+                    push_deltas(&mut (first as usize..(first + len) as usize))
+                },
+            }
+        }
+    }
+    Ok(deltas)
+}
+
+#[cfg(test)]
+mod tests {
+    use polars_core::series::IsSorted;
+
+    use super::*;
+
+    fn sum_values(df: DataFrame) -> PolarsResult<DataFrame> {
+        Ok(df.sum())
+    }
+
+    fn resample(
+        df: &DataFrame,
+        by: &[&str],
+        every: &str,
+        how: &ResampleHow<fn(DataFrame) -> PolarsResult<DataFrame>>,
+    ) -> DataFrame {
+        let by = by.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        df.resample(
+            by,
+            "idx",
+            Duration::parse(every),
+            Duration::parse("0i"),
+            how,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_resample() -> PolarsResult<()> {
+        let mut df = df!(
+            "idx" => &[0i64, 2, 4, 6, 7],
+            "values" => &[1, 2, 3, 4, 5],
+        )?;
+        df.apply("idx", |s| {
+            let mut s = s.clone();
+            s.set_sorted_flag(IsSorted::Ascending);
+            s
+        })?;
+        let mut how = ResampleHow::new(sum_values as fn(DataFrame) -> PolarsResult<DataFrame>);
+
+        // The native frequency is 2, so this upsamples.
+        let out = resample(&df, &[], "1i", &how);
+        assert_eq!(
+            Vec::from(out.column("idx")?.i64()?),
+            (0..8).map(Some).collect::<Vec<_>>()
+        );
+        let out = resample(&df, &[], "2i", &how);
+        assert_eq!(
+            Vec::from(out.column("idx")?.i64()?),
+            &[Some(0), Some(2), Some(4), Some(6)]
+        );
+
+        // Coarser than the native frequency, so this downsamples.
+        let out = resample(&df, &[], "4i", &how);
+        assert_eq!(Vec::from(out.column("idx")?.i64()?), &[Some(0), Some(4)]);
+        assert_eq!(
+            Vec::from(out.column("values")?.i32()?),
+            &[Some(3), Some(12)]
+        );
+
+        how.direction = Some(ResampleDirection::Downsample);
+        let out = resample(&df, &[], "1i", &how);
+        assert_eq!(out.height(), 8);
+        assert_eq!(
+            Vec::from(out.column("values")?.i32()?),
+            &[
+                Some(1),
+                None,
+                Some(2),
+                None,
+                Some(3),
+                None,
+                Some(4),
+                Some(5)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_resample_group_frequency() -> PolarsResult<()> {
+        let mut df = df!(
+            "idx" => &[0i64, 1, 2, 3, 4, 5],
+            "groups" => &["a", "b", "a", "b", "a", "b"],
+            "values" => &[1, 2, 3, 4, 5, 6],
+        )?;
+        df.apply("idx", |s| {
+            let mut s = s.clone();
+            s.set_sorted_flag(IsSorted::Ascending);
+            s
+        })?;
+        let mut how = ResampleHow::new(sum_values as fn(DataFrame) -> PolarsResult<DataFrame>);
+        how.upsample_options.indicator = true;
+
+        // The groups have a native frequency of 2, the column as a whole of 1.
+        let out = resample(&df, &["groups"], "2i", &how);
+        assert!(out.column("_upsampled").is_ok());
+        let out = resample(&df, &[], "2i", &how);
+        assert!(out.column("_upsampled").is_err());
+        Ok(())
+    }
+}
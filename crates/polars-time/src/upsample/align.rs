@@ -0,0 +1,36 @@
+use polars_core::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How the rows of the source whose timestamp doesn't land on a point of the upsample
+/// range are handled, e.g. because `every` and `offset` don't divide the spacing of the
+/// source. Rows before the first or after the last point of the range are not affected.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UpsampleUnaligned {
+    /// Leave them out of the output.
+    #[default]
+    Drop,
+    /// Raise an error that reports the first of them.
+    Error,
+    /// Keep them in the output, sorted in between the points of the range. The output is
+    /// then no longer regular.
+    OuterJoin,
+}
+
+/// Find the rows of `source` that lie within `range` without landing on one of its points.
+/// Both `index_column` and `range` must be sorted.
+pub(super) fn unaligned_rows(index_column: &Series, range: &Series) -> PolarsResult<Vec<IdxSize>> {
+    let range = range.to_physical_repr().cast(&DataType::Int64)?;
+    let range = range.i64()?.into_no_null_iter().collect::<Vec<_>>();
+    let s = index_column.to_physical_repr().cast(&DataType::Int64)?;
+    Ok(s.i64()?
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, t)| match range.binary_search(&t?) {
+            Ok(_) => None,
+            Err(pos) if pos == 0 || pos == range.len() => None,
+            Err(_) => Some(i as IdxSize),
+        })
+        .collect())
+}
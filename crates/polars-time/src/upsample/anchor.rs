@@ -0,0 +1,55 @@
+use arrow::legacy::time_zone::Tz;
+use chrono::Weekday;
+use polars_core::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+/// The calendar boundary the start of an upsample range is snapped back to, before the
+/// `offset` is applied. E.g. a monthly range anchored to [`CalendarAnchor::MonthStart`]
+/// starts on the first of the month of the first timestamp.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CalendarAnchor {
+    /// The start of the week, on the given weekday.
+    WeekStart(Weekday),
+    /// The first day of the month.
+    MonthStart,
+    /// The first day of January, April, July or October.
+    QuarterStart,
+    /// The first day of the year.
+    YearStart,
+}
+
+impl CalendarAnchor {
+    /// The latest anchor at or before the timestamp `t`, in local time if `tz` is given.
+    pub(super) fn snap(&self, t: i64, tu: TimeUnit, tz: Option<&Tz>) -> PolarsResult<i64> {
+        let truncate = |d: Duration, t| match tu {
+            TimeUnit::Nanoseconds => d.truncate_ns(t, tz),
+            TimeUnit::Microseconds => d.truncate_us(t, tz),
+            TimeUnit::Milliseconds => d.truncate_ms(t, tz),
+        };
+        let add = |d: Duration, t| match tu {
+            TimeUnit::Nanoseconds => d.add_ns(t, tz),
+            TimeUnit::Microseconds => d.add_us(t, tz),
+            TimeUnit::Milliseconds => d.add_ms(t, tz),
+        };
+        match self {
+            CalendarAnchor::WeekStart(weekday) => {
+                // Weeks are truncated to Monday.
+                let monday = truncate(Duration::from_weeks(1), t)?;
+                let days = weekday.num_days_from_monday() as i64;
+                let start = add(Duration::from_days(days), monday)?;
+                if start > t {
+                    add(Duration::from_weeks(1) * -1, start)
+                } else {
+                    Ok(start)
+                }
+            },
+            CalendarAnchor::MonthStart => truncate(Duration::from_months(1), t),
+            CalendarAnchor::QuarterStart => truncate(Duration::from_months(3), t),
+            CalendarAnchor::YearStart => truncate(Duration::from_months(12), t),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use arrow::legacy::time_zone::Tz;
+use arrow::temporal_conversions::MILLISECONDS_IN_DAY;
+use polars_core::prelude::*;
+use polars_core::series::IsSorted;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{extend_to_end, UpsampleOptions};
+use crate::prelude::*;
+use crate::windows::calendar::NS_DAY;
+
+/// The days that count for an upsample with a business day `every`, e.g. `"1bd"`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BusinessCalendar {
+    /// Which days of the week are business days, starting at Monday.
+    pub weekmask: [bool; 7],
+    /// Dates that are not business days, as the number of days since the UNIX epoch, i.e.
+    /// the physical representation of a `Date`.
+    pub holidays: Vec<i32>,
+}
+
+impl Default for BusinessCalendar {
+    fn default() -> Self {
+        BusinessCalendar {
+            weekmask: [true, true, true, true, true, false, false],
+            holidays: vec![],
+        }
+    }
+}
+
+impl BusinessCalendar {
+    fn is_business_day(&self, day: i64, holidays: &[i64]) -> bool {
+        // 1970-01-01 is a Thursday.
+        let weekday = (day + 3).rem_euclid(7) as usize;
+        self.weekmask[weekday] && holidays.binary_search(&day).is_err()
+    }
+}
+
+/// Create the range of business days of a `Date` or time zone naive `Datetime` index column.
+///
+/// The range starts at the first timestamp moved by `offset`, rolled forward to the next
+/// business day if it isn't one, and steps `every` business days, keeping the time of day.
+pub(super) fn business_day_range(
+    index_column: &Series,
+    every: Duration,
+    offset: Duration,
+    options: &UpsampleOptions,
+) -> PolarsResult<Series> {
+    polars_ensure!(
+        !every.negative && every.days() > 0,
+        ComputeError: "`every` must be positive"
+    );
+    polars_ensure!(
+        options.grid.anchor.is_none()
+            && options.grid.offset_fraction.is_none()
+            && !options.emit_window_bounds,
+        ComputeError: "upsample `anchor`, `offset_fraction` and `emit_window_bounds` cannot be combined with a business day `every`"
+    );
+    polars_ensure!(
+        !offset.parsed_int && !offset.business_days,
+        ComputeError: "upsample with a business day `every` requires `offset` to be a duration, e.g. '1d'"
+    );
+    let calendar = &options.grid.business_calendar;
+    polars_ensure!(
+        calendar.weekmask.iter().any(|&business| business),
+        ComputeError: "upsample business calendar must have at least one business day in its weekmask"
+    );
+    let mut holidays = calendar
+        .holidays
+        .iter()
+        .map(|&day| day as i64)
+        .collect::<Vec<_>>();
+    holidays.sort_unstable();
+
+    let (per_day, add): (i64, fn(&Duration, i64, Option<&Tz>) -> PolarsResult<i64>) =
+        match index_column.dtype() {
+            DataType::Date => {
+                polars_ensure!(
+                    offset.is_zero() || offset.is_full_days(),
+                    ComputeError: "upsample of a Date index column requires `offset` to be a whole number of days, e.g. '1d'"
+                );
+                (1, Duration::add_ms)
+            },
+            DataType::Datetime(tu, None) => match tu {
+                TimeUnit::Nanoseconds => (NS_DAY, Duration::add_ns),
+                TimeUnit::Microseconds => (NS_DAY / 1_000, Duration::add_us),
+                TimeUnit::Milliseconds => (MILLISECONDS_IN_DAY, Duration::add_ms),
+            },
+            dt => polars_bail!(
+                ComputeError: "upsample with a business day `every` requires a Date or time zone naive Datetime index column, got {}", dt
+            ),
+        };
+    let s = index_column.to_physical_repr().cast(&DataType::Int64)?;
+    let ca = s.i64()?;
+    let (Some(first), Some(last)) = (
+        ca.into_iter().flatten().next(),
+        ca.into_iter().flatten().next_back(),
+    ) else {
+        polars_bail!(ComputeError: "cannot determine upsample boundaries: all elements are null")
+    };
+    let last = extend_to_end(last, options.grid.end)?;
+    let first = match index_column.dtype() {
+        DataType::Date => {
+            add(&offset, first * MILLISECONDS_IN_DAY, None)?.div_euclid(MILLISECONDS_IN_DAY)
+        },
+        _ => add(&offset, first, None)?,
+    };
+
+    let (mut day, time_of_day) = (first.div_euclid(per_day), first.rem_euclid(per_day));
+    while !calendar.is_business_day(day, &holidays) {
+        day += 1;
+    }
+    let mut values = vec![];
+    while day * per_day + time_of_day <= last {
+        values.push(day * per_day + time_of_day);
+        for _ in 0..every.days() {
+            day += 1;
+            while !calendar.is_business_day(day, &holidays) {
+                day += 1;
+            }
+        }
+    }
+    if matches!(
+        options.grid.closed,
+        ClosedWindow::Right | ClosedWindow::None
+    ) && !values.is_empty()
+    {
+        values.remove(0);
+    }
+    if matches!(options.grid.closed, ClosedWindow::Left | ClosedWindow::None)
+        && values.last() == Some(&last)
+    {
+        values.pop();
+    }
+
+    let mut range = Int64Chunked::from_vec(index_column.name(), values)
+        .into_series()
+        .cast(index_column.dtype())?;
+    range.set_sorted_flag(IsSorted::Ascending);
+    Ok(range)
+}
@@ -0,0 +1,145 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use polars_core::prelude::*;
+use polars_core::utils::accumulate_dataframes_vertical;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{in_source_order, ROW_INDEX};
+
+/// Collapses the rows that share a timestamp into a single row.
+pub type DuplicateReducer = Arc<dyn Fn(DataFrame) -> PolarsResult<DataFrame> + Send + Sync>;
+
+/// How the rows of the source that share a timestamp (within a group) are handled by an
+/// upsample. Without collapsing them, the join onto the range would yield a row for every
+/// duplicate.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UpsampleDuplicates {
+    /// Raise an error.
+    #[default]
+    Raise,
+    /// Keep the first of the duplicated rows.
+    First,
+    /// Keep the last of the duplicated rows.
+    Last,
+    /// Take the mean of the numeric columns and the first value of the other columns.
+    Mean,
+    /// Collapse the rows with the reducer. It is called for every timestamp with the rows
+    /// of that timestamp, without the time column and the `by` columns, and must return a
+    /// single row.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(DuplicateReducer),
+}
+
+impl Debug for UpsampleDuplicates {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpsampleDuplicates::Raise => write!(f, "Raise"),
+            UpsampleDuplicates::First => write!(f, "First"),
+            UpsampleDuplicates::Last => write!(f, "Last"),
+            UpsampleDuplicates::Mean => write!(f, "Mean"),
+            UpsampleDuplicates::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+impl PartialEq for UpsampleDuplicates {
+    fn eq(&self, other: &Self) -> bool {
+        use UpsampleDuplicates::*;
+        match (self, other) {
+            (Raise, Raise) | (First, First) | (Last, Last) | (Mean, Mean) => true,
+            (Custom(l), Custom(r)) => Arc::ptr_eq(l, r),
+            _ => false,
+        }
+    }
+}
+
+/// Collapse the rows of `source` that share a timestamp. Returns `None` if there are no
+/// duplicates. The index column must be sorted.
+///
+/// A collapsed row keeps the [`ROW_INDEX`] of the first of its rows.
+pub(super) fn collapse_duplicates(
+    source: &DataFrame,
+    index_column: &Series,
+    by: &[String],
+    duplicates: &UpsampleDuplicates,
+) -> PolarsResult<Option<DataFrame>> {
+    let s = index_column.to_physical_repr().cast(&DataType::Int64)?;
+    let ca = s.i64()?;
+
+    // (offset, len) of the rows that share a timestamp.
+    let mut runs: Vec<(IdxSize, IdxSize)> = Vec::with_capacity(ca.len());
+    let mut prev = None;
+    for (i, t) in ca.into_iter().enumerate() {
+        match runs.last_mut() {
+            // Nulls are never joined onto the range, so they don't have to be collapsed.
+            Some((_, len)) if t.is_some() && prev == Some(t) => *len += 1,
+            _ => runs.push((i as IdxSize, 1)),
+        }
+        prev = Some(t);
+    }
+    if runs.len() == ca.len() {
+        return Ok(None);
+    }
+
+    let mean: &(dyn Fn(DataFrame) -> PolarsResult<DataFrame> + Send + Sync) = &mean_reducer;
+    let reducer = match duplicates {
+        UpsampleDuplicates::Raise => {
+            let (offset, _) = runs.iter().find(|(_, len)| *len > 1).unwrap();
+            polars_bail!(
+                ComputeError: "upsample source has duplicate timestamps in column '{}', e.g. {}; \
+                set the `duplicates` option to collapse them",
+                index_column.name(), index_column.get(*offset as usize)?
+            )
+        },
+        UpsampleDuplicates::First => {
+            let idx = runs.iter().map(|(offset, _)| *offset).collect();
+            return Ok(Some(source.take(&IdxCa::from_vec("", idx))?));
+        },
+        UpsampleDuplicates::Last => {
+            let idx = runs.iter().map(|(offset, len)| offset + len - 1).collect();
+            return Ok(Some(source.take(&IdxCa::from_vec("", idx))?));
+        },
+        UpsampleDuplicates::Mean => mean,
+        UpsampleDuplicates::Custom(reducer) => reducer.as_ref(),
+    };
+
+    let mut key_columns = vec![index_column.name(), ROW_INDEX];
+    key_columns.extend(by.iter().map(|s| s.as_str()));
+    let keys = source.select(&key_columns)?;
+    let values = source.drop_many(&key_columns);
+    let dfs = runs
+        .iter()
+        .map(|&(offset, len)| {
+            let reduced = reducer(values.slice(offset as i64, len as usize))?;
+            polars_ensure!(
+                reduced.height() == 1,
+                ComputeError: "upsample duplicates reducer must return a single row, got {} rows",
+                reduced.height()
+            );
+            let mut row = keys.slice(offset as i64, 1);
+            row.hstack_mut(reduced.get_columns())?;
+            Ok(row)
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let out = accumulate_dataframes_vertical(dfs)?;
+    // Restore the column order of the source, followed by the columns the reducer added.
+    in_source_order(source.get_column_names(), out).map(Some)
+}
+
+fn mean_reducer(df: DataFrame) -> PolarsResult<DataFrame> {
+    let columns = df
+        .get_columns()
+        .iter()
+        .map(|s| {
+            if s.dtype().is_numeric() {
+                s.mean_as_series()
+            } else {
+                s.head(Some(1))
+            }
+        })
+        .collect();
+    DataFrame::new(columns)
+}
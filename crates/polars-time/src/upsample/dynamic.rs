@@ -0,0 +1,203 @@
+use arrow::legacy::time_zone::Tz;
+use polars_core::prelude::*;
+use polars_core::series::IsSorted;
+
+#[cfg(feature = "timezones")]
+use super::parse_time_zone;
+use super::{PolarsUpsample, UpsampleOptions};
+use crate::group_by::dynamic::{LB_NAME, UP_NAME};
+use crate::prelude::*;
+
+/// Upsample the output of a [`PolarsTemporalGroupby::group_by_dynamic`] aggregation to all
+/// of its windows, so that the windows without rows get a row of nulls.
+///
+/// `agg` must have the window labels in `options.index_column`, sorted within the groups of
+/// `by`, and `options` must be the options of the `group_by_dynamic`. Every group is
+/// upsampled from its first up to its last window. If the boundaries of the windows were
+/// included, they are also set for the inserted windows. Errors for [`Label::DataPoint`], as
+/// these labels are not regular.
+pub fn upsample_dynamic_windows<I: IntoVec<String>>(
+    agg: &DataFrame,
+    by: I,
+    options: &DynamicGroupOptions,
+) -> PolarsResult<DataFrame> {
+    polars_ensure!(
+        !matches!(options.label, Label::DataPoint),
+        ComputeError: "cannot upsample windows that are labelled by a data point, use the left or right label"
+    );
+    let index_column = options.index_column.as_str();
+    let mut agg = agg.clone();
+    // The labels only have to be sorted within the groups, which the upsample checks.
+    agg.apply(index_column, |s| {
+        let mut s = s.clone();
+        s.set_sorted_flag(IsSorted::Ascending);
+        s
+    })?;
+    let upsample_options = UpsampleOptions {
+        maintain_order: true,
+        ..Default::default()
+    };
+    // The labels already include the offset of the windows.
+    let mut out = agg.upsample_with_options(
+        by,
+        index_column,
+        options.every,
+        Duration::new(0),
+        &upsample_options,
+    )?;
+    if options.include_boundaries {
+        fill_boundaries(&mut out, options)?;
+    }
+    Ok(out)
+}
+
+/// Set the window boundaries of the inserted windows, computed from their label.
+fn fill_boundaries(out: &mut DataFrame, options: &DynamicGroupOptions) -> PolarsResult<()> {
+    for name in [LB_NAME, UP_NAME] {
+        let Ok(boundary) = out.column(name) else {
+            continue;
+        };
+        let dtype = boundary.dtype().clone();
+        let add = boundary_add(&dtype)?;
+        let label = out.column(&options.index_column)?.cast(&dtype)?;
+        let label = label.to_physical_repr().cast(&DataType::Int64)?;
+        let known = boundary.to_physical_repr().cast(&DataType::Int64)?;
+        let filled = known
+            .i64()?
+            .into_iter()
+            .zip(label.i64()?)
+            .map(|(known, label)| match (known, label) {
+                (Some(known), _) => Ok(Some(known)),
+                (None, Some(label)) => {
+                    let start = match options.label {
+                        Label::Right => add(&(options.period * -1), label)?,
+                        _ => label,
+                    };
+                    Ok(Some(if name == LB_NAME {
+                        start
+                    } else {
+                        add(&options.period, start)?
+                    }))
+                },
+                (None, None) => Ok(None),
+            })
+            .collect::<PolarsResult<Int64Chunked>>()?;
+        let mut filled = filled.into_series().cast(&dtype)?;
+        filled.rename(name);
+        out.with_column(filled)?;
+    }
+    Ok(())
+}
+
+type BoundaryAdd = Box<dyn Fn(&Duration, i64) -> PolarsResult<i64>>;
+
+/// Add a duration to the physical value of a window boundary of `dtype`.
+fn boundary_add(dtype: &DataType) -> PolarsResult<BoundaryAdd> {
+    Ok(match dtype {
+        DataType::Datetime(tu, tz) => {
+            #[cfg(feature = "timezones")]
+            let tz = match tz {
+                Some(tz) => Some(parse_time_zone(tz)?),
+                None => None,
+            };
+            #[cfg(not(feature = "timezones"))]
+            let tz: Option<Tz> = {
+                let _ = tz;
+                None
+            };
+            let add: fn(&Duration, i64, Option<&Tz>) -> PolarsResult<i64> = match tu {
+                TimeUnit::Nanoseconds => Duration::add_ns,
+                TimeUnit::Microseconds => Duration::add_us,
+                TimeUnit::Milliseconds => Duration::add_ms,
+            };
+            Box::new(move |d, t| add(d, t, tz.as_ref()))
+        },
+        DataType::Int32 | DataType::Int64 => Box::new(|d, t| {
+            Ok(if d.negative {
+                t - d.nanoseconds()
+            } else {
+                t + d.nanoseconds()
+            })
+        }),
+        dt => polars_bail!(
+            ComputeError: "unexpected dtype {} of the window boundaries", dt
+        ),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    #[test]
+    fn test_upsample_dynamic_windows() -> PolarsResult<()> {
+        let hour = |h| {
+            NaiveDate::from_ymd_opt(2021, 12, 16)
+                .unwrap()
+                .and_hms_opt(h, 0, 0)
+                .unwrap()
+        };
+        let ms = |h| hour(h).timestamp_millis();
+        // The windows of 01:00, 03:00 and 04:00 are empty.
+        let mut df = df!(
+            "time" => [0, 0, 2, 5].map(hour),
+            "values" => &[1, 2, 3, 4],
+        )?;
+        df.apply("time", |s| {
+            let mut s = s.clone();
+            s.set_sorted_flag(IsSorted::Ascending);
+            s
+        })?;
+
+        for label in [Label::Left, Label::Right] {
+            let options = DynamicGroupOptions {
+                index_column: "time".into(),
+                every: Duration::parse("1h"),
+                period: Duration::parse("1h"),
+                offset: Duration::parse("0h"),
+                label,
+                include_boundaries: true,
+                closed_window: ClosedWindow::Left,
+                ..Default::default()
+            };
+            let (time_key, mut keys, groups) = df.group_by_dynamic(vec![], &options)?;
+            let sums = unsafe { df.column("values")?.agg_sum(&groups) };
+            keys.push(time_key);
+            keys.push(sums);
+            let agg = DataFrame::new(keys)?;
+            assert_eq!(agg.height(), 3);
+
+            let out = upsample_dynamic_windows::<[String; 0]>(&agg, [], &options)?;
+            let shift = if label == Label::Left { 0 } else { 1 };
+            let column = |name| -> PolarsResult<Vec<Option<i64>>> {
+                Ok(Vec::from(out.column(name)?.to_physical_repr().i64()?))
+            };
+            assert_eq!(
+                column("time")?,
+                (0..6).map(|h| Some(ms(h + shift))).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                column(LB_NAME)?,
+                (0..6).map(|h| Some(ms(h))).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                column(UP_NAME)?,
+                (1..7).map(|h| Some(ms(h))).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                Vec::from(out.column("values")?.i32()?),
+                &[Some(3), None, Some(3), None, None, Some(4)]
+            );
+        }
+
+        let options = DynamicGroupOptions {
+            index_column: "time".into(),
+            label: Label::DataPoint,
+            ..Default::default()
+        };
+        assert!(upsample_dynamic_windows::<[String; 0]>(&df, [], &options).is_err());
+        Ok(())
+    }
+}
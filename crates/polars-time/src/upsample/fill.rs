@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+
+use polars_core::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::join::duration_in_units;
+use super::ROW_INDEX;
+use crate::prelude::*;
+
+/// Strategy to fill the values of the rows inserted by an upsample.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UpsampleFill {
+    /// Leave the inserted rows null.
+    #[default]
+    Null,
+    /// Fill with the previous known value.
+    Forward,
+    /// Fill with the next known value.
+    Backward,
+    /// Interpolate linearly between the surrounding known values, weighted by
+    /// their distance in time. Decimal columns are interpolated at their scale, other
+    /// non-numeric columns are forward filled.
+    Linear,
+    /// Fill with the known value that is closest in time. Ties pick the previous value.
+    Nearest,
+    /// Interpolate like [`UpsampleFill::Linear`], for values that only grow (or only
+    /// shrink if `descending` is set), e.g. a running total. Errors if the known values of a
+    /// numeric column are not monotonic, so the filled values are guaranteed to be.
+    Monotonic { descending: bool },
+    /// Fill with the values of the last source row at or before the inserted row, taken by a
+    /// backward as-of join of the source onto the range rather than by a forward fill after
+    /// the join. Unlike [`UpsampleFill::Forward`], the nulls of that row are not filled from
+    /// earlier rows. Can't be combined with a join `tolerance` or with the `columns`, `limit`
+    /// or `max_gap` of [`UpsampleFillOptions`].
+    AsOfForward,
+}
+
+/// How a `by` column of the rows inserted by a grouped upsample is filled.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UpsampleByFill {
+    /// Fill the nulls of the `by` column with the previous value of the group. The rows
+    /// before the first row of the group have no previous value and stay null, e.g. to tell
+    /// them apart.
+    Forward,
+    /// Set the `by` column of every row to the key of the group.
+    #[default]
+    GroupKey,
+}
+
+/// How the rows inserted by an upsample are filled.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UpsampleFillOptions {
+    /// Fill the values of the inserted rows.
+    pub strategy: UpsampleFill,
+    /// Fill the values of these columns with their own strategy instead of `strategy`, e.g.
+    /// to forward fill a counter and interpolate a measurement in the same upsample.
+    pub columns: HashMap<String, UpsampleFill>,
+    /// Fill the `by` columns of the inserted rows.
+    pub by: UpsampleByFill,
+    /// Fill these `by` columns with their own strategy instead of `by`.
+    pub by_columns: HashMap<String, UpsampleByFill>,
+    /// Only fill an inserted row from a known value at most this many rows away. The
+    /// linear fill only interpolates gaps of at most this many rows. The other rows stay
+    /// null.
+    pub limit: Option<usize>,
+    /// Only interpolate between known values that are at most this far apart in time, e.g.
+    /// to leave an outage null. Applies to the linear and monotonic fills of numeric columns.
+    pub max_gap: Option<Duration>,
+    /// Remove the inserted rows in which all value columns are null, i.e. the rows that are
+    /// not filled by `strategy`. Rows of the source are kept.
+    pub drop_empty: bool,
+}
+
+impl UpsampleFillOptions {
+    /// The fill of the value column `name`.
+    pub(super) fn fill_of(&self, name: &str) -> UpsampleFill {
+        self.columns.get(name).copied().unwrap_or(self.strategy)
+    }
+
+    /// The fill of the `by` column `name`.
+    pub(super) fn by_fill_of(&self, name: &str) -> UpsampleByFill {
+        self.by_columns.get(name).copied().unwrap_or(self.by)
+    }
+
+    /// Ensure the columns of `by_columns` are `by` columns.
+    pub(super) fn check_by_columns(&self, by: &[String]) -> PolarsResult<()> {
+        for name in self.by_columns.keys() {
+            polars_ensure!(
+                by.contains(name),
+                ColumnNotFound: "upsample fill `by_columns` column '{}' is not a `by` column", name
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Fill the inserted rows of an upsampled [`DataFrame`], every column with its fill of
+/// `options`.
+///
+/// Only rows marked in `inserted` are written, nulls of the source are kept. With a limit,
+/// a row is only filled from a known value at most that many rows away, see
+/// [`UpsampleFillOptions::limit`].
+pub(super) fn fill_upsampled(
+    df: &mut DataFrame,
+    time_column: &str,
+    skip: &[String],
+    options: &UpsampleFillOptions,
+    inserted: &BooleanChunked,
+) -> PolarsResult<()> {
+    let nothing_to_fill = std::iter::once(&options.strategy)
+        .chain(options.columns.values())
+        .all(|fill| matches!(fill, UpsampleFill::Null | UpsampleFill::AsOfForward));
+    if nothing_to_fill {
+        return Ok(());
+    }
+    let time = df.column(time_column)?;
+    let max_gap = options
+        .max_gap
+        .map(|max_gap| duration_in_units(time.dtype(), max_gap, "max_gap"))
+        .transpose()?;
+    let time = time.to_physical_repr().cast(&DataType::Int64)?;
+    let time = time.i64()?.into_no_null_iter().collect::<Vec<_>>();
+    let inserted = inserted.into_no_null_iter().collect::<Vec<_>>();
+
+    let filled = df
+        .get_columns()
+        .iter()
+        .filter(|s| {
+            s.name() != time_column
+                && s.name() != ROW_INDEX
+                && !skip.iter().any(|name| name == s.name())
+        })
+        .map(|s| {
+            let fill = options.fill_of(s.name());
+            fill_column(s, &time, &inserted, fill, options.limit, max_gap)
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    for s in filled {
+        df.with_column(s)?;
+    }
+    Ok(())
+}
+
+fn fill_column(
+    s: &Series,
+    time: &[i64],
+    inserted: &[bool],
+    fill: UpsampleFill,
+    limit: Option<usize>,
+    max_gap: Option<i64>,
+) -> PolarsResult<Series> {
+    match fill {
+        // The as-of join already filled the inserted rows.
+        UpsampleFill::Null | UpsampleFill::AsOfForward => Ok(s.clone()),
+        UpsampleFill::Linear if s.dtype().is_numeric() => {
+            interpolate_linear(s, time, inserted, limit, max_gap)
+        },
+        #[cfg(feature = "dtype-decimal")]
+        UpsampleFill::Linear if s.dtype().is_decimal() => {
+            interpolate_decimal(s, time, inserted, limit, max_gap)
+        },
+        UpsampleFill::Monotonic { descending }
+            if s.dtype().is_numeric() || s.dtype().is_decimal() =>
+        {
+            check_monotonic(s, descending)?;
+            fill_column(s, time, inserted, UpsampleFill::Linear, limit, max_gap)
+        },
+        UpsampleFill::Linear | UpsampleFill::Monotonic { .. } => s.take(&fill_indices(
+            s,
+            time,
+            inserted,
+            UpsampleFill::Forward,
+            limit,
+        )),
+        _ => s.take(&fill_indices(s, time, inserted, fill, limit)),
+    }
+}
+
+/// Check that the non-null values of `s` are non-decreasing, or non-increasing if
+/// `descending` is set.
+fn check_monotonic(s: &Series, descending: bool) -> PolarsResult<()> {
+    let values = s.cast(&DataType::Float64)?;
+    let mut known = values.f64()?.into_iter().flatten();
+    let Some(mut prev) = known.next() else {
+        return Ok(());
+    };
+    for v in known {
+        let ordered = if descending { v <= prev } else { v >= prev };
+        polars_ensure!(
+            ordered,
+            ComputeError: "upsample `Monotonic` fill requires the known values of column '{}' to be {}, \
+            but {} is followed by {}",
+            s.name(), if descending { "non-increasing" } else { "non-decreasing" }, prev, v
+        );
+        prev = v;
+    }
+    Ok(())
+}
+
+/// Whether a row `distance` rows away from a known value may be filled from it.
+#[inline]
+fn within_limit(distance: usize, limit: Option<usize>) -> bool {
+    limit.map_or(true, |limit| distance <= limit)
+}
+
+/// Whether the known values at `start` and `end` are close enough in time to interpolate
+/// between them, see [`UpsampleFillOptions::max_gap`].
+fn within_gap(start: i64, end: i64, max_gap: Option<i64>) -> bool {
+    max_gap.map_or(true, |max_gap| (end - start).abs() <= max_gap)
+}
+
+/// Fill the nulls of `s` with the previous known value. Unlike [`Series::fill_null`] this
+/// works for nested dtypes, e.g. a struct key of a grouped upsample.
+pub(super) fn fill_forward(s: &Series) -> PolarsResult<Series> {
+    let valid = known_values(s);
+    let idx = previous_known(&valid)
+        .into_iter()
+        .map(|i| i.map(|i| i as IdxSize))
+        .collect();
+    s.take(&idx)
+}
+
+/// Whether every row of `s` holds a value. A struct row counts as known if any of its fields
+/// is, only the rows inserted by the join are null in all fields.
+fn known_values(s: &Series) -> Vec<bool> {
+    (!s.is_null()).into_no_null_iter().collect()
+}
+
+/// The previous known value for every row.
+fn previous_known(valid: &[bool]) -> Vec<Option<usize>> {
+    let mut last = None;
+    valid
+        .iter()
+        .enumerate()
+        .map(|(i, valid)| {
+            if *valid {
+                last = Some(i)
+            }
+            last
+        })
+        .collect()
+}
+
+/// Compute the gather indices that fill the inserted rows of `s`.
+fn fill_indices(
+    s: &Series,
+    time: &[i64],
+    inserted: &[bool],
+    fill: UpsampleFill,
+    limit: Option<usize>,
+) -> IdxCa {
+    let valid = known_values(s);
+    let len = valid.len();
+
+    // Previous and next known value for every row.
+    let prev = previous_known(&valid);
+    let mut next = vec![None; len];
+    let mut last = None;
+    for (i, valid) in valid.iter().enumerate().rev() {
+        if *valid {
+            last = Some(i)
+        }
+        next[i] = last;
+    }
+
+    (0..len)
+        .map(|i| {
+            let prev = prev[i].filter(|p| within_limit(i - p, limit));
+            let next = next[i].filter(|n| within_limit(n - i, limit));
+            let src = if !inserted[i] {
+                Some(i)
+            } else {
+                match fill {
+                    UpsampleFill::Forward => prev,
+                    UpsampleFill::Backward => next,
+                    UpsampleFill::Nearest => match (prev, next) {
+                        (Some(p), Some(n)) => {
+                            if time[n] - time[i] < time[i] - time[p] {
+                                Some(n)
+                            } else {
+                                Some(p)
+                            }
+                        },
+                        (p, n) => p.or(n),
+                    },
+                    UpsampleFill::Null
+                    | UpsampleFill::Linear
+                    | UpsampleFill::Monotonic { .. }
+                    | UpsampleFill::AsOfForward => unreachable!(),
+                }
+            };
+            src.map(|i| i as IdxSize)
+        })
+        .collect()
+}
+
+/// [`interpolate_linear`] of the integers underlying a decimal column, so that its scale is
+/// kept. The interpolated values are rounded half up at the scale.
+#[cfg(feature = "dtype-decimal")]
+fn interpolate_decimal(
+    s: &Series,
+    time: &[i64],
+    inserted: &[bool],
+    limit: Option<usize>,
+    max_gap: Option<i64>,
+) -> PolarsResult<Series> {
+    let ca = s.decimal()?;
+    let mut out = ca.0.into_iter().collect::<Vec<_>>();
+
+    let mut prev: Option<usize> = None;
+    for i in 0..out.len() {
+        if let Some(end) = out[i] {
+            if let Some(p) = prev.filter(|&p| {
+                within_limit(i - p - 1, limit) && within_gap(time[p], time[i], max_gap)
+            }) {
+                let start = out[p].unwrap();
+                let span = (time[i] - time[p]) as i128;
+                for j in p + 1..i {
+                    if inserted[j] {
+                        let step = (end - start) * (time[j] - time[p]) as i128;
+                        let rounded =
+                            step.div_euclid(span) + (2 * step.rem_euclid(span) >= span) as i128;
+                        out[j] = Some(start + rounded);
+                    }
+                }
+            }
+            prev = Some(i);
+        }
+    }
+
+    let mut out: Int128Chunked = out.into_iter().collect();
+    out.rename(s.name());
+    Ok(out
+        .into_decimal_unchecked(ca.precision(), ca.scale())
+        .into_series())
+}
+
+/// Linear interpolation that takes the time between the known values into account,
+/// so irregular sources are interpolated correctly. Gaps of more than `limit` rows or longer
+/// than `max_gap` are not interpolated.
+fn interpolate_linear(
+    s: &Series,
+    time: &[i64],
+    inserted: &[bool],
+    limit: Option<usize>,
+    max_gap: Option<i64>,
+) -> PolarsResult<Series> {
+    let values = s.cast(&DataType::Float64)?;
+    let mut out = values.f64()?.into_iter().collect::<Vec<_>>();
+
+    let mut prev: Option<usize> = None;
+    for i in 0..out.len() {
+        if let Some(end) = out[i] {
+            if let Some(p) = prev.filter(|&p| {
+                within_limit(i - p - 1, limit) && within_gap(time[p], time[i], max_gap)
+            }) {
+                let start = out[p].unwrap();
+                let span = (time[i] - time[p]) as f64;
+                for j in p + 1..i {
+                    if inserted[j] {
+                        let fraction = (time[j] - time[p]) as f64 / span;
+                        out[j] = Some(start + (end - start) * fraction);
+                    }
+                }
+            }
+            prev = Some(i);
+        }
+    }
+
+    let mut ca: Float64Chunked = out.into_iter().collect();
+    ca.rename(s.name());
+    match s.dtype() {
+        DataType::Float32 => ca.cast(&DataType::Float32),
+        _ => Ok(ca.into_series()),
+    }
+}
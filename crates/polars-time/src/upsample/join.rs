@@ -0,0 +1,252 @@
+use polars_core::prelude::*;
+use polars_core::series::IsSorted;
+use polars_ops::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{UpsampleUnaligned, ROW_INDEX};
+use crate::prelude::*;
+
+/// The copy of the index column of the source that an as-of join keeps on the output.
+const ASOF_TIME: &str = "__POLARS_UPSAMPLE_ASOF_TIME";
+
+/// The algorithm of the left join of the source onto the upsample range.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UpsampleJoin {
+    /// Hash join the source onto the range.
+    #[default]
+    Hash,
+    /// Merge the source into the range, making use of both being sorted. Falls back to the
+    /// hash join if the range is not strictly increasing.
+    SortedMerge,
+}
+
+/// Which row of the source is joined onto a point of the range of an upsample with a
+/// `tolerance`, if several rows are within the tolerance of the point.
+///
+/// Without a tolerance, rows only collide if they have the same timestamp, which is handled
+/// by [`UpsampleDuplicates`](super::UpsampleDuplicates) and raises by default.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CollisionPolicy {
+    /// The row closest to the point, the later row on a tie.
+    #[default]
+    Closest,
+    /// The earliest row within the tolerance.
+    First,
+    /// The latest row within the tolerance.
+    Last,
+}
+
+/// How the source is joined onto the range of an upsample.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UpsampleJoinOptions {
+    /// The algorithm of the join of the source onto the range.
+    pub algorithm: UpsampleJoin,
+    /// How rows of the source that don't land on a point of the range are handled.
+    pub on_unaligned: UpsampleUnaligned,
+    /// Join the nearest row of the source within this tolerance onto every point of the
+    /// range, instead of the row with the exact timestamp, e.g. to snap timestamps with
+    /// jitter onto the range. The other rows of the source are dropped, and a row may be
+    /// joined onto two points if the tolerance is at least half of `every`. Requires
+    /// `on_unaligned` to be [`UpsampleUnaligned::Drop`]; `algorithm` doesn't apply.
+    pub tolerance: Option<Duration>,
+    /// Which row is joined onto a point if several rows are within the `tolerance`.
+    pub on_collision: CollisionPolicy,
+}
+
+/// Left join `source` onto `range` on the index column.
+///
+/// The index column of `source` must be sorted ascending, and every non-null timestamp must
+/// occur only once.
+pub(super) fn join_range(
+    range: Series,
+    source: &DataFrame,
+    join: UpsampleJoin,
+) -> PolarsResult<DataFrame> {
+    let name = range.name().to_string();
+    if let UpsampleJoin::SortedMerge = join {
+        if let Some(idx) = merge_indices(&range, source.column(&name)?)? {
+            let mut out = range.into_frame();
+            out.hstack_mut(source.drop(&name)?.take(&idx)?.get_columns())?;
+            return Ok(out);
+        }
+    }
+    range
+        .into_frame()
+        .join(source, [&name], [&name], JoinArgs::new(JoinType::Left))
+}
+
+/// Join the nearest row of `source` within `tolerance` onto every point of `range`, see
+/// [`UpsampleJoinOptions::tolerance`].
+///
+/// The index column of `source` must be sorted ascending.
+pub(super) fn join_range_nearest(
+    range: Series,
+    source: &DataFrame,
+    tolerance: Duration,
+    on_collision: CollisionPolicy,
+) -> PolarsResult<DataFrame> {
+    let name = range.name().to_string();
+    let tolerance = duration_in_units(range.dtype(), tolerance, "tolerance")?;
+    let index_column = source.column(&name)?;
+    let mut source = source.filter(&index_column.is_not_null())?;
+    if let CollisionPolicy::First | CollisionPolicy::Last = on_collision {
+        let last = matches!(on_collision, CollisionPolicy::Last);
+        let idx = window_indices(&range, source.column(&name)?, tolerance, last)?;
+        let mut out = range.into_frame();
+        out.hstack_mut(source.drop(&name)?.take(&idx)?.get_columns())?;
+        return Ok(out);
+    }
+    source.apply(&name, |s| {
+        let mut s = s.clone();
+        s.set_sorted_flag(IsSorted::Ascending);
+        s
+    })?;
+    range.into_frame().join_asof(
+        &source,
+        &name,
+        &name,
+        AsofStrategy::Nearest,
+        Some(AnyValue::Int64(tolerance)),
+        None,
+    )
+}
+
+/// Join the last row of `source` at or before every point of `range`, see
+/// [`UpsampleFill::AsOfForward`](super::UpsampleFill::AsOfForward).
+///
+/// The [`ROW_INDEX`] of a point that is joined onto an earlier row is set to null, so the
+/// point counts as inserted. The index column of `source` must be sorted ascending.
+pub(super) fn join_range_backward(range: Series, source: &DataFrame) -> PolarsResult<DataFrame> {
+    let name = range.name().to_string();
+    let index_column = source.column(&name)?;
+    let mut source = source.filter(&index_column.is_not_null())?;
+    source.apply(&name, |s| {
+        let mut s = s.clone();
+        s.set_sorted_flag(IsSorted::Ascending);
+        s
+    })?;
+    // The timestamp of the joined row tells the exact matches from the filled points.
+    let mut matched = source.column(&name)?.clone();
+    matched.rename(ASOF_TIME);
+    source.with_column(matched)?;
+    let mut out =
+        range
+            .into_frame()
+            .join_asof(&source, &name, &name, AsofStrategy::Backward, None, None)?;
+    let matched = out.drop_in_place(ASOF_TIME)?;
+    let exact = matched.equal_missing(out.column(&name)?)?;
+    let row_index = out.column(ROW_INDEX)?.idx()?;
+    let mut row_index: IdxCa = row_index
+        .into_iter()
+        .zip(exact.into_no_null_iter())
+        .map(|(i, exact)| i.filter(|_| exact))
+        .collect();
+    row_index.rename(ROW_INDEX);
+    out.with_column(row_index)?;
+    Ok(out)
+}
+
+/// The first, or the `last`, row of the non-null sorted `index_column` within `tolerance` of
+/// every point of the sorted `range`.
+fn window_indices(
+    range: &Series,
+    index_column: &Series,
+    tolerance: i64,
+    last: bool,
+) -> PolarsResult<IdxCa> {
+    let range = range.to_physical_repr().cast(&DataType::Int64)?;
+    let s = index_column
+        .to_physical_repr()
+        .cast(&DataType::Int64)?
+        .rechunk();
+    let values = s.i64()?.cont_slice()?;
+    let (mut lo, mut hi) = (0, 0);
+    let mut idx = Vec::with_capacity(range.len());
+    for t in range.i64()?.into_no_null_iter() {
+        while lo < values.len() && values[lo] < t.saturating_sub(tolerance) {
+            lo += 1;
+        }
+        hi = hi.max(lo);
+        while hi < values.len() && values[hi] <= t.saturating_add(tolerance) {
+            hi += 1;
+        }
+        idx.push((lo < hi).then(|| (if last { hi - 1 } else { lo }) as IdxSize));
+    }
+    Ok(idx.into_iter().collect())
+}
+
+/// Express the duration of the upsample option `option` in the physical unit of an index
+/// column of `dtype`.
+pub(super) fn duration_in_units(
+    dtype: &DataType,
+    duration: Duration,
+    option: &str,
+) -> PolarsResult<i64> {
+    polars_ensure!(
+        !duration.negative,
+        ComputeError: "upsample `{}` must not be negative", option
+    );
+    polars_ensure!(
+        !duration.business_days,
+        ComputeError: "upsample `{}` cannot be a number of business days", option
+    );
+    let units = match dtype {
+        DataType::Datetime(tu, _) if !duration.parsed_int && duration.months() == 0 => {
+            let ns = duration.duration_ns();
+            match tu {
+                TimeUnit::Nanoseconds => ns,
+                TimeUnit::Microseconds => ns / 1_000,
+                TimeUnit::Milliseconds => ns / 1_000_000,
+            }
+        },
+        DataType::Date
+            if !duration.parsed_int && duration.months() == 0 && duration.is_full_days() =>
+        {
+            duration.weeks() * 7 + duration.days()
+        },
+        DataType::Time if !duration.parsed_int && duration.is_constant_duration() => {
+            duration.nanoseconds()
+        },
+        DataType::Int32 | DataType::Int64 | DataType::UInt64
+            if duration.parsed_int && duration.is_constant_duration() =>
+        {
+            duration.nanoseconds()
+        },
+        dt => polars_bail!(
+            ComputeError: "upsample `{}` doesn't fit an index column of dtype {}; use a \
+            fixed duration, whole days for a Date and an index count for an integer column",
+            option, dt
+        ),
+    };
+    Ok(units)
+}
+
+/// The row of `index_column` that matches every point of `range`. Returns `None` if `range`
+/// is not strictly increasing.
+fn merge_indices(range: &Series, index_column: &Series) -> PolarsResult<Option<IdxCa>> {
+    let range = range.to_physical_repr().cast(&DataType::Int64)?;
+    let s = index_column.to_physical_repr().cast(&DataType::Int64)?;
+    let range = range.i64()?;
+    let ca = s.i64()?;
+
+    let mut source = ca
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, t)| Some((t?, i as IdxSize)))
+        .peekable();
+    let mut idx = Vec::with_capacity(range.len());
+    let mut prev = None;
+    for t in range.into_no_null_iter() {
+        if prev.map_or(false, |prev| t <= prev) {
+            return Ok(None);
+        }
+        prev = Some(t);
+        while source.next_if(|(s, _)| *s < t).is_some() {}
+        idx.push(source.next_if(|(s, _)| *s == t).map(|(_, i)| i));
+    }
+    Ok(Some(idx.into_iter().collect()))
+}
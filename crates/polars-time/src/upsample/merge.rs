@@ -0,0 +1,57 @@
+use polars_core::prelude::*;
+
+use super::{upsample_range, PolarsUpsample, UpsampleOptions};
+use crate::prelude::*;
+
+/// Upsample every frame of `frames` onto a shared regular range and coalesce their columns,
+/// i.e. take the first non-null value of a column in the order of `frames`.
+///
+/// The range spans from the first to the last timestamp of `time_column` over all frames,
+/// which must have the same dtype. A column that occurs in several frames must have the same
+/// dtype in all of them. The columns follow `time_column` in order of first occurrence.
+pub fn merge_upsampled(
+    frames: &[DataFrame],
+    time_column: &str,
+    every: Duration,
+    offset: Duration,
+) -> PolarsResult<DataFrame> {
+    polars_ensure!(
+        !frames.is_empty(),
+        ComputeError: "'merge_upsampled' needs at least one frame"
+    );
+    let mut index = frames[0].column(time_column)?.clone();
+    for df in &frames[1..] {
+        index.append(df.column(time_column)?)?;
+    }
+    let index = index.drop_nulls().sort(false);
+    let grid = upsample_range(&index, every, offset, &UpsampleOptions::default())?;
+
+    let mut columns = vec![grid.clone()];
+    for df in frames {
+        let upsampled = df.upsample_to_grid::<[String; 0]>([], time_column, &grid)?;
+        for s in upsampled.get_columns() {
+            if s.name() == time_column {
+                continue;
+            }
+            match columns.iter_mut().find(|column| column.name() == s.name()) {
+                Some(column) => *column = coalesce(column, s)?,
+                None => columns.push(s.clone()),
+            }
+        }
+    }
+    DataFrame::new(columns)
+}
+
+/// Take the values of `a`, and those of `b` where `a` is null.
+fn coalesce(a: &Series, b: &Series) -> PolarsResult<Series> {
+    let len = a.len();
+    let idx = a
+        .is_null()
+        .into_no_null_iter()
+        .enumerate()
+        .map(|(i, null)| (if null { len + i } else { i }) as IdxSize)
+        .collect::<Vec<_>>();
+    let mut both = a.clone();
+    both.append(b)?;
+    both.take(&IdxCa::from_vec("", idx))
+}
@@ -0,0 +1,5036 @@
+mod align;
+mod anchor;
+mod business;
+mod duplicates;
+#[cfg(any(feature = "dtype-date", feature = "dtype-datetime"))]
+mod dynamic;
+mod fill;
+mod join;
+mod merge;
+mod multi;
+mod tz;
+
+use std::borrow::Cow;
+use std::sync::Mutex;
+
+use align::unaligned_rows;
+pub use align::UpsampleUnaligned;
+pub use anchor::CalendarAnchor;
+use arrow::temporal_conversions::MILLISECONDS_IN_DAY;
+use business::business_day_range;
+pub use business::BusinessCalendar;
+#[cfg(feature = "timezones")]
+use chrono_tz::Tz;
+use duplicates::collapse_duplicates;
+pub use duplicates::{DuplicateReducer, UpsampleDuplicates};
+#[cfg(any(feature = "dtype-date", feature = "dtype-datetime"))]
+pub use dynamic::upsample_dynamic_windows;
+use fill::{fill_forward, fill_upsampled};
+pub use fill::{UpsampleByFill, UpsampleFill, UpsampleFillOptions};
+use join::{join_range, join_range_backward, join_range_nearest};
+pub use join::{CollisionPolicy, UpsampleJoin, UpsampleJoinOptions};
+pub use merge::merge_upsampled;
+use multi::upsample_multi_impl;
+use polars_core::frame::group_by::GroupsIndicator;
+use polars_core::frame::row::Row;
+use polars_core::prelude::*;
+use polars_core::series::IsSorted;
+use polars_core::utils::ensure_sorted_arg;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "timezones")]
+use tz::{fixed_offset_shift, upsample_range_tz};
+pub use tz::{UpsampleAmbiguous, UpsampleNonExistent};
+
+use crate::prelude::*;
+use crate::resample::index_deltas;
+#[cfg(feature = "timezones")]
+use crate::utils::unlocalize_timestamp;
+use crate::windows::calendar::NS_DAY;
+
+const ROW_INDEX: &str = "__POLARS_UPSAMPLE_ROW_INDEX";
+/// Groups larger than this are never upsampled in parallel.
+const MAX_PARALLEL_GROUP_LEN: usize = 1 << 14;
+/// The deltas of an index with a larger coefficient of variation are too irregular to infer
+/// the `every` of [`PolarsUpsample::upsample_auto`].
+const MAX_AUTO_EVERY_VARIATION: f64 = 1.0;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UpsampleOptions {
+    /// The range the source is upsampled onto.
+    pub grid: UpsampleGridOptions,
+    /// Fill the inserted rows.
+    pub fill: UpsampleFillOptions,
+    /// Join the source onto the range.
+    pub join: UpsampleJoinOptions,
+    /// How rows of the source that share a timestamp within a group are handled.
+    pub duplicates: UpsampleDuplicates,
+    /// Maintain the order of the groups.
+    pub maintain_order: bool,
+    /// Order the blocks of the groups by their key instead, ascending with the nulls first.
+    /// Takes precedence over `maintain_order`.
+    pub sort_groups: bool,
+    /// Upsample the groups in parallel. This is only done if all groups are small,
+    /// as upsampling large groups in parallel may overflow the stack.
+    pub parallel: bool,
+    /// Return zero rows instead of raising if the index column (of a group) only contains
+    /// nulls. An empty frame always results in zero rows.
+    pub empty_on_all_null: bool,
+    /// Append a boolean column that is `true` for the inserted rows and `false` for the
+    /// rows of the source.
+    pub indicator: bool,
+    /// Name of the indicator column.
+    pub indicator_name: String,
+    /// Append a column with the index of the row of the source every row comes from, and
+    /// null for the inserted rows. It is appended after the indicator column.
+    pub with_source_index: bool,
+    /// Name of the source index column.
+    pub source_index_name: String,
+    /// Maximum number of rows of the grid of [`PolarsUpsample::upsample_multi`].
+    pub max_cells: usize,
+    /// Only upsample these value columns, besides the index and `by` columns. The other
+    /// columns of the source are dropped before the join, which is cheaper for wide frames.
+    pub columns: Option<Vec<String>>,
+    /// Append the `{time_column}_start` and `{time_column}_end` columns with the interval
+    /// of every row: its timestamp and the timestamp plus `every`. Calendar durations follow
+    /// the length of the interval, e.g. the month, so the end of the last interval is the
+    /// start of the next one. Requires a range with a fixed `every`.
+    pub emit_window_bounds: bool,
+}
+
+impl Default for UpsampleOptions {
+    fn default() -> Self {
+        Self {
+            grid: UpsampleGridOptions::default(),
+            fill: UpsampleFillOptions::default(),
+            join: UpsampleJoinOptions::default(),
+            duplicates: UpsampleDuplicates::Raise,
+            maintain_order: false,
+            sort_groups: false,
+            parallel: true,
+            empty_on_all_null: false,
+            indicator: false,
+            indicator_name: "_upsampled".to_string(),
+            with_source_index: false,
+            source_index_name: "_source_index".to_string(),
+            max_cells: 1 << 28,
+            columns: None,
+            emit_window_bounds: false,
+        }
+    }
+}
+
+/// The range of an upsample, besides its `every` and `offset`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UpsampleGridOptions {
+    /// Extend the range up to this boundary, even if the index column ends before it.
+    /// It is expressed in the physical representation of the index column, e.g. the
+    /// timestamp in the time unit of a `Datetime` column, and must not be before the
+    /// last element of the index column.
+    pub end: Option<i64>,
+    /// Which boundaries of the range are included.
+    pub closed: ClosedWindow,
+    /// Round an `every` that is finer than the time unit of a `Datetime` index column up to
+    /// the time unit, instead of raising.
+    pub round_every: bool,
+    /// Move every point of the range by this fraction of `every`, e.g. `0.5` for the middle
+    /// of the interval. It is resolved per interval, so for calendar durations the shift
+    /// depends on the length of e.g. the month. It adds to `offset`, which first moves the
+    /// start of the range. Must be in the range `[0, 1)`.
+    pub offset_fraction: Option<f64>,
+    /// Snap the start of the range back to a calendar boundary, before `offset` is applied.
+    /// Requires a `Date` or `Datetime` index column.
+    pub anchor: Option<CalendarAnchor>,
+    /// Add the first and last timestamp of the index column (of a group) to the range, so
+    /// that these rows are kept even if e.g. `offset` moves the range off them. The range
+    /// is then no longer regular at its boundaries.
+    pub preserve_endpoints: bool,
+    /// Upsample every group onto one range, from the earliest to the latest timestamp of
+    /// all groups, instead of a range per group. The groups then share their timestamps,
+    /// e.g. for a pivot, with null rows where a group has no data. The `by` columns of the
+    /// inserted rows are set to the key of the group, as with [`UpsampleByFill::GroupKey`].
+    pub shared: bool,
+    /// How ambiguous local times of the range of a time zone aware index are handled.
+    pub ambiguous: UpsampleAmbiguous,
+    /// How non-existent local times of the range of a time zone aware index are handled.
+    pub non_existent: UpsampleNonExistent,
+    /// The business days of a range with a business day `every`, e.g. `"1bd"`. Defaults to
+    /// Monday to Friday without holidays.
+    pub business_calendar: BusinessCalendar,
+}
+
+impl Default for UpsampleGridOptions {
+    fn default() -> Self {
+        Self {
+            end: None,
+            closed: ClosedWindow::Both,
+            round_every: false,
+            offset_fraction: None,
+            anchor: None,
+            preserve_endpoints: false,
+            shared: false,
+            ambiguous: UpsampleAmbiguous::Earliest,
+            non_existent: UpsampleNonExistent::Skip,
+            business_calendar: BusinessCalendar::default(),
+        }
+    }
+}
+
+/// Row counts of an upsample.
+#[derive(Clone, Debug, Default)]
+pub struct UpsampleReport {
+    /// Number of output rows that originate from the source.
+    pub original_rows: usize,
+    /// Number of output rows inserted by the upsample.
+    pub inserted_rows: usize,
+    /// The `by` keys with the `original_rows` and `inserted_rows` of every group.
+    /// Only set if the upsample was done per group.
+    pub per_group: Option<DataFrame>,
+}
+
+pub trait PolarsUpsample {
+    /// Upsample a [`DataFrame`] at a regular frequency.
+    ///
+    /// # Arguments
+    /// * `by` - First group by these columns and then upsample for every group
+    /// * `time_column` - Will be used to determine a date_range.
+    ///                   Note that this column has to be sorted for the output to make sense.
+    ///                   If it is flagged as sorted descending, the output is descending as
+    ///                   well; the range is still determined from the earliest timestamp.
+    ///                   Integer columns are upsampled with an index count `every`, e.g. "1i".
+    ///                   The range is computed in Int64, so UInt64 values above `i64::MAX`
+    ///                   raise an error.
+    ///                   Time columns are upsampled within a single day.
+    /// * `every` - interval will start 'every' duration
+    /// * `offset` - change the start of the date_range by this offset. A negative offset
+    ///              starts the range earlier in whole steps of `every`, so that the first
+    ///              element of `time_column` stays on the range.
+    ///
+    /// The `every` and `offset` arguments are created with
+    /// the following string language:
+    /// - 1ns   (1 nanosecond)
+    /// - 1us   (1 microsecond)
+    /// - 1ms   (1 millisecond)
+    /// - 1s    (1 second)
+    /// - 1m    (1 minute)
+    /// - 1h    (1 hour)
+    /// - 1d    (1 calendar day)
+    /// - 1w    (1 calendar week)
+    /// - 1mo   (1 calendar month)
+    /// - 1q    (1 calendar quarter)
+    /// - 1y    (1 calendar year)
+    /// - 1i    (1 index count)
+    ///
+    /// Or combine them:
+    /// "3d12h4m25s" # 3 days, 12 hours, 4 minutes, and 25 seconds
+    ///
+    /// By "calendar day", we mean the corresponding time on the next
+    /// day (which may not be 24 hours, depending on daylight savings).
+    /// Similarly for "calendar week", "calendar month", "calendar quarter",
+    /// and "calendar year".
+    ///
+    /// For a time zone aware `time_column`, a combined `every` first adds its calendar part
+    /// in local time and then its clock part in elapsed time. It raises if the clock part of
+    /// a step crosses a daylight saving time transition.
+    fn upsample<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+    ) -> PolarsResult<DataFrame>;
+
+    /// Upsample a DataFrame at a regular frequency.
+    ///
+    /// # Arguments
+    /// * `by` - First group by these columns and then upsample for every group
+    /// * `time_column` - Will be used to determine a date_range.
+    ///                   Note that this column has to be sorted for the output to make sense.
+    ///                   Integer columns are upsampled with an index count `every`, e.g. "1i".
+    ///                   The range is computed in Int64, so UInt64 values above `i64::MAX`
+    ///                   raise an error.
+    /// * `every` - interval will start 'every' duration
+    /// * `offset` - change the start of the date_range by this offset.
+    ///
+    /// The `every` and `offset` arguments are created with
+    /// the following string language:
+    /// - 1ns   (1 nanosecond)
+    /// - 1us   (1 microsecond)
+    /// - 1ms   (1 millisecond)
+    /// - 1s    (1 second)
+    /// - 1m    (1 minute)
+    /// - 1h    (1 hour)
+    /// - 1d    (1 calendar day)
+    /// - 1w    (1 calendar week)
+    /// - 1mo   (1 calendar month)
+    /// - 1q    (1 calendar quarter)
+    /// - 1y    (1 calendar year)
+    /// - 1i    (1 index count)
+    ///
+    /// Or combine them:
+    /// "3d12h4m25s" # 3 days, 12 hours, 4 minutes, and 25 seconds
+    ///
+    /// By "calendar day", we mean the corresponding time on the next
+    /// day (which may not be 24 hours, depending on daylight savings).
+    /// Similarly for "calendar week", "calendar month", "calendar quarter",
+    /// and "calendar year".
+    fn upsample_stable<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+    ) -> PolarsResult<DataFrame>;
+
+    /// Upsample a [`DataFrame`] at a regular frequency with extra options.
+    ///
+    /// See [`PolarsUpsample::upsample`] for the arguments and [`UpsampleOptions`]
+    /// for the options.
+    fn upsample_with_options<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        options: &UpsampleOptions,
+    ) -> PolarsResult<DataFrame>;
+
+    /// Upsample a [`DataFrame`] at a regular frequency and report how many rows were
+    /// inserted.
+    ///
+    /// See [`PolarsUpsample::upsample_with_options`].
+    fn upsample_with_report<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        options: &UpsampleOptions,
+    ) -> PolarsResult<(DataFrame, UpsampleReport)>;
+
+    /// Upsample a [`DataFrame`] onto the regular grid of multiple time columns.
+    ///
+    /// A range is created for every column of `time_columns` with the `every` and `offset`
+    /// at the same position. The ranges span from the minimum to the maximum of their
+    /// column, so the time columns don't have to be sorted. The source is left joined onto
+    /// all combinations of the ranges.
+    ///
+    /// Errors if the grid of a group has more than `options.max_cells` rows. The `by` columns
+    /// are always set to the key of the group, while `options.fill.strategy` and
+    /// `options.grid.end` are not supported.
+    fn upsample_multi<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_columns: &[&str],
+        every: &[Duration],
+        offset: &[Duration],
+        options: &UpsampleOptions,
+    ) -> PolarsResult<DataFrame>;
+
+    /// Upsample a [`DataFrame`] onto the timestamps of `grid`, e.g. the business days of a
+    /// trading calendar, instead of onto a regular range.
+    ///
+    /// `grid` must be strictly increasing, without nulls, and have the dtype of `time_column`.
+    /// Every group is joined onto the same grid, the `by` columns are set to the key of the
+    /// group. Rows of the source that are not on the grid are dropped.
+    fn upsample_to_grid<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        grid: &Series,
+    ) -> PolarsResult<DataFrame>;
+
+    /// Upsample a [`DataFrame`] onto the timestamps of another frame, e.g. to align a series
+    /// to the index of another one.
+    ///
+    /// The grid are the unique non-null timestamps of `reference_time_column` of
+    /// `reference`, sorted ascending. It must have the dtype of `time_column`. See
+    /// [`PolarsUpsample::upsample_to_grid`].
+    fn upsample_align_to<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        reference: &DataFrame,
+        reference_time_column: &str,
+    ) -> PolarsResult<DataFrame>;
+
+    /// Upsample a [`DataFrame`] at the most common spacing of `time_column`, without an
+    /// `every`.
+    ///
+    /// The spacing is the mode of the deltas between consecutive non-null timestamps,
+    /// computed within the groups if `by` is given. Duplicated timestamps are ignored.
+    /// Errors if several deltas are equally common, or if the deltas are too irregular, i.e.
+    /// their coefficient of variation is above 1. Specify `every` with
+    /// [`PolarsUpsample::upsample`] in those cases.
+    fn upsample_auto<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+    ) -> PolarsResult<DataFrame>;
+
+    /// Create the regular range of `time_column` of every group, without the values.
+    ///
+    /// The output contains `time_column` and the `by` columns in the order of the source,
+    /// with the rows an upsample
+    /// with the same arguments would have. The source is not joined onto the range, which
+    /// makes this cheaper than [`PolarsUpsample::upsample`] if other frames are joined onto
+    /// the skeleton afterwards.
+    fn upsample_skeleton<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+    ) -> PolarsResult<DataFrame>;
+
+    /// Upsample a [`DataFrame`] per group, at the frequency of the group.
+    ///
+    /// `every` is called with the rows of every group, e.g. to look up the `every` of its key
+    /// in a map, and `default_every` is used for the groups for which it returns `None`.
+    /// See [`PolarsUpsample::upsample_with_options`] for the other arguments.
+    fn upsample_by_freq<I, F>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: F,
+        default_every: Duration,
+        offset: Duration,
+        options: &UpsampleOptions,
+    ) -> PolarsResult<DataFrame>
+    where
+        I: IntoVec<String>,
+        F: Fn(&DataFrame) -> Option<Duration> + Send + Sync;
+
+    /// Upsample a [`DataFrame`] one group at a time and pass the upsampled frame of every
+    /// group to `f`, instead of collecting them into a single frame.
+    ///
+    /// Only the result of one group is held at a time. The groups are passed in the order of
+    /// their first row if `options.maintain_order` is set, in an unspecified order otherwise.
+    /// `f` is not called for an empty frame. See [`PolarsUpsample::upsample_with_options`]
+    /// for the other arguments; `options.parallel` is ignored.
+    fn upsample_batched<I, F>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        options: &UpsampleOptions,
+        f: F,
+    ) -> PolarsResult<()>
+    where
+        I: IntoVec<String>,
+        F: FnMut(DataFrame) -> PolarsResult<()>;
+
+    /// Upsample a [`DataFrame`] per group and return the upsampled frame of every group with
+    /// the values of its `by` columns, instead of concatenating the frames.
+    ///
+    /// The groups are ordered as in [`PolarsUpsample::upsample_batched`]. Without `by`, the
+    /// whole frame is a single group with an empty key. See
+    /// [`PolarsUpsample::upsample_with_options`] for the other arguments.
+    fn upsample_partitioned<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        options: &UpsampleOptions,
+    ) -> PolarsResult<Vec<(Row<'static>, DataFrame)>>;
+}
+
+impl PolarsUpsample for DataFrame {
+    fn upsample<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+    ) -> PolarsResult<DataFrame> {
+        self.upsample_with_report(by, time_column, every, offset, &Default::default())
+            .map(|(out, _)| out)
+    }
+
+    fn upsample_stable<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+    ) -> PolarsResult<DataFrame> {
+        let options = UpsampleOptions {
+            maintain_order: true,
+            ..Default::default()
+        };
+        self.upsample_with_report(by, time_column, every, offset, &options)
+            .map(|(out, _)| out)
+    }
+
+    fn upsample_with_options<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        options: &UpsampleOptions,
+    ) -> PolarsResult<DataFrame> {
+        self.upsample_with_report(by, time_column, every, offset, options)
+            .map(|(out, _)| out)
+    }
+
+    fn upsample_with_report<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        options: &UpsampleOptions,
+    ) -> PolarsResult<(DataFrame, UpsampleReport)> {
+        let by = by.into_vec();
+        upsample_impl(self, by, time_column, every, offset, options)
+    }
+
+    fn upsample_multi<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_columns: &[&str],
+        every: &[Duration],
+        offset: &[Duration],
+        options: &UpsampleOptions,
+    ) -> PolarsResult<DataFrame> {
+        let by = by.into_vec();
+        upsample_multi_impl(self, by, time_columns, every, offset, options)
+    }
+
+    fn upsample_to_grid<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        grid: &Series,
+    ) -> PolarsResult<DataFrame> {
+        let by = by.into_vec();
+        let grid = validate_grid(self.column(time_column)?, grid)?;
+        upsample_with_grid(
+            self,
+            by,
+            time_column,
+            Grid::Fixed(&grid),
+            &Default::default(),
+        )
+        .map(|(out, _)| out)
+    }
+
+    fn upsample_align_to<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        reference: &DataFrame,
+        reference_time_column: &str,
+    ) -> PolarsResult<DataFrame> {
+        let grid = reference
+            .column(reference_time_column)?
+            .drop_nulls()
+            .sort(false)
+            .unique_stable()?;
+        self.upsample_to_grid(by, time_column, &grid)
+    }
+
+    fn upsample_auto<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+    ) -> PolarsResult<DataFrame> {
+        let by = by.into_vec();
+        let every = infer_every(self, &by, self.column(time_column)?)?;
+        upsample_impl(
+            self,
+            by,
+            time_column,
+            every,
+            Duration::parse("0"),
+            &Default::default(),
+        )
+        .map(|(out, _)| out)
+    }
+
+    fn upsample_skeleton<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+    ) -> PolarsResult<DataFrame> {
+        let by = by.into_vec();
+        upsample_skeleton_impl(self, &by, time_column, every, offset)
+    }
+
+    fn upsample_by_freq<I, F>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: F,
+        default_every: Duration,
+        offset: Duration,
+        options: &UpsampleOptions,
+    ) -> PolarsResult<DataFrame>
+    where
+        I: IntoVec<String>,
+        F: Fn(&DataFrame) -> Option<Duration> + Send + Sync,
+    {
+        let by = by.into_vec();
+        let grid = Grid::PerGroup {
+            every: &every,
+            default_every,
+            offset,
+        };
+        upsample_with_grid(self, by, time_column, grid, options).map(|(out, _)| out)
+    }
+
+    fn upsample_batched<I, F>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        options: &UpsampleOptions,
+        f: F,
+    ) -> PolarsResult<()>
+    where
+        I: IntoVec<String>,
+        F: FnMut(DataFrame) -> PolarsResult<()>,
+    {
+        let by = by.into_vec();
+        let grid = Grid::Regular { every, offset };
+        upsample_batched_impl(self, &by, time_column, grid, options, f)
+    }
+
+    fn upsample_partitioned<I: IntoVec<String>>(
+        &self,
+        by: I,
+        time_column: &str,
+        every: Duration,
+        offset: Duration,
+        options: &UpsampleOptions,
+    ) -> PolarsResult<Vec<(Row<'static>, DataFrame)>> {
+        let by = by.into_vec();
+        let mut partitions = vec![];
+        self.upsample_batched(by.clone(), time_column, every, offset, options, |df| {
+            // The keys of the inserted rows may not be filled, so the key is the first
+            // known value of every `by` column.
+            let key = by
+                .iter()
+                .map(|name| {
+                    let s = df.column(name)?.drop_nulls();
+                    if s.is_empty() {
+                        Ok(AnyValue::Null)
+                    } else {
+                        s.get(0)?.into_static()
+                    }
+                })
+                .collect::<PolarsResult<Vec<_>>>()?;
+            partitions.push((Row(key), df));
+            Ok(())
+        })?;
+        Ok(partitions)
+    }
+}
+
+fn upsample_skeleton_impl(
+    source: &DataFrame,
+    by: &[String],
+    index_column: &str,
+    every: Duration,
+    offset: Duration,
+) -> PolarsResult<DataFrame> {
+    let names = source
+        .get_column_names()
+        .into_iter()
+        .filter(|name| *name == index_column || by.iter().any(|by| by == name))
+        .collect::<Vec<_>>();
+    let source = source.select(names)?;
+    if source.height() == 0 {
+        return Ok(source);
+    }
+    let s = source.column(index_column)?;
+    ensure_sorted_arg(s, "upsample")?;
+    if s.is_sorted_flag() == IsSorted::Descending {
+        let out = upsample_skeleton_impl(&source.reverse(), by, index_column, every, offset)?;
+        return Ok(out.reverse());
+    }
+
+    let options = UpsampleOptions::default();
+    let skeleton = |df: DataFrame| -> PolarsResult<DataFrame> {
+        let index_column = df.column(index_column)?;
+        ensure_group_sorted(&df, index_column, by, "upsample")?;
+        let range = upsample_range(index_column, every, offset, &options)?;
+        let height = range.len();
+        let mut columns = vec![range];
+        for column in by {
+            // All rows of the group have the same key.
+            columns.push(df.column(column)?.new_from_index(0, height));
+        }
+        DataFrame::new(columns)
+    };
+    let out = if by.is_empty() {
+        skeleton(source.clone())?
+    } else {
+        source.group_by(by)?.apply(skeleton)?
+    };
+    in_source_order(source.get_column_names(), out)
+}
+
+/// Infer the `every` of [`PolarsUpsample::upsample_auto`] from the mode of the deltas of
+/// the index column.
+fn infer_every(df: &DataFrame, by: &[String], index_column: &Series) -> PolarsResult<Duration> {
+    let mut deltas = index_deltas(df, by, index_column)?;
+    polars_ensure!(
+        !deltas.is_empty(),
+        ComputeError: "cannot infer upsample `every` of column '{}': it needs at least two distinct timestamps; specify `every`",
+        index_column.name()
+    );
+    deltas.sort_unstable();
+
+    // The most common deltas.
+    let mut modes = vec![];
+    let mut max_count = 0;
+    let mut start = 0;
+    while start < deltas.len() {
+        let delta = deltas[start];
+        let count = deltas[start..].iter().take_while(|d| **d == delta).count();
+        if count > max_count {
+            max_count = count;
+            modes.clear();
+        }
+        if count == max_count {
+            modes.push(delta);
+        }
+        start += count;
+    }
+    polars_ensure!(
+        modes.len() == 1,
+        ComputeError: "cannot infer upsample `every` of column '{}': the deltas {:?} are equally common; specify `every`",
+        index_column.name(), modes
+    );
+
+    let n = deltas.len() as f64;
+    let mean = deltas.iter().map(|d| *d as f64).sum::<f64>() / n;
+    let var = deltas
+        .iter()
+        .map(|d| (*d as f64 - mean).powi(2))
+        .sum::<f64>()
+        / n;
+    let variation = var.sqrt() / mean;
+    polars_ensure!(
+        variation <= MAX_AUTO_EVERY_VARIATION,
+        ComputeError: "cannot infer upsample `every` of column '{}': the timestamps are too irregular (coefficient of variation {:.2}); specify `every`",
+        index_column.name(), variation
+    );
+
+    let mode = modes[0];
+    match index_column.dtype() {
+        DataType::Datetime(tu, _) => {
+            let factor = match tu {
+                TimeUnit::Nanoseconds => 1,
+                TimeUnit::Microseconds => 1_000,
+                TimeUnit::Milliseconds => 1_000_000,
+            };
+            Ok(Duration::from_nsecs(mode * factor))
+        },
+        DataType::Date => Ok(Duration::from_days(mode)),
+        _ => Ok(Duration::new(mode)),
+    }
+}
+
+/// Whether `s` is exactly regular at `every` and `offset`, i.e. equal to the range
+/// [`PolarsUpsample::upsample`] joins onto, without doing the upsample.
+///
+/// Calendar durations and the time zone of `s` are handled like in the upsample, so e.g. an
+/// hourly time zone aware series is regular across a DST transition. Returns `false` if `s`
+/// contains nulls. An empty series is regular.
+pub fn is_regular(s: &Series, every: Duration, offset: Duration) -> PolarsResult<bool> {
+    if s.is_empty() {
+        return Ok(true);
+    }
+    if s.null_count() > 0 {
+        return Ok(false);
+    }
+    let range = upsample_range(s, every, offset, &UpsampleOptions::default())?;
+    if range.len() != s.len() {
+        return Ok(false);
+    }
+    let range = range.to_physical_repr().cast(&DataType::Int64)?;
+    let s = s.to_physical_repr().cast(&DataType::Int64)?;
+    Ok(s.i64()?
+        .into_no_null_iter()
+        .eq(range.i64()?.into_no_null_iter()))
+}
+
+/// Check the arguments of [`PolarsUpsample::upsample`] without doing the upsample, e.g. to
+/// fail fast before the work is scheduled.
+///
+/// Checks that `time_column` and the `by` columns exist, that the index column has a
+/// supported dtype and is sorted, that its time zone can be parsed, and that `every` and
+/// `offset` fit the dtype. If several checks fail, their errors are combined into a single
+/// `ComputeError`.
+pub fn upsample_validate<I: IntoVec<String>>(
+    df: &DataFrame,
+    by: I,
+    time_column: &str,
+    every: Duration,
+    offset: Duration,
+) -> PolarsResult<()> {
+    let mut errors = vec![];
+    match df.column(time_column) {
+        Ok(s) => {
+            errors.extend(check_range_args(s.dtype(), every, offset).err());
+            errors.extend(ensure_sorted_arg(s, "upsample").err());
+            #[cfg(feature = "timezones")]
+            if let DataType::Datetime(tu, Some(tz)) = s.dtype() {
+                if fixed_offset_shift(tz, *tu).is_none() {
+                    errors.extend(parse_time_zone(tz).err());
+                }
+            }
+        },
+        Err(err) => errors.push(err),
+    }
+    for name in by.into_vec() {
+        errors.extend(df.column(&name).err());
+    }
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.pop().unwrap()),
+        _ => {
+            let msg = errors
+                .iter()
+                .map(|err| err.to_string())
+                .collect::<Vec<_>>()
+                .join("; ");
+            polars_bail!(ComputeError: "invalid upsample arguments: {}", msg)
+        },
+    }
+}
+
+/// Estimate the number of rows of [`PolarsUpsample::upsample`], without doing the upsample,
+/// e.g. to abort an upsample that would create too many rows.
+///
+/// The length of the range of every group is computed from its first and last timestamp. The
+/// estimate is exact for a source without duplicated timestamps, and an upper bound if
+/// non-existent local times of a time zone aware range are skipped. Groups without timestamps
+/// count as zero rows. Requires a `Date` or `Datetime` index column.
+pub fn estimate_upsample_rows<I: IntoVec<String>>(
+    df: &DataFrame,
+    by: I,
+    time_column: &str,
+    every: Duration,
+    offset: Duration,
+) -> PolarsResult<usize> {
+    let by = by.into_vec();
+    let s = df.column(time_column)?;
+    if s.is_empty() {
+        return Ok(0);
+    }
+    ensure_sorted_arg(s, "upsample")?;
+    let descending = s.is_sorted_flag() == IsSorted::Descending;
+    let s = match s.dtype() {
+        DataType::Date => s.cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?,
+        DataType::Datetime(_, _) => s.clone(),
+        dt => polars_bail!(
+            ComputeError: "upsample row estimate not allowed for index column of dtype {}", dt,
+        ),
+    };
+    let range_len = |index: Series| {
+        let index = if descending { index.reverse() } else { index };
+        estimate_range_len(&index, every, offset)
+    };
+    if by.is_empty() {
+        return range_len(s);
+    }
+
+    let mut rows = 0;
+    for group in df.group_by(by)?.get_groups().iter() {
+        let index = match group {
+            GroupsIndicator::Idx((_, idx)) => {
+                s.take(&IdxCa::from_iter_values("", idx.iter().copied()))?
+            },
+            GroupsIndicator::Slice([first, len]) => s.slice(first as i64, len as usize),
+        };
+        rows += range_len(index)?;
+    }
+    Ok(rows)
+}
+
+/// The length of the range of the sorted `Datetime` column `index`, computed from its
+/// [`upsample_bounds`].
+fn estimate_range_len(index: &Series, every: Duration, offset: Duration) -> PolarsResult<usize> {
+    if index.null_count() == index.len() {
+        return Ok(0);
+    }
+    let DataType::Datetime(tu, tz) = index.dtype() else {
+        unreachable!()
+    };
+    let every = every_in_time_unit(index.name(), every, *tu, &UpsampleOptions::default())?;
+    let utc;
+    let index = if every.is_constant_duration() && tz.is_some() {
+        // A constant `every` is stepped in UTC.
+        utc = index
+            .to_physical_repr()
+            .cast(&DataType::Datetime(*tu, None))?;
+        &utc
+    } else {
+        index
+    };
+    let (first, last, tu, _) = upsample_bounds(index, every, offset)?;
+    if last < first {
+        return Ok(0);
+    }
+    if every.is_constant_duration() {
+        let step = match tu {
+            TimeUnit::Nanoseconds => every.duration_ns(),
+            TimeUnit::Microseconds => every.duration_us(),
+            TimeUnit::Milliseconds => every.duration_ms(),
+        };
+        return Ok(((last - first) / step) as usize + 1);
+    }
+    // Calendar durations don't have a fixed length, count the steps.
+    let add = |d: &Duration, t| match tu {
+        TimeUnit::Nanoseconds => d.add_ns(t, None),
+        TimeUnit::Microseconds => d.add_us(t, None),
+        TimeUnit::Milliseconds => d.add_ms(t, None),
+    };
+    let mut len = 0;
+    while add(&(every * len as i64), first)? <= last {
+        len += 1;
+    }
+    Ok(len)
+}
+
+/// The schema of the output of an upsample of a frame with the `input` schema.
+///
+/// The columns of the input are kept in order, but the numeric value columns become
+/// `Float64` if they are averaged or interpolated. A [`UpsampleDuplicates::Custom`] reducer
+/// is assumed to keep the schema.
+pub fn upsample_schema(
+    input: &Schema,
+    by: &[String],
+    time_column: &str,
+    options: &UpsampleOptions,
+) -> PolarsResult<Schema> {
+    input.try_get(time_column)?;
+    if let Some(columns) = &options.columns {
+        for name in columns {
+            input.try_get(name)?;
+        }
+    }
+    for name in options.fill.columns.keys() {
+        input.try_get(name)?;
+    }
+    options.fill.check_by_columns(by)?;
+    let mut schema = input
+        .iter()
+        .filter(|(name, _)| {
+            options.columns.as_ref().map_or(true, |columns| {
+                name.as_str() == time_column
+                    || by.iter().any(|by| by == name.as_str())
+                    || columns.iter().any(|c| c == name.as_str())
+            })
+        })
+        .map(|(name, dtype)| {
+            if name != time_column && !by.iter().any(|by| by == name.as_str()) {
+                Field::new(name, value_dtype(name, dtype, options))
+            } else {
+                Field::new(name, dtype.clone())
+            }
+        })
+        .collect::<Schema>();
+    if options.emit_window_bounds {
+        let dtype = input.try_get(time_column)?;
+        for suffix in ["start", "end"] {
+            schema.with_column(format!("{time_column}_{suffix}").into(), dtype.clone());
+        }
+    }
+    if options.indicator {
+        schema.with_column(options.indicator_name.as_str().into(), DataType::Boolean);
+    }
+    if options.with_source_index {
+        schema.with_column(options.source_index_name.as_str().into(), IDX_DTYPE);
+    }
+    Ok(schema)
+}
+
+/// The dtype of a value column of the output of an upsample.
+fn value_dtype(name: &str, dtype: &DataType, options: &UpsampleOptions) -> DataType {
+    let to_float = matches!(options.duplicates, UpsampleDuplicates::Mean)
+        || matches!(
+            options.fill.fill_of(name),
+            UpsampleFill::Linear | UpsampleFill::Monotonic { .. }
+        );
+    if to_float && dtype.is_numeric() && !matches!(dtype, DataType::Float32) {
+        DataType::Float64
+    } else {
+        dtype.clone()
+    }
+}
+
+/// Cast the value columns of `df` to their [`value_dtype`], so that the dtype doesn't depend
+/// on whether e.g. a group had duplicates.
+fn cast_values(
+    df: &mut DataFrame,
+    time_column: &str,
+    by: &[String],
+    options: &UpsampleOptions,
+) -> PolarsResult<()> {
+    let casted = df
+        .get_columns()
+        .iter()
+        .filter(|s| {
+            s.name() != time_column
+                && s.name() != ROW_INDEX
+                && !by.iter().any(|name| name == s.name())
+                && &value_dtype(s.name(), s.dtype(), options) != s.dtype()
+        })
+        .map(|s| s.cast(&value_dtype(s.name(), s.dtype(), options)))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    for s in casted {
+        df.with_column(s)?;
+    }
+    Ok(())
+}
+
+/// The range the source of an upsample is joined onto.
+#[derive(Clone, Copy)]
+enum Grid<'a> {
+    /// A regular range, see [`upsample_range`].
+    Regular { every: Duration, offset: Duration },
+    /// A regular range with the `every` of the group, see [`PolarsUpsample::upsample_by_freq`].
+    PerGroup {
+        every: &'a (dyn Fn(&DataFrame) -> Option<Duration> + Send + Sync),
+        default_every: Duration,
+        offset: Duration,
+    },
+    /// A range given by the caller.
+    Fixed(&'a Series),
+}
+
+/// Check that an upsample grid fits the index column and flag it as sorted.
+fn validate_grid(index_column: &Series, grid: &Series) -> PolarsResult<Series> {
+    polars_ensure!(
+        grid.dtype() == index_column.dtype(),
+        SchemaMismatch: "upsample grid has dtype {}, but the index column '{}' has dtype {}",
+        grid.dtype(), index_column.name(), index_column.dtype()
+    );
+    polars_ensure!(
+        grid.null_count() == 0,
+        ComputeError: "upsample grid must not contain nulls"
+    );
+    let s = grid.to_physical_repr().cast(&DataType::Int64)?;
+    let values = s.i64()?;
+    let increasing = values
+        .into_no_null_iter()
+        .zip(values.into_no_null_iter().skip(1))
+        .all(|(a, b)| a < b);
+    polars_ensure!(
+        increasing,
+        ComputeError: "upsample grid must be strictly increasing"
+    );
+    let mut grid = grid.clone();
+    grid.set_sorted_flag(IsSorted::Ascending);
+    Ok(grid)
+}
+
+/// `(first source row, original rows, inserted rows)` of an upsampled group.
+type GroupCounts = Mutex<Vec<(IdxSize, IdxSize, IdxSize)>>;
+
+pub(crate) fn upsample_impl(
+    source: &DataFrame,
+    by: Vec<String>,
+    index_column: &str,
+    every: Duration,
+    offset: Duration,
+    options: &UpsampleOptions,
+) -> PolarsResult<(DataFrame, UpsampleReport)> {
+    if options.grid.shared && !by.is_empty() {
+        let s = source.column(index_column)?;
+        // The range only depends on the boundaries of all groups.
+        let mut bounds = s.min_as_series();
+        bounds.append(&s.max_as_series())?;
+        bounds.set_sorted_flag(IsSorted::Ascending);
+        let range = upsample_range(&bounds, every, offset, options)?;
+        return upsample_with_grid(source, by, index_column, Grid::Fixed(&range), options);
+    }
+    let grid = Grid::Regular { every, offset };
+    upsample_with_grid(source, by, index_column, grid, options)
+}
+
+fn upsample_with_grid(
+    source: &DataFrame,
+    by: Vec<String>,
+    index_column: &str,
+    grid: Grid,
+    options: &UpsampleOptions,
+) -> PolarsResult<(DataFrame, UpsampleReport)> {
+    let source = project_columns(source, index_column, &by, options)?;
+    // Track the source rows, so that we know which rows were inserted.
+    let source = source.with_row_count(ROW_INDEX, None)?;
+    let group_counts = GroupCounts::default();
+    let out = upsample_frame(
+        &source,
+        &by,
+        index_column,
+        grid,
+        options,
+        Some(&group_counts),
+    )?;
+
+    let source_rows = out.column(ROW_INDEX)?.clone();
+    let inserted_rows = source_rows.null_count();
+    let per_group = if by.is_empty() {
+        None
+    } else {
+        let mut counts = group_counts.into_inner().unwrap();
+        counts.sort_unstable_by_key(|(first, _, _)| *first);
+        let (first, (original, inserted)): (Vec<_>, (Vec<_>, Vec<_>)) = counts
+            .into_iter()
+            .map(|(first, original, inserted)| (first, (original, inserted)))
+            .unzip();
+        let mut keys = source.select(&by)?.take(&IdxCa::from_vec("", first))?;
+        keys.with_column(IdxCa::from_vec("original_rows", original).into_series())?;
+        keys.with_column(IdxCa::from_vec("inserted_rows", inserted).into_series())?;
+        Some(keys)
+    };
+    let report = UpsampleReport {
+        original_rows: out.height() - inserted_rows,
+        inserted_rows,
+        per_group,
+    };
+    let out = finish_upsampled(source.get_column_names(), out, options)?;
+    Ok((out, report))
+}
+
+/// Select the index column, the `by` columns and the value columns of `options.columns` of
+/// `source`, in the order of `source`.
+fn project_columns<'a>(
+    source: &'a DataFrame,
+    index_column: &str,
+    by: &[String],
+    options: &UpsampleOptions,
+) -> PolarsResult<Cow<'a, DataFrame>> {
+    for name in options.fill.columns.keys() {
+        source.column(name)?;
+    }
+    options.fill.check_by_columns(by)?;
+    let Some(columns) = &options.columns else {
+        return Ok(Cow::Borrowed(source));
+    };
+    for name in columns {
+        source.column(name)?;
+    }
+    let selection = source
+        .get_column_names()
+        .into_iter()
+        .filter(|name| {
+            *name == index_column
+                || by.iter().any(|by| by == name)
+                || columns.iter().any(|c| c == name)
+        })
+        .collect::<Vec<_>>();
+    Ok(Cow::Owned(source.select(selection)?))
+}
+
+/// Drop the [`ROW_INDEX`] of an upsampled frame, restore the column order of the source
+/// and append the indicator and the source index columns.
+fn finish_upsampled(
+    names: Vec<&str>,
+    out: DataFrame,
+    options: &UpsampleOptions,
+) -> PolarsResult<DataFrame> {
+    let mut source_index = out.column(ROW_INDEX)?.clone();
+    let mut out = in_source_order(names, out.drop(ROW_INDEX)?)?;
+    if options.indicator {
+        let mut inserted = source_index.is_null();
+        inserted.rename(&options.indicator_name);
+        out.hstack_mut(&[inserted.into_series()])?;
+    }
+    if options.with_source_index {
+        source_index.rename(&options.source_index_name);
+        out.hstack_mut(&[source_index])?;
+    }
+    Ok(out)
+}
+
+fn upsample_batched_impl<F>(
+    source: &DataFrame,
+    by: &[String],
+    index_column: &str,
+    grid: Grid,
+    options: &UpsampleOptions,
+    mut f: F,
+) -> PolarsResult<()>
+where
+    F: FnMut(DataFrame) -> PolarsResult<()>,
+{
+    if source.height() == 0 {
+        return Ok(());
+    }
+    let source = project_columns(source, index_column, by, options)?;
+    let source = source.with_row_count(ROW_INDEX, None)?;
+    let names = source.get_column_names();
+    if by.is_empty() {
+        let out = upsample_frame(&source, by, index_column, grid, options, None)?;
+        return f(finish_upsampled(names, out, options)?);
+    }
+
+    let s = source.column(index_column)?;
+    ensure_sorted_arg(s, "upsample")?;
+    let descending = s.is_sorted_flag() == IsSorted::Descending;
+    let gb = if options.maintain_order {
+        source.group_by_stable(by)
+    } else {
+        source.group_by(by)
+    }?;
+    for group in gb.get_groups().iter() {
+        let df = match group {
+            GroupsIndicator::Idx((_, idx)) => {
+                source.take(&IdxCa::from_iter_values("", idx.iter().copied()))?
+            },
+            GroupsIndicator::Slice([first, len]) => source.slice(first as i64, len as usize),
+        };
+        let out = if descending {
+            upsample_group(&df.reverse(), by, index_column, grid, options, None)?.reverse()
+        } else {
+            upsample_group(&df, by, index_column, grid, options, None)?
+        };
+        f(finish_upsampled(names.clone(), out, options)?)?;
+    }
+    Ok(())
+}
+
+fn upsample_frame(
+    source: &DataFrame,
+    by: &[String],
+    index_column: &str,
+    grid: Grid,
+    options: &UpsampleOptions,
+    group_counts: Option<&GroupCounts>,
+) -> PolarsResult<DataFrame> {
+    let s = source.column(index_column)?;
+    if source.height() == 0 {
+        return empty_upsampled(source, index_column, by, options);
+    }
+    ensure_sorted_arg(s, "upsample")?;
+    if s.is_sorted_flag() == IsSorted::Descending {
+        // Upsample in ascending order and restore the order of the source afterwards.
+        let mut out = upsample_frame(
+            &source.reverse(),
+            by,
+            index_column,
+            grid,
+            options,
+            group_counts,
+        )?
+        .reverse();
+        if by.is_empty() {
+            flag_sorted(&mut out, index_column, IsSorted::Descending)?;
+        } else if options.sort_groups {
+            // The reverse also reversed the order of the groups.
+            out = sort_group_blocks(&out, by)?;
+        }
+        return Ok(out);
+    }
+    if by.is_empty() {
+        let index_column = source.column(index_column)?;
+        let mut out = upsample_single_impl(source, index_column, by, grid, options)?;
+        // The range is sorted and the join keeps its order. With groups the index column is
+        // only sorted within the groups.
+        flag_sorted(&mut out, index_column.name(), IsSorted::Ascending)?;
+        Ok(out)
+    } else {
+        let gb = if options.maintain_order {
+            source.group_by_stable(by)
+        } else {
+            source.group_by(by)
+        };
+        let gb = gb?;
+        let apply_group = |df: DataFrame| -> PolarsResult<DataFrame> {
+            upsample_group(&df, by, index_column, grid, options, group_counts)
+        };
+        // Large groups may SO when upsampled in parallel.
+        let parallel = options.parallel
+            && gb
+                .get_groups()
+                .iter()
+                .all(|g| g.len() <= MAX_PARALLEL_GROUP_LEN);
+        let out = if parallel {
+            #[allow(deprecated)]
+            gb.par_apply(apply_group)
+        } else {
+            gb.apply(apply_group)
+        }?;
+        if options.sort_groups {
+            sort_group_blocks(&out, by)
+        } else {
+            Ok(out)
+        }
+    }
+}
+
+/// Order the blocks of the groups of an upsampled frame by their key. The rows within a
+/// group keep their order.
+fn sort_group_blocks(df: &DataFrame, by: &[String]) -> PolarsResult<DataFrame> {
+    df.sort(by.to_vec(), vec![false; by.len()], true)
+}
+
+/// Upsample a single group and fill its `by` columns. The counts of the group are pushed
+/// to `group_counts` if given.
+fn upsample_group(
+    df: &DataFrame,
+    by: &[String],
+    index_column: &str,
+    grid: Grid,
+    options: &UpsampleOptions,
+    group_counts: Option<&GroupCounts>,
+) -> PolarsResult<DataFrame> {
+    let index_column = df.column(index_column)?;
+    ensure_group_sorted(df, index_column, by, "upsample")?;
+    let mut upsampled_df = upsample_single_impl(df, index_column, by, grid, options)?;
+    let height = upsampled_df.height();
+    for column in by {
+        let by_fill = match options.grid.shared {
+            // Rows of a shared grid before the first row of the group have no key to forward.
+            true => UpsampleByFill::GroupKey,
+            false => options.fill.by_fill_of(column),
+        };
+        let filled_group = match by_fill {
+            UpsampleByFill::Forward => fill_forward(upsampled_df.column(column)?)?,
+            // All rows of the group have the same key.
+            UpsampleByFill::GroupKey => df.column(column)?.new_from_index(0, height),
+        };
+        upsampled_df.with_column(filled_group)?;
+    }
+    if let Some(group_counts) = group_counts {
+        let first = df.column(ROW_INDEX)?.idx()?.get(0).unwrap();
+        let inserted = upsampled_df.column(ROW_INDEX)?.null_count();
+        let original = upsampled_df.height() - inserted;
+        group_counts
+            .lock()
+            .unwrap()
+            .push((first, original as IdxSize, inserted as IdxSize));
+    }
+    Ok(upsampled_df)
+}
+
+/// Flag the column `name` of `df` as sorted.
+fn flag_sorted(df: &mut DataFrame, name: &str, sorted: IsSorted) -> PolarsResult<()> {
+    df.apply(name, |s| {
+        let mut s = s.clone();
+        s.set_sorted_flag(sorted);
+        s
+    })?;
+    Ok(())
+}
+
+/// Select the columns of `out` in the order of `names`, followed by the columns of `out`
+/// that are not in `names`. Names that are not in `out` are skipped.
+fn in_source_order(names: Vec<&str>, out: DataFrame) -> PolarsResult<DataFrame> {
+    let mut selection = names
+        .iter()
+        .copied()
+        .filter(|name| out.get_column_index(name).is_some())
+        .collect::<Vec<_>>();
+    selection.extend(
+        out.get_column_names()
+            .into_iter()
+            .filter(|name| !names.contains(name)),
+    );
+    out.select(selection)
+}
+
+/// Ensure the index column of a group is sorted. `ensure_sorted_arg` only checks the sorted
+/// flag of the whole column, which doesn't guarantee that every group is sorted.
+pub(crate) fn ensure_group_sorted(
+    df: &DataFrame,
+    index_column: &Series,
+    by: &[String],
+    operation: &str,
+) -> PolarsResult<()> {
+    let s = index_column.to_physical_repr().cast(&DataType::Int64)?;
+    let mut values = s.i64()?.into_iter().flatten();
+    let sorted = match values.next() {
+        Some(mut prev) => values.all(|v| {
+            let sorted = v >= prev;
+            prev = v;
+            sorted
+        }),
+        None => true,
+    };
+    if !sorted {
+        let key = by
+            .iter()
+            .map(|name| Ok(format!("{}={}", name, df.column(name)?.get(0)?)))
+            .collect::<PolarsResult<Vec<_>>>()?;
+        polars_bail!(
+            ComputeError: "argument in operation '{}' is not sorted in group [{}], sort the index column within the groups first",
+            operation, key.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// The zero-row result of an upsample, with the schema of a non-empty result.
+fn empty_upsampled(
+    source: &DataFrame,
+    index_col_name: &str,
+    by: &[String],
+    options: &UpsampleOptions,
+) -> PolarsResult<DataFrame> {
+    // The range is joined with the source, so the index column comes first.
+    let mut names = vec![index_col_name];
+    names.extend(
+        source
+            .get_column_names()
+            .into_iter()
+            .filter(|name| *name != index_col_name),
+    );
+    let mut out = source.select(names)?.clear();
+    let inserted = BooleanChunked::full("", false, 0);
+    fill_upsampled(&mut out, index_col_name, by, &options.fill, &inserted)?;
+    cast_values(&mut out, index_col_name, by, options)?;
+    Ok(out)
+}
+
+/// Upsample a single group. The `source` must contain the [`ROW_INDEX`] column.
+fn upsample_single_impl(
+    source: &DataFrame,
+    index_column: &Series,
+    by: &[String],
+    grid: Grid,
+    options: &UpsampleOptions,
+) -> PolarsResult<DataFrame> {
+    let index_col_name = index_column.name();
+    let collapsed = collapse_duplicates(source, index_column, by, &options.duplicates)?;
+    let source = collapsed.as_ref().unwrap_or(source);
+    let every = match grid {
+        Grid::Regular { every, .. } => Some(every),
+        Grid::PerGroup {
+            every,
+            default_every,
+            ..
+        } => Some(every(source).unwrap_or(default_every)),
+        Grid::Fixed(_) => None,
+    };
+    let range = match (grid, every) {
+        (Grid::Fixed(grid), _) => {
+            let mut grid = grid.clone();
+            grid.rename(index_col_name);
+            grid
+        },
+        _ if index_column.null_count() == index_column.len() && options.empty_on_all_null => {
+            let out = empty_upsampled(source, index_col_name, by, options)?;
+            return with_window_bounds(out, index_col_name, every, options);
+        },
+        (Grid::Regular { offset, .. } | Grid::PerGroup { offset, .. }, Some(every)) => {
+            upsample_range(index_column, every, offset, options)?
+        },
+        _ => unreachable!(),
+    };
+    let range = if options.grid.preserve_endpoints {
+        with_endpoints(range, index_column)?
+    } else {
+        range
+    };
+    let unaligned = match options.join.on_unaligned {
+        UpsampleUnaligned::Drop => vec![],
+        _ => unaligned_rows(source.column(index_col_name)?, &range)?,
+    };
+    if let UpsampleFill::AsOfForward = options.fill.strategy {
+        polars_ensure!(
+            options.join.tolerance.is_none()
+                && options.fill.columns.is_empty()
+                && options.fill.limit.is_none()
+                && options.fill.max_gap.is_none(),
+            ComputeError: "upsample `fill` AsOfForward cannot be combined with the `tolerance`, `fill.columns`, `fill.limit` and `max_gap` options"
+        );
+    }
+    polars_ensure!(
+        !options
+            .fill
+            .columns
+            .values()
+            .any(|fill| matches!(fill, UpsampleFill::AsOfForward)),
+        ComputeError: "upsample AsOfForward fills at the join and can only be the `fill.strategy` of all columns"
+    );
+    let mut out = match options.join.tolerance {
+        Some(tolerance) => {
+            polars_ensure!(
+                matches!(options.join.on_unaligned, UpsampleUnaligned::Drop),
+                ComputeError: "upsample `tolerance` requires `on_unaligned` to drop the unaligned rows"
+            );
+            join_range_nearest(range, source, tolerance, options.join.on_collision)?
+        },
+        None if matches!(options.fill.strategy, UpsampleFill::AsOfForward) => {
+            join_range_backward(range, source)?
+        },
+        None => join_range(range, source, options.join.algorithm)?,
+    };
+    if let Some(&first) = unaligned.first() {
+        if matches!(options.join.on_unaligned, UpsampleUnaligned::Error) {
+            polars_bail!(
+                ComputeError: "upsample source has a timestamp in column '{}' that is not on the range: {}; \
+                set the `on_unaligned` option to keep or drop such rows",
+                index_col_name, source.column(index_col_name)?.get(first as usize)?
+            )
+        }
+        let rows = source
+            .take(&IdxCa::from_vec("", unaligned))?
+            .select(out.get_column_names())?;
+        out.vstack_mut(&rows)?;
+        out = out.sort([index_col_name], false, true)?;
+    }
+    // The rows without a source row index are inserted.
+    let inserted = out.column(ROW_INDEX)?.is_null();
+    fill_upsampled(&mut out, index_col_name, by, &options.fill, &inserted)?;
+    cast_values(&mut out, index_col_name, by, options)?;
+    if options.fill.drop_empty {
+        // After the fill, so that the filled rows are kept.
+        out = drop_empty_rows(&out, index_col_name, by, &inserted)?;
+    }
+    with_window_bounds(out, index_col_name, every, options)
+}
+
+/// Add the first and last non-null value of the sorted `index_column` to the sorted `range`.
+fn with_endpoints(range: Series, index_column: &Series) -> PolarsResult<Series> {
+    let index_column = index_column.drop_nulls();
+    if index_column.is_empty() {
+        return Ok(range);
+    }
+    let mut endpoints = index_column.slice(0, 1);
+    endpoints.append(&index_column.slice(-1, 1))?;
+    let mut range = range;
+    range.append(&endpoints.cast(range.dtype())?)?;
+    range.sort(false).unique_stable()
+}
+
+/// Remove the `inserted` rows of `df` in which all value columns are null.
+fn drop_empty_rows(
+    df: &DataFrame,
+    index_col_name: &str,
+    by: &[String],
+    inserted: &BooleanChunked,
+) -> PolarsResult<DataFrame> {
+    let mut empty = inserted.clone();
+    for s in df.get_columns() {
+        if s.name() != index_col_name
+            && s.name() != ROW_INDEX
+            && !by.iter().any(|name| name == s.name())
+        {
+            empty = &empty & &s.is_null();
+        }
+    }
+    df.filter(&!&empty)
+}
+
+/// Compute the boundaries of the range an upsample of the `index` column is joined onto.
+///
+/// Returns `(first, last, time_unit, time_zone)`. `first` and `last` are in the time unit
+/// of `index` and are expressed in local time if `index` has a time zone. The `offset` is
+/// already applied to `first`.
+pub fn upsample_bounds(
+    index: &Series,
+    every: Duration,
+    offset: Duration,
+) -> PolarsResult<(i64, i64, TimeUnit, Option<TimeZone>)> {
+    upsample_bounds_anchored(index, every, offset, None)
+}
+
+/// [`upsample_bounds`] where `first` is snapped back to the `anchor` before the `offset` is
+/// applied.
+fn upsample_bounds_anchored(
+    index: &Series,
+    every: Duration,
+    offset: Duration,
+    anchor: Option<CalendarAnchor>,
+) -> PolarsResult<(i64, i64, TimeUnit, Option<TimeZone>)> {
+    polars_ensure!(
+        !every.negative && !every.is_zero(),
+        ComputeError: "`every` must be positive"
+    );
+    match index.dtype() {
+        DataType::Datetime(tu, tz) => {
+            let s = index.cast(&DataType::Int64)?;
+            let ca = s.i64()?;
+            let first = ca.into_iter().flatten().next();
+            let last = ca.into_iter().flatten().next_back();
+            match (first, last) {
+                (Some(first), Some(last)) => {
+                    let (first, last) = match tz {
+                        #[cfg(feature = "timezones")]
+                        Some(tz) => {
+                            let tz = parse_time_zone(tz)?;
+                            (
+                                unlocalize_timestamp(first, *tu, tz),
+                                unlocalize_timestamp(last, *tu, tz),
+                            )
+                        },
+                        _ => (first, last),
+                    };
+                    let first = match anchor {
+                        Some(anchor) => anchor.snap(first, *tu, None)?,
+                        None => first,
+                    };
+                    let first = range_start(first, every, offset, |d, t| match tu {
+                        TimeUnit::Nanoseconds => d.add_ns(t, None),
+                        TimeUnit::Microseconds => d.add_us(t, None),
+                        TimeUnit::Milliseconds => d.add_ms(t, None),
+                    })?;
+                    Ok((first, last, *tu, tz.clone()))
+                },
+                _ => polars_bail!(
+                    ComputeError: "cannot determine upsample boundaries: all elements are null"
+                ),
+            }
+        },
+        dt => polars_bail!(
+            ComputeError: "upsample not allowed for index column of dtype {}", dt,
+        ),
+    }
+}
+
+#[cfg(feature = "timezones")]
+fn parse_time_zone(tz: &str) -> PolarsResult<Tz> {
+    tz.parse::<Tz>()
+        .map_err(|_| polars_err!(ComputeError: "unable to parse time zone: '{}'", tz))
+}
+
+/// Create the range the source is joined onto.
+fn upsample_range(
+    index_column: &Series,
+    every: Duration,
+    offset: Duration,
+    options: &UpsampleOptions,
+) -> PolarsResult<Series> {
+    if every.parsed_int && matches!(index_column.dtype(), DataType::Datetime(_, _)) {
+        return index_count_range(index_column, every, offset, options);
+    }
+    if every.business_days {
+        return business_day_range(index_column, every, offset, options);
+    }
+    #[cfg(feature = "timezones")]
+    if let DataType::Datetime(tu, Some(tz)) = index_column.dtype() {
+        if let Some(shift) = fixed_offset_shift(tz, *tu) {
+            // A fixed offset has no daylight saving time, so the range is created in local
+            // time and shifted back to UTC.
+            let local = (index_column.to_physical_repr().i64()? + shift).into_datetime(*tu, None);
+            let options = UpsampleOptions {
+                grid: UpsampleGridOptions {
+                    end: options.grid.end.map(|end| end + shift),
+                    ..options.grid.clone()
+                },
+                ..options.clone()
+            };
+            let range = upsample_range(&local.into_series(), every, offset, &options)?;
+            let mut range =
+                (range.to_physical_repr().i64()? - shift).into_datetime(*tu, Some(tz.clone()));
+            range.rename(index_column.name());
+            range.set_sorted_flag(IsSorted::Ascending);
+            return Ok(range.into_series());
+        }
+    }
+    let every = match index_column.dtype() {
+        DataType::Datetime(tu, _) => every_in_time_unit(index_column.name(), every, *tu, options)?,
+        _ => every,
+    };
+    let range = regular_range(index_column, every, offset, options)?;
+    match options.grid.offset_fraction {
+        Some(fraction) => shift_by_fraction(range, index_column, every, fraction, options),
+        None => Ok(range),
+    }
+}
+
+/// Create the range of a Datetime index column for an `every` that is an index count `n`.
+///
+/// The range has the points of the index column with `n - 1` evenly spaced points inserted
+/// between every pair of adjacent points, rounded down to the time unit. Points that would
+/// coincide because a gap is shorter than `n` time units are only kept once.
+fn index_count_range(
+    index_column: &Series,
+    every: Duration,
+    offset: Duration,
+    options: &UpsampleOptions,
+) -> PolarsResult<Series> {
+    check_range_args(index_column.dtype(), every, offset)?;
+    polars_ensure!(
+        options.grid.anchor.is_none()
+            && options.grid.end.is_none()
+            && options.grid.offset_fraction.is_none(),
+        ComputeError: "upsample `anchor`, `end` and `offset_fraction` cannot be combined with an index count `every`"
+    );
+    let DataType::Datetime(tu, tz) = index_column.dtype() else {
+        unreachable!()
+    };
+    let n = every.nanoseconds();
+    let s = index_column.to_physical_repr();
+    let ca = s.i64()?;
+    // Every point is followed by at most `n - 1` inserted points.
+    polars_ensure!(
+        usize::try_from(n).ok().and_then(|n| ca.len().checked_mul(n)).is_some(),
+        ComputeError: "upsample with an index count `every` of {}i on {} rows overflows", n, ca.len()
+    );
+    // Most gaps may be shorter than `n` time units, so only reserve for the points themselves.
+    let mut values = Vec::with_capacity(ca.len());
+    let mut prev = None;
+    for t in ca.into_iter().flatten() {
+        if let Some(prev) = prev.filter(|&prev| prev < t) {
+            let gap = t as i128 - prev as i128;
+            values.extend((1..n).map(|k| prev + (gap * k as i128 / n as i128) as i64));
+        }
+        values.push(t);
+        prev = Some(t);
+    }
+    polars_ensure!(
+        !values.is_empty(),
+        ComputeError: "cannot determine upsample boundaries: all elements are null"
+    );
+    values.dedup();
+    let mut range = Int64Chunked::from_vec(index_column.name(), values);
+    range.set_sorted_flag(IsSorted::Ascending);
+    Ok(range.into_datetime(*tu, tz.clone()).into_series())
+}
+
+/// Check that `every` can be represented in the time unit of the index column, or round it
+/// up to the time unit if `options.grid.round_every` is set.
+fn every_in_time_unit(
+    name: &str,
+    every: Duration,
+    tu: TimeUnit,
+    options: &UpsampleOptions,
+) -> PolarsResult<Duration> {
+    let unit = match tu {
+        TimeUnit::Nanoseconds => return Ok(every),
+        TimeUnit::Microseconds => 1_000,
+        TimeUnit::Milliseconds => 1_000_000,
+    };
+    let nsecs = every.nanoseconds();
+    if nsecs % unit == 0 {
+        return Ok(every);
+    }
+    polars_ensure!(
+        options.grid.round_every,
+        ComputeError: "upsample `every` has a resolution of {}ns, which is finer than the time unit \
+        of the index column '{}' ({}); set `round_every` to round it up to the time unit",
+        nsecs, name, tu
+    );
+    let rounded = (nsecs / unit + 1) * unit;
+    Ok(every.with_nanoseconds(rounded))
+}
+
+/// Move every point of the range by `fraction` of the interval up to the next point.
+///
+/// The interval is computed per point, so calendar durations land on the same phase of
+/// intervals of different lengths, e.g. half way through months of 28 and 31 days. Points
+/// that are moved after the last element of the index column (or `options.grid.end`) are dropped.
+fn shift_by_fraction(
+    range: Series,
+    index_column: &Series,
+    every: Duration,
+    fraction: f64,
+    options: &UpsampleOptions,
+) -> PolarsResult<Series> {
+    polars_ensure!(
+        (0.0..1.0).contains(&fraction),
+        ComputeError: "upsample `offset_fraction` must be in the range [0, 1), got {}", fraction
+    );
+    let next = every_adder(index_column.dtype(), every)?;
+
+    let s = index_column.to_physical_repr().cast(&DataType::Int64)?;
+    let last = s
+        .i64()?
+        .into_iter()
+        .flatten()
+        .next_back()
+        .unwrap_or(i64::MIN);
+    let last = extend_to_end(last, options.grid.end)?;
+    let points = range.to_physical_repr().cast(&DataType::Int64)?;
+    let mut values = Vec::with_capacity(points.len());
+    for t in points.i64()?.into_no_null_iter() {
+        let shifted = t + (fraction * (next(t)? - t) as f64).round() as i64;
+        if shifted > last {
+            break;
+        }
+        values.push(shifted);
+    }
+
+    let mut out = Int64Chunked::from_vec(range.name(), values)
+        .into_series()
+        .cast(&range.dtype().to_physical())?
+        .cast(range.dtype())?;
+    out.set_sorted_flag(IsSorted::Ascending);
+    Ok(out)
+}
+
+/// A function that adds `every` to a physical value of an index column of `dtype`.
+///
+/// Calendar durations are added in the time zone of a `Datetime`, so the interval follows
+/// the length of the month or day it starts in.
+fn every_adder(
+    dtype: &DataType,
+    every: Duration,
+) -> PolarsResult<Box<dyn Fn(i64) -> PolarsResult<i64>>> {
+    Ok(match dtype {
+        DataType::Datetime(tu, tz) => {
+            #[cfg(feature = "timezones")]
+            let tz = match tz {
+                Some(tz) => Some(parse_time_zone(tz)?),
+                None => None,
+            };
+            #[cfg(not(feature = "timezones"))]
+            let tz = {
+                let _ = tz;
+                None
+            };
+            let add: fn(&Duration, i64, Option<&_>) -> PolarsResult<i64> = match tu {
+                TimeUnit::Nanoseconds => Duration::add_ns,
+                TimeUnit::Microseconds => Duration::add_us,
+                TimeUnit::Milliseconds => Duration::add_ms,
+            };
+            Box::new(move |t| add(&every, t, tz.as_ref()))
+        },
+        DataType::Date => {
+            Box::new(
+                move |t| Ok(every.add_ms(t * MILLISECONDS_IN_DAY, None)? / MILLISECONDS_IN_DAY),
+            )
+        },
+        _ => Box::new(move |t| Ok(t + every.nanoseconds())),
+    })
+}
+
+/// Append the interval columns of [`UpsampleOptions::emit_window_bounds`] to an upsampled
+/// group. `every` is the `every` of the group, or `None` for a fixed grid.
+fn with_window_bounds(
+    mut out: DataFrame,
+    index_col_name: &str,
+    every: Option<Duration>,
+    options: &UpsampleOptions,
+) -> PolarsResult<DataFrame> {
+    if !options.emit_window_bounds {
+        return Ok(out);
+    }
+    let index_column = out.column(index_col_name)?;
+    let dtype = index_column.dtype().clone();
+    let every = match every {
+        Some(every) if !(every.parsed_int && matches!(dtype, DataType::Datetime(_, _))) => every,
+        _ => polars_bail!(
+            ComputeError: "upsample `emit_window_bounds` requires a fixed `every`, \
+            not a grid or an index count on a Datetime index column"
+        ),
+    };
+    let add = every_adder(&dtype, every)?;
+    let s = index_column.to_physical_repr().cast(&DataType::Int64)?;
+    let end = s
+        .i64()?
+        .into_iter()
+        .map(|t| t.map(&add).transpose())
+        .collect::<PolarsResult<Int64Chunked>>()?;
+    let mut start = index_column.clone();
+    start.rename(&format!("{index_col_name}_start"));
+    let mut end = end.into_series().cast(&dtype.to_physical())?.cast(&dtype)?;
+    end.rename(&format!("{index_col_name}_end"));
+    out.hstack_mut(&[start, end])?;
+    Ok(out)
+}
+
+/// Create the regular range of the index column.
+pub(crate) fn regular_range(
+    index_column: &Series,
+    every: Duration,
+    offset: Duration,
+    options: &UpsampleOptions,
+) -> PolarsResult<Series> {
+    let index_col_name = index_column.name();
+    let end = options.grid.end;
+    let closed = options.grid.closed;
+
+    check_range_args(index_column.dtype(), every, offset)?;
+    use DataType::*;
+    match index_column.dtype() {
+        #[cfg(feature = "timezones")]
+        Datetime(tu, Some(tz)) => upsample_range_tz(index_column, every, offset, options, *tu, tz),
+        Datetime(_, _) => {
+            let (first, last, tu, tz) =
+                upsample_bounds_anchored(index_column, every, offset, options.grid.anchor)?;
+            let last = extend_to_end(last, end)?;
+            Ok(
+                datetime_range_impl(index_col_name, first, last, every, closed, tu, tz.as_ref())?
+                    .into_series(),
+            )
+        },
+        Date => {
+            let s = index_column.to_physical_repr();
+            let ca = s.i32()?;
+            let first = ca.into_iter().flatten().next();
+            let last = ca.into_iter().flatten().next_back();
+            match (first, last) {
+                (Some(first), Some(last)) => {
+                    let first = first as i64 * MILLISECONDS_IN_DAY;
+                    let first = match options.grid.anchor {
+                        Some(anchor) => anchor.snap(first, TimeUnit::Milliseconds, None)?,
+                        None => first,
+                    };
+                    let first = range_start(first, every, offset, |d, t| d.add_ms(t, None))?
+                        .div_euclid(MILLISECONDS_IN_DAY);
+                    let last = extend_to_end(last as i64, end)?;
+                    let mut range = if every.months() == 0 {
+                        let step = every.weeks() * 7 + every.days();
+                        let values = stepped_range(first, last, step, closed)
+                            .into_iter()
+                            .map(|day| day as i32)
+                            .collect();
+                        Int32Chunked::from_vec(index_col_name, values)
+                    } else {
+                        // Calendar months don't have a fixed number of days.
+                        let range = datetime_range_impl(
+                            index_col_name,
+                            first * MILLISECONDS_IN_DAY,
+                            last * MILLISECONDS_IN_DAY,
+                            every,
+                            closed,
+                            TimeUnit::Milliseconds,
+                            None,
+                        )?;
+                        range
+                            .into_iter()
+                            .map(|t| t.map(|t| (t / MILLISECONDS_IN_DAY) as i32))
+                            .collect()
+                    };
+                    range.rename(index_col_name);
+                    range.set_sorted_flag(IsSorted::Ascending);
+                    Ok(range.into_date().into_series())
+                },
+                _ => polars_bail!(
+                    ComputeError: "cannot determine upsample boundaries: all elements are null"
+                ),
+            }
+        },
+        Int32 | Int64 | UInt64 | Time if options.grid.anchor.is_some() => polars_bail!(
+            ComputeError: "upsample `anchor` requires a Date or Datetime index column, got {}",
+            index_column.dtype()
+        ),
+        Int32 | Int64 | UInt64 => {
+            let step = every.nanoseconds();
+            // A UInt64 value above `i64::MAX` has no Int64 range, so don't turn it into a null.
+            let s = index_column.strict_cast(&Int64)?;
+            let ca = s.i64()?;
+            let first = ca.into_iter().flatten().next();
+            let last = ca.into_iter().flatten().next_back();
+            match (first, last) {
+                (Some(first), Some(last)) => {
+                    let first = range_start(first, every, offset, |d, t| {
+                        Ok(if d.negative {
+                            t - d.nanoseconds()
+                        } else {
+                            t + d.nanoseconds()
+                        })
+                    })?;
+                    let last = extend_to_end(last, end)?;
+                    let values = stepped_range(first, last, step, closed);
+                    let mut range = Int64Chunked::from_vec(index_col_name, values);
+                    range.set_sorted_flag(IsSorted::Ascending);
+                    // A negative `offset` can move the start of a UInt64 range below 0.
+                    range.into_series().strict_cast(index_column.dtype())
+                },
+                _ => polars_bail!(
+                    ComputeError: "cannot determine upsample boundaries: all elements are null"
+                ),
+            }
+        },
+        Time => {
+            let step = every.nanoseconds();
+            let s = index_column.to_physical_repr();
+            let ca = s.i64()?;
+            let first = ca.into_iter().flatten().next();
+            let last = ca.into_iter().flatten().next_back();
+            match (first, last) {
+                (Some(first), Some(last)) => {
+                    // The values are the nanoseconds since midnight, the range doesn't wrap
+                    // around to the next day.
+                    let first = range_start(first, every, offset, |d, t| {
+                        Ok(if d.negative {
+                            t - d.nanoseconds()
+                        } else {
+                            t + d.nanoseconds()
+                        })
+                    })?;
+                    let last = extend_to_end(last, end)?;
+                    polars_ensure!(
+                        first >= 0 && last < NS_DAY,
+                        ComputeError: "upsample range of the Time index column '{}' crosses the 24h boundary",
+                        index_col_name
+                    );
+                    let values = stepped_range(first, last, step, closed);
+                    let mut range = Int64Chunked::from_vec(index_col_name, values);
+                    range.set_sorted_flag(IsSorted::Ascending);
+                    Ok(range.into_time().into_series())
+                },
+                _ => polars_bail!(
+                    ComputeError: "cannot determine upsample boundaries: all elements are null"
+                ),
+            }
+        },
+        dt => polars_bail!(
+            ComputeError: "upsample not allowed for index column of dtype {}", dt,
+        ),
+    }
+}
+
+/// Check that an index column of `dtype` can be upsampled with `every` and `offset`.
+fn check_range_args(dtype: &DataType, every: Duration, offset: Duration) -> PolarsResult<()> {
+    use DataType::*;
+    match dtype {
+        Datetime(_, _) => {
+            polars_ensure!(
+                !every.negative && !every.is_zero(),
+                ComputeError: "`every` must be positive"
+            );
+            polars_ensure!(
+                !every.parsed_int || (every.is_constant_duration() && offset.is_zero()),
+                ComputeError: "upsample of a Datetime index column with an index count `every` requires a zero `offset`"
+            );
+        },
+        Date => {
+            polars_ensure!(
+                !every.negative && !every.is_zero(),
+                ComputeError: "`every` must be positive"
+            );
+            polars_ensure!(
+                !every.parsed_int && every.is_full_days(),
+                ComputeError: "upsample of a Date index column requires `every` to be a whole number of days, e.g. '1d'"
+            );
+            // A sub-day offset would be truncated to the day it lands on.
+            polars_ensure!(
+                offset.is_zero() || (!offset.parsed_int && offset.is_full_days()),
+                ComputeError: "upsample of a Date index column requires `offset` to be a whole number of days, e.g. '1d'"
+            );
+        },
+        Int32 | Int64 | UInt64 => {
+            polars_ensure!(
+                every.parsed_int && every.is_constant_duration(),
+                ComputeError: "upsample of an integer index column requires `every` to be an index count, e.g. '1i'"
+            );
+            polars_ensure!(
+                offset.is_zero() || (offset.parsed_int && offset.is_constant_duration()),
+                ComputeError: "upsample of an integer index column requires `offset` to be an index count, e.g. '1i'"
+            );
+            polars_ensure!(
+                every.nanoseconds() > 0 && !every.negative,
+                ComputeError: "`every` must be positive"
+            );
+        },
+        Time => {
+            polars_ensure!(
+                !every.parsed_int && every.is_constant_duration() && offset.is_constant_duration(),
+                ComputeError: "upsample of a Time index column requires `every` and `offset` to be shorter than a day, e.g. '5m'"
+            );
+            polars_ensure!(
+                every.nanoseconds() > 0 && !every.negative,
+                ComputeError: "`every` must be positive"
+            );
+        },
+        dt => polars_bail!(
+            ComputeError: "upsample not allowed for index column of dtype {}", dt,
+        ),
+    }
+    Ok(())
+}
+
+/// Apply `offset` to the `first` element of the index column to get the start of the range.
+///
+/// A negative `offset` moves the start back in whole steps of `every`, so that `first` stays
+/// on the range: the range starts at the latest such step at or before `first + offset`.
+fn range_start<F>(first: i64, every: Duration, offset: Duration, add: F) -> PolarsResult<i64>
+where
+    F: Fn(&Duration, i64) -> PolarsResult<i64>,
+{
+    if !offset.negative || offset.is_zero() {
+        return add(&offset, first);
+    }
+    let target = add(&offset, first)?;
+    let steps = if every.is_constant_duration() {
+        let step = add(&every, first)? - first;
+        (first - target + step - 1) / step
+    } else {
+        // Calendar durations don't have a fixed length.
+        let mut steps = 1;
+        while add(&(every * -steps), first)? > target {
+            steps += 1;
+        }
+        steps
+    };
+    add(&(every * -steps), first)
+}
+
+/// Create the values `first, first + step, ..` up to and including `last`, without the
+/// boundaries that are excluded by `closed`.
+fn stepped_range(first: i64, last: i64, step: i64, closed: ClosedWindow) -> Vec<i64> {
+    if last < first {
+        return vec![];
+    }
+    let skip_first = matches!(closed, ClosedWindow::Right | ClosedWindow::None);
+    let mut values = (first..=last)
+        .step_by(step as usize)
+        .skip(skip_first as usize)
+        .collect::<Vec<_>>();
+    if matches!(closed, ClosedWindow::Left | ClosedWindow::None) && values.last() == Some(&last) {
+        values.pop();
+    }
+    values
+}
+
+/// Extend the `last` element of the range to the `end` boundary.
+fn extend_to_end(last: i64, end: Option<i64>) -> PolarsResult<i64> {
+    match end {
+        Some(end) => {
+            polars_ensure!(
+                end >= last,
+                ComputeError: "upsample `end` must not be before the last element of the index column"
+            );
+            Ok(end)
+        },
+        None => Ok(last),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::{Datelike, NaiveDate, NaiveDateTime, Weekday};
+    use polars_core::prelude::*;
+    use polars_core::series::IsSorted;
+    use polars_ops::prelude::{interpolate, InterpolationMethod};
+
+    use crate::prelude::*;
+
+    fn hm(hour: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2021, 12, 16)
+            .unwrap()
+            .and_hms_opt(hour, min, 0)
+            .unwrap()
+    }
+
+    fn upsample_fill(df: &DataFrame, every: &str, fill: UpsampleFill) -> DataFrame {
+        let options = UpsampleOptions {
+            fill: UpsampleFillOptions {
+                strategy: fill,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        df.upsample_with_options::<[String; 0]>(
+            [],
+            "time",
+            Duration::parse(every),
+            Duration::parse("0"),
+            &options,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_upsample_groupby() {
+        let time = date_range(
+            "time",
+            NaiveDate::from_ymd_opt(2021, 12, 16)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+            NaiveDate::from_ymd_opt(2021, 12, 16)
+                .unwrap()
+                .and_hms_opt(2, 0, 0)
+                .unwrap(),
+            Duration::parse("30m"),
+            ClosedWindow::Both,
+            TimeUnit::Milliseconds,
+            None,
+        )
+        .unwrap();
+        let df = df!(
+            "time" => time,
+            "groups" => &["a", "a", "b", "b", "b"],
+            "values" => &[1.0, 2.0, 3.0, 4.0, 5.0],
+        )
+        .unwrap();
+        let out1 = df
+            .clone()
+            .upsample_stable::<[String; 1]>(
+                [String::from("groups")],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0"),
+            )
+            .unwrap();
+        let df = df!(
+            "time" => &[
+            NaiveDate::from_ymd_opt(2021, 12, 16).unwrap().and_hms_opt(0, 0, 0).unwrap(),
+            NaiveDate::from_ymd_opt(2021, 12, 16).unwrap().and_hms_opt(0, 15, 0).unwrap(),
+            NaiveDate::from_ymd_opt(2021, 12, 16).unwrap().and_hms_opt(0, 30, 0).unwrap(),
+            NaiveDate::from_ymd_opt(2021, 12, 16).unwrap().and_hms_opt(1, 0, 0).unwrap(),
+            NaiveDate::from_ymd_opt(2021, 12, 16).unwrap().and_hms_opt(1, 15, 0).unwrap(),
+            NaiveDate::from_ymd_opt(2021, 12, 16).unwrap().and_hms_opt(1, 30, 0).unwrap(),
+            NaiveDate::from_ymd_opt(2021, 12, 16).unwrap().and_hms_opt(1, 45, 0).unwrap(),
+            NaiveDate::from_ymd_opt(2021, 12, 16).unwrap().and_hms_opt(2, 0, 0).unwrap(),
+            ],
+            "groups" => &["a", "a", "a", "b", "b", "b", "b", "b"],
+            "values" => &[Some(1.0), None, Some(2.0), Some(3.0), None, Some(4.0), None, Some(5.0)],
+        )
+        .unwrap();
+        assert_eq!(df, out1)
+    }
+
+    #[test]
+    fn test_upsample_fill_linear_irregular() {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 15), hm(1, 0)],
+            "values" => &[0.0, 1.0, 5.0],
+            "labels" => &["x", "y", "z"],
+        )
+        .unwrap();
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+
+        let out = upsample_fill(&df, "15m", UpsampleFill::Linear);
+        let values = out.column("values").unwrap().f64().unwrap();
+        let expected = [0.0, 1.0, 1.0 + 4.0 / 3.0, 1.0 + 8.0 / 3.0, 5.0];
+        assert_eq!(values.len(), expected.len());
+        for (v, e) in values.into_iter().zip(expected) {
+            assert!((v.unwrap() - e).abs() < 1e-9);
+        }
+        // non-numeric columns are forward filled
+        assert_eq!(
+            Vec::from(out.column("labels").unwrap().utf8().unwrap()),
+            &[Some("x"), Some("y"), Some("y"), Some("y"), Some("z")]
+        );
+    }
+
+    #[test]
+    fn test_upsample_fill_linear_matches_interpolate() {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(1, 0), hm(2, 0)],
+            "values" => &[1.0, 3.0, 7.0],
+        )
+        .unwrap();
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+
+        let out = upsample_fill(&df, "30m", UpsampleFill::Linear);
+        let upsampled = upsample_fill(&df, "30m", UpsampleFill::Null);
+        let expected = interpolate(
+            upsampled.column("values").unwrap(),
+            InterpolationMethod::Linear,
+        );
+        assert!(out.column("values").unwrap().equals_missing(&expected));
+    }
+
+    #[test]
+    fn test_upsample_fill_strategies() {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(1, 0)],
+            "values" => &[1, 2],
+        )
+        .unwrap();
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+
+        for (fill, expected) in [
+            (UpsampleFill::Null, [Some(1), None, None, Some(2)]),
+            (UpsampleFill::Forward, [Some(1), Some(1), Some(1), Some(2)]),
+            (UpsampleFill::Backward, [Some(1), Some(2), Some(2), Some(2)]),
+            (UpsampleFill::Nearest, [Some(1), Some(1), Some(2), Some(2)]),
+        ] {
+            let out = upsample_fill(&df, "20m", fill);
+            assert_eq!(
+                Vec::from(out.column("values").unwrap().i32().unwrap()),
+                &expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_upsample_integer_index() {
+        let df = df!(
+            "idx" => &[0i64, 1, 4, 10],
+            "values" => &[1, 2, 3, 4],
+        )
+        .unwrap();
+        let df = df.with_sorted_flag("idx", IsSorted::Ascending).unwrap();
+
+        let out = df
+            .upsample::<[String; 0]>([], "idx", Duration::parse("2i"), Duration::parse("0"))
+            .unwrap();
+        let expected = df!(
+            "idx" => &[0i64, 2, 4, 6, 8, 10],
+            "values" => &[Some(1), None, Some(3), None, None, Some(4)],
+        )
+        .unwrap();
+        assert!(out.equals_missing(&expected));
+        assert_eq!(out.column("idx").unwrap().dtype(), &DataType::Int64);
+
+        // a single element
+        let df = df!("idx" => &[3i32], "values" => &[1])
+            .unwrap()
+            .with_sorted_flag("idx", IsSorted::Ascending)
+            .unwrap();
+        let out = df
+            .upsample::<[String; 0]>([], "idx", Duration::parse("1i"), Duration::parse("0"))
+            .unwrap();
+        assert!(out.equals_missing(&df));
+        assert_eq!(out.column("idx").unwrap().dtype(), &DataType::Int32);
+
+        // a time based `every` is not an index count
+        assert!(df
+            .upsample::<[String; 0]>([], "idx", Duration::parse("1h"), Duration::parse("0"))
+            .is_err());
+
+        // UInt64 values above `i64::MAX` can't be upsampled, instead of being dropped
+        let df = df!("idx" => &[u64::MAX - 2, u64::MAX], "values" => &[1, 2]).unwrap();
+        let df = df.with_sorted_flag("idx", IsSorted::Ascending).unwrap();
+        let err = df
+            .upsample::<[String; 0]>([], "idx", Duration::parse("1i"), Duration::parse("0"))
+            .unwrap_err();
+        assert!(matches!(err, PolarsError::ComputeError(_)));
+    }
+
+    #[test]
+    fn test_upsample_date() {
+        let date = |month, day| NaiveDate::from_ymd_opt(2021, month, day).unwrap();
+        let df = df!(
+            "date" => &[date(1, 30), date(2, 2)],
+            "values" => &[1, 2],
+        )
+        .unwrap();
+        let df = df.with_sorted_flag("date", IsSorted::Ascending).unwrap();
+
+        let out = df
+            .upsample::<[String; 0]>([], "date", Duration::parse("1d"), Duration::parse("0"))
+            .unwrap();
+        let expected = df!(
+            "date" => &[date(1, 30), date(1, 31), date(2, 1), date(2, 2)],
+            "values" => &[Some(1), None, None, Some(2)],
+        )
+        .unwrap();
+        assert!(out.equals_missing(&expected));
+        assert_eq!(out.column("date").unwrap().dtype(), &DataType::Date);
+
+        // a sub-day `every` would create dates in between
+        assert!(df
+            .upsample::<[String; 0]>([], "date", Duration::parse("12h"), Duration::parse("0"))
+            .is_err());
+        // a sub-day `offset` would be truncated
+        let err = df
+            .upsample::<[String; 0]>([], "date", Duration::parse("1d"), Duration::parse("12h"))
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("`offset` to be a whole number of days"));
+        let out = df
+            .upsample::<[String; 0]>([], "date", Duration::parse("1d"), Duration::parse("1d"))
+            .unwrap();
+        assert_eq!(out.column("date").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_upsample_end() {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 30)],
+            "values" => &[1, 2],
+        )
+        .unwrap();
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+
+        let upsample = |end: NaiveDateTime, fill| {
+            let options = UpsampleOptions {
+                grid: UpsampleGridOptions {
+                    end: Some(end.timestamp_millis()),
+                    ..Default::default()
+                },
+                fill: UpsampleFillOptions {
+                    strategy: fill,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0"),
+                &options,
+            )
+        };
+        // two intervals past the last point
+        let out = upsample(hm(1, 0), UpsampleFill::Null).unwrap();
+        let expected = df!(
+            "time" => &[hm(0, 0), hm(0, 15), hm(0, 30), hm(0, 45), hm(1, 0)],
+            "values" => &[Some(1), None, Some(2), None, None],
+        )
+        .unwrap();
+        assert!(out.equals_missing(&expected));
+
+        let out = upsample(hm(1, 0), UpsampleFill::Forward).unwrap();
+        assert_eq!(
+            Vec::from(out.column("values").unwrap().i32().unwrap()),
+            &[Some(1), Some(1), Some(2), Some(2), Some(2)]
+        );
+
+        assert!(upsample(hm(0, 15), UpsampleFill::Null).is_err());
+    }
+
+    #[test]
+    fn test_upsample_closed() {
+        // 00:20 is not on the grid
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 20), hm(1, 0)],
+            "values" => &[1, 2, 3],
+        )
+        .unwrap();
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let idx = df!("idx" => &[0i64, 1, 3]).unwrap();
+        let idx = idx.with_sorted_flag("idx", IsSorted::Ascending).unwrap();
+
+        for (closed, expected_time, expected_idx) in [
+            (
+                ClosedWindow::Both,
+                vec![hm(0, 0), hm(0, 15), hm(0, 30), hm(0, 45), hm(1, 0)],
+                vec![0i64, 1, 2, 3],
+            ),
+            (
+                ClosedWindow::Right,
+                vec![hm(0, 15), hm(0, 30), hm(0, 45), hm(1, 0)],
+                vec![1, 2, 3],
+            ),
+            (
+                ClosedWindow::Left,
+                vec![hm(0, 0), hm(0, 15), hm(0, 30), hm(0, 45)],
+                vec![0, 1, 2],
+            ),
+            (
+                ClosedWindow::None,
+                vec![hm(0, 15), hm(0, 30), hm(0, 45)],
+                vec![1, 2],
+            ),
+        ] {
+            let options = UpsampleOptions {
+                grid: UpsampleGridOptions {
+                    closed,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let out = df
+                .upsample_with_options::<[String; 0]>(
+                    [],
+                    "time",
+                    Duration::parse("15m"),
+                    Duration::parse("0"),
+                    &options,
+                )
+                .unwrap();
+            let time = DatetimeChunked::new("time", &expected_time).into_series();
+            assert!(out.column("time").unwrap().equals(&time), "{closed:?}");
+
+            let out = idx
+                .upsample_with_options::<[String; 0]>(
+                    [],
+                    "idx",
+                    Duration::parse("1i"),
+                    Duration::parse("0"),
+                    &options,
+                )
+                .unwrap();
+            assert_eq!(
+                Vec::from(out.column("idx").unwrap().i64().unwrap()),
+                expected_idx.into_iter().map(Some).collect::<Vec<_>>(),
+                "{closed:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_upsample_unsorted_group() {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(1, 0), hm(0, 30), hm(2, 0)],
+            "groups" => &["a", "b", "b", "a"],
+            "values" => &[1, 2, 3, 4],
+        )
+        .unwrap();
+        // the sorted flag is set, but group "b" is not sorted
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+
+        let err = df
+            .upsample_stable::<[String; 1]>(
+                [String::from("groups")],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0"),
+            )
+            .unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("not sorted in group [groups=\"b\"]"), "{msg}");
+    }
+
+    #[test]
+    fn test_upsample_by_fill() {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 30), hm(0, 0), hm(0, 30)],
+            "groups" => &[Some("a"), Some("a"), None, None],
+            "values" => &[1, 2, 3, 4],
+        )
+        .unwrap();
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+
+        let upsample = |by_fill| {
+            // the first row of every group is inserted
+            let options = UpsampleOptions {
+                grid: UpsampleGridOptions {
+                    closed: ClosedWindow::Right,
+                    ..Default::default()
+                },
+                fill: UpsampleFillOptions {
+                    by: by_fill,
+                    ..Default::default()
+                },
+                maintain_order: true,
+                ..Default::default()
+            };
+            df.upsample_with_options::<[String; 1]>(
+                [String::from("groups")],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0"),
+                &options,
+            )
+            .unwrap()
+        };
+        // The inserted first row has no key to forward.
+        let out = upsample(UpsampleByFill::Forward);
+        assert_eq!(
+            Vec::from(out.column("groups").unwrap().utf8().unwrap()),
+            &[None, Some("a"), None, None]
+        );
+        let out = upsample(UpsampleByFill::GroupKey);
+        assert_eq!(
+            Vec::from(out.column("groups").unwrap().utf8().unwrap()),
+            &[Some("a"), Some("a"), None, None]
+        );
+        assert_eq!(
+            Vec::from(out.column("values").unwrap().i32().unwrap()),
+            &[None, Some(2), None, Some(4)]
+        );
+    }
+
+    #[test]
+    fn test_upsample_by_column_fill() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 15), hm(0, 30), hm(0, 15), hm(0, 30)],
+            "groups" => &["a", "a", "b", "b"],
+            "labels" => &[Some("x"), Some("x"), None, None],
+            "values" => &[1, 2, 3, 4],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |by_column_fill: &[(&str, UpsampleByFill)]| {
+            // The first row of every group is inserted.
+            let options = UpsampleOptions {
+                fill: UpsampleFillOptions {
+                    by_columns: by_column_fill
+                        .iter()
+                        .map(|(name, fill)| (name.to_string(), *fill))
+                        .collect(),
+                    ..Default::default()
+                },
+                maintain_order: true,
+                ..Default::default()
+            };
+            df.upsample_with_options(
+                ["groups", "labels"],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("-15m"),
+                &options,
+            )
+        };
+        let out = upsample(&[("labels", UpsampleByFill::Forward)])?;
+        assert_eq!(
+            Vec::from(out.column("groups")?.utf8()?),
+            &[
+                Some("a"),
+                Some("a"),
+                Some("a"),
+                Some("b"),
+                Some("b"),
+                Some("b")
+            ]
+        );
+        assert_eq!(
+            Vec::from(out.column("labels")?.utf8()?),
+            &[None, Some("x"), Some("x"), None, None, None]
+        );
+        let out = upsample(&[])?;
+        assert_eq!(
+            Vec::from(out.column("labels")?.utf8()?),
+            &[Some("x"), Some("x"), Some("x"), None, None, None]
+        );
+        assert!(upsample(&[("values", UpsampleByFill::Forward)]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_multi() {
+        let df = df!(
+            "asof" => &[hm(0, 30), hm(0, 0)],
+            "observed" => &[hm(0, 0), hm(1, 0)],
+            "values" => &[1, 2],
+        )
+        .unwrap();
+
+        let upsample = |max_cells| {
+            let options = UpsampleOptions {
+                max_cells,
+                ..Default::default()
+            };
+            df.upsample_multi::<[String; 0]>(
+                [],
+                &["asof", "observed"],
+                &[Duration::parse("15m"), Duration::parse("30m")],
+                &[Duration::parse("0"), Duration::parse("0")],
+                &options,
+            )
+        };
+        let out = upsample(9).unwrap();
+        let asof = [hm(0, 0), hm(0, 15), hm(0, 30)];
+        let observed = [hm(0, 0), hm(0, 30), hm(1, 0)];
+        let expected = df!(
+            "asof" => asof.iter().flat_map(|t| [*t; 3]).collect::<Vec<_>>(),
+            "observed" => (0..3).flat_map(|_| observed).collect::<Vec<_>>(),
+            "values" => &[None, None, Some(2), None, None, None, Some(1), None, None],
+        )
+        .unwrap();
+        assert!(out.equals_missing(&expected));
+
+        assert!(upsample(8).is_err());
+    }
+
+    #[test]
+    fn test_upsample_indicator() {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 45), hm(1, 0)],
+            "values" => &[1, 2, 3],
+        )
+        .unwrap();
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+
+        let options = UpsampleOptions {
+            indicator: true,
+            ..Default::default()
+        };
+        let out = df
+            .upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0"),
+                &options,
+            )
+            .unwrap();
+        let indicator = out.column("_upsampled").unwrap().bool().unwrap();
+        assert_eq!(
+            Vec::from(indicator),
+            &[
+                Some(false),
+                Some(true),
+                Some(true),
+                Some(false),
+                Some(false)
+            ]
+        );
+        // aligns with the rows without a value
+        let values = out.column("values").unwrap();
+        assert_eq!(Vec::from(indicator), Vec::from(&values.is_null()));
+
+        // the name must not clash with the source
+        let options = UpsampleOptions {
+            indicator: true,
+            indicator_name: "values".to_string(),
+            ..Default::default()
+        };
+        assert!(df
+            .upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0"),
+                &options,
+            )
+            .is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "timezones")]
+    fn test_upsample_dst() {
+        let utc = |month, day, hour, min| {
+            NaiveDate::from_ymd_opt(2021, month, day)
+                .unwrap()
+                .and_hms_opt(hour, min, 0)
+                .unwrap()
+                .timestamp_millis()
+        };
+        let upsample = |times: &[i64], every, options: &UpsampleOptions| {
+            let time = Int64Chunked::from_slice("time", times)
+                .into_datetime(TimeUnit::Milliseconds, Some("America/New_York".to_string()));
+            let df = df!("time" => time.into_series(), "values" => (0..times.len() as i32).collect::<Vec<_>>())
+                .unwrap();
+            let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+            df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse(every),
+                Duration::parse("0"),
+                options,
+            )
+            .map(|out| {
+                Vec::from(
+                    out.column("time")
+                        .unwrap()
+                        .to_physical_repr()
+                        .i64()
+                        .unwrap(),
+                )
+            })
+        };
+        let options = UpsampleOptions::default();
+        let hourly = |from, to| (from..=to).map(|h| Some(h * 3_600_000)).collect::<Vec<_>>();
+
+        // spring forward: 00:00 EST up to 05:00 EDT, there is no 02:00
+        let out = upsample(&[utc(3, 14, 5, 0), utc(3, 14, 9, 0)], "1h", &options).unwrap();
+        let expected = hourly(0, 4)
+            .into_iter()
+            .map(|t| t.map(|t| t + utc(3, 14, 5, 0)))
+            .collect::<Vec<_>>();
+        assert_eq!(out, expected);
+
+        // fall back: starting on the ambiguous 01:30 EDT, 01:30 EST is in the range once
+        let out = upsample(&[utc(11, 7, 5, 30), utc(11, 7, 8, 30)], "1h", &options).unwrap();
+        let expected = hourly(0, 3)
+            .into_iter()
+            .map(|t| t.map(|t| t + utc(11, 7, 5, 30)))
+            .collect::<Vec<_>>();
+        assert_eq!(out, expected);
+
+        // a calendar day lands on the non-existent 02:30 EST -> EDT
+        let times = [utc(3, 13, 7, 30), utc(3, 15, 6, 30)];
+        let out = upsample(&times, "1d", &options).unwrap();
+        assert_eq!(out, &[Some(times[0]), Some(times[1])]);
+        let raise = UpsampleOptions {
+            grid: UpsampleGridOptions {
+                non_existent: UpsampleNonExistent::Raise,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(upsample(&times, "1d", &raise).is_err());
+
+        // a calendar day lands on the ambiguous 01:30
+        let times = [utc(11, 6, 5, 30), utc(11, 8, 6, 30)];
+        let out = upsample(&times, "1d", &options).unwrap();
+        assert_eq!(
+            out,
+            &[Some(times[0]), Some(utc(11, 7, 5, 30)), Some(times[1])]
+        );
+        let latest = UpsampleOptions {
+            grid: UpsampleGridOptions {
+                ambiguous: UpsampleAmbiguous::Latest,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let out = upsample(&times, "1d", &latest).unwrap();
+        assert_eq!(
+            out,
+            &[Some(times[0]), Some(utc(11, 7, 6, 30)), Some(times[1])]
+        );
+        let raise = UpsampleOptions {
+            grid: UpsampleGridOptions {
+                ambiguous: UpsampleAmbiguous::Raise,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(upsample(&times, "1d", &raise).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "timezones")]
+    fn test_upsample_dst_mixed_every() {
+        let utc = |day, hour| {
+            NaiveDate::from_ymd_opt(2021, 3, day)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+                .timestamp_millis()
+        };
+        let upsample = |times: &[i64]| {
+            let time = Int64Chunked::from_slice("time", times)
+                .into_datetime(TimeUnit::Milliseconds, Some("America/New_York".to_string()));
+            let df = df!("time" => time.into_series(), "values" => &[1, 2]).unwrap();
+            let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+            df.upsample::<[String; 0]>([], "time", Duration::parse("1d1h"), Duration::parse("0"))
+                .map(|out| {
+                    Vec::from(
+                        out.column("time")
+                            .unwrap()
+                            .to_physical_repr()
+                            .i64()
+                            .unwrap(),
+                    )
+                })
+        };
+
+        // 00:00 EST, 01:00 EST and 02:00 EDT: the days are added in local time across the
+        // spring forward, the hours in elapsed time.
+        let out = upsample(&[utc(13, 5), utc(15, 6)]).unwrap();
+        assert_eq!(out, &[Some(utc(13, 5)), Some(utc(14, 6)), Some(utc(15, 6))]);
+
+        // The hour after 01:00 EST on the 14th is 03:00 EDT, but 02:00 in local time.
+        let err = upsample(&[utc(13, 6), utc(15, 9)]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("crosses a daylight saving time transition"));
+    }
+
+    #[test]
+    #[cfg(feature = "timezones")]
+    fn test_upsample_fixed_offset() -> PolarsResult<()> {
+        // 00:00 and 03:00 in +05:30.
+        let utc = |day, hour, min| {
+            NaiveDate::from_ymd_opt(2021, 3, day)
+                .unwrap()
+                .and_hms_opt(hour, min, 0)
+                .unwrap()
+                .timestamp_millis()
+        };
+        let time = Int64Chunked::from_slice("time", &[utc(13, 18, 30), utc(13, 21, 30)])
+            .into_datetime(TimeUnit::Milliseconds, Some("+05:30".to_string()));
+        let df = df!("time" => time.into_series(), "values" => &[1, 4])?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+
+        let out =
+            df.upsample::<[String; 0]>([], "time", Duration::parse("1h"), Duration::parse("0"))?;
+        let time = out.column("time")?;
+        assert_eq!(
+            time.dtype(),
+            &DataType::Datetime(TimeUnit::Milliseconds, Some("+05:30".to_string()))
+        );
+        assert_eq!(
+            Vec::from(time.to_physical_repr().i64()?),
+            [(18, 30), (19, 30), (20, 30), (21, 30)].map(|(h, m)| Some(utc(13, h, m)))
+        );
+        assert_eq!(
+            Vec::from(out.column("values")?.i32()?),
+            &[Some(1), None, None, Some(4)]
+        );
+
+        // A calendar anchor snaps to the start of the month in the fixed offset, not in UTC.
+        let options = UpsampleOptions {
+            grid: UpsampleGridOptions {
+                anchor: Some(CalendarAnchor::MonthStart),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let out = df.upsample_with_options::<[String; 0]>(
+            [],
+            "time",
+            Duration::parse("1d"),
+            Duration::parse("0"),
+            &options,
+        )?;
+        let time = out.column("time")?.to_physical_repr();
+        let march_first = utc(13, 18, 30) - 13 * MILLISECONDS_IN_DAY;
+        assert_eq!(time.i64()?.get(0), Some(march_first));
+        assert_eq!(time.len(), 14);
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_offset_fraction() -> PolarsResult<()> {
+        let day = |month, day| NaiveDate::from_ymd_opt(2021, month, day).unwrap();
+        let time = Int64Chunked::from_slice(
+            "time",
+            &[
+                day(1, 1).and_hms_opt(0, 0, 0).unwrap().timestamp_millis(),
+                day(4, 20).and_hms_opt(0, 0, 0).unwrap().timestamp_millis(),
+            ],
+        )
+        .into_datetime(TimeUnit::Milliseconds, None);
+        let df = df!("time" => time.into_series(), "values" => &[1, 2])?
+            .with_sorted_flag("time", IsSorted::Ascending)
+            .unwrap();
+        let options = UpsampleOptions {
+            grid: UpsampleGridOptions {
+                offset_fraction: Some(0.5),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let out = df.upsample_with_options::<[String; 0]>(
+            [],
+            "time",
+            Duration::parse("1mo"),
+            Duration::parse("0"),
+            &options,
+        )?;
+        // Half of 31, 28, 31 and 30 days.
+        let expected = [
+            day(1, 16).and_hms_opt(12, 0, 0),
+            day(2, 15).and_hms_opt(0, 0, 0),
+            day(3, 16).and_hms_opt(12, 0, 0),
+            day(4, 16).and_hms_opt(0, 0, 0),
+        ]
+        .map(|t| Some(t.unwrap().timestamp_millis()));
+        assert_eq!(
+            Vec::from(out.column("time")?.to_physical_repr().i64()?),
+            &expected
+        );
+
+        // The absolute offset moves the start of the range first.
+        let out = df.upsample_with_options::<[String; 0]>(
+            [],
+            "time",
+            Duration::parse("1mo"),
+            Duration::parse("1mo"),
+            &options,
+        )?;
+        assert_eq!(
+            Vec::from(out.column("time")?.to_physical_repr().i64()?),
+            &expected[1..]
+        );
+
+        let options = UpsampleOptions {
+            grid: UpsampleGridOptions {
+                offset_fraction: Some(1.5),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(df
+            .upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("1mo"),
+                Duration::parse("0"),
+                &options,
+            )
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_duplicates() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 30), hm(0, 30), hm(1, 0)],
+            "values" => &[1, 2, 4, 5],
+            "labels" => &["a", "b", "c", "d"],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |duplicates| {
+            let options = UpsampleOptions {
+                duplicates,
+                ..Default::default()
+            };
+            df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0"),
+                &options,
+            )
+        };
+
+        assert!(upsample(UpsampleDuplicates::Raise).is_err());
+
+        let out = upsample(UpsampleDuplicates::First)?;
+        assert_eq!(out.height(), 5);
+        assert_eq!(
+            Vec::from(out.column("values")?.i32()?),
+            &[Some(1), None, Some(2), None, Some(5)]
+        );
+        let out = upsample(UpsampleDuplicates::Last)?;
+        assert_eq!(
+            Vec::from(out.column("values")?.i32()?),
+            &[Some(1), None, Some(4), None, Some(5)]
+        );
+        let out = upsample(UpsampleDuplicates::Mean)?;
+        assert_eq!(
+            Vec::from(out.column("values")?.f64()?),
+            &[Some(1.0), None, Some(3.0), None, Some(5.0)]
+        );
+        assert_eq!(
+            Vec::from(out.column("labels")?.utf8()?),
+            &[Some("a"), None, Some("b"), None, Some("d")]
+        );
+
+        let sum: DuplicateReducer = Arc::new(|df: DataFrame| Ok(df.select(["values"])?.sum()));
+        let out = upsample(UpsampleDuplicates::Custom(sum))?;
+        assert_eq!(out.get_column_names(), &["time", "values"]);
+        assert_eq!(
+            Vec::from(out.column("values")?.i32()?),
+            &[Some(1), None, Some(6), None, Some(5)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_align_to() -> PolarsResult<()> {
+        let day = |day| {
+            NaiveDate::from_ymd_opt(2021, 12, day)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        };
+        let df = df!(
+            "time" => (1..=6).map(day).collect::<Vec<_>>(),
+            "groups" => &["a", "a", "a", "b", "b", "b"],
+            "values" => &[1, 2, 3, 4, 5, 6],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        // An irregular, unsorted index with duplicates and a null.
+        let reference = df!(
+            "index" => &[Some(day(9)), Some(day(2)), None, Some(day(5)), Some(day(2)), Some(day(3))],
+        )?;
+
+        let out = df.upsample_align_to::<[String; 0]>([], "time", &reference, "index")?;
+        let expected = df!(
+            "time" => [2, 3, 5, 9].map(day),
+            "groups" => &[Some("a"), Some("a"), Some("b"), None],
+            "values" => &[Some(2), Some(3), Some(5), None],
+        )?;
+        assert!(out.equals_missing(&expected));
+
+        let out = df.upsample_align_to(["groups"], "time", &reference, "index")?;
+        let out = out.sort(["groups"], false, true)?;
+        assert_eq!(
+            Vec::from(out.column("time")?.datetime()?),
+            [[2, 3, 5, 9], [2, 3, 5, 9]]
+                .concat()
+                .into_iter()
+                .map(|d| Some(day(d).timestamp_millis()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Vec::from(out.column("values")?.i32()?),
+            &[Some(2), Some(3), None, None, None, None, Some(5), None]
+        );
+
+        let reference = df!("index" => &[1, 2])?;
+        assert!(df
+            .upsample_align_to::<[String; 0]>([], "time", &reference, "index")
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_to_grid() -> PolarsResult<()> {
+        let day = |day| {
+            NaiveDate::from_ymd_opt(2021, 12, day)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        };
+        // Thursday up to and including Tuesday.
+        let df = df!(
+            "time" => (16..=21).map(day).collect::<Vec<_>>(),
+            "groups" => &["a", "a", "a", "a", "b", "b"],
+            "values" => &[1, 2, 3, 4, 5, 6],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        // The business days up to and including Wednesday.
+        let grid = df!("grid" => [16, 17, 20, 21, 22].map(day))?;
+        let grid = grid.column("grid")?;
+
+        let out = df.upsample_to_grid::<[String; 0]>([], "time", grid)?;
+        let expected = df!(
+            "time" => [16, 17, 20, 21, 22].map(day),
+            "groups" => &[Some("a"), Some("a"), Some("b"), Some("b"), None],
+            "values" => &[Some(1), Some(2), Some(5), Some(6), None],
+        )?;
+        assert!(out.equals_missing(&expected));
+
+        let out = df.upsample_to_grid(["groups"], "time", grid)?;
+        let out = out.sort(["groups"], false, true)?;
+        assert_eq!(out.height(), 10);
+        assert_eq!(
+            Vec::from(out.column("groups")?.utf8()?),
+            [["a"; 5], ["b"; 5]]
+                .concat()
+                .into_iter()
+                .map(Some)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Vec::from(out.column("values")?.i32()?),
+            &[
+                Some(1),
+                Some(2),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(5),
+                Some(6),
+                None
+            ]
+        );
+
+        // The grid must be sorted and have the dtype of the index column.
+        let unsorted = Series::new("grid", [17, 16].map(day));
+        assert!(df
+            .upsample_to_grid::<[String; 0]>([], "time", &unsorted)
+            .is_err());
+        let dates = grid.cast(&DataType::Date)?;
+        assert!(df
+            .upsample_to_grid::<[String; 0]>([], "time", &dates)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_empty() -> PolarsResult<()> {
+        let df = df!(
+            "groups" => &["a"],
+            "time" => &[hm(0, 0)],
+            "values" => &[1],
+        )?;
+        let df = df
+            .clear()
+            .with_sorted_flag("time", IsSorted::Ascending)
+            .unwrap();
+        let options = UpsampleOptions {
+            fill: UpsampleFillOptions {
+                strategy: UpsampleFill::Linear,
+                ..Default::default()
+            },
+            indicator: true,
+            ..Default::default()
+        };
+        let expected = [
+            ("time", df.column("time")?.dtype().clone()),
+            ("groups", DataType::Utf8),
+            ("values", DataType::Float64),
+            ("_upsampled", DataType::Boolean),
+        ];
+        for by in [vec![], vec!["groups".to_string()]] {
+            let (out, report) = df.upsample_with_report(
+                by,
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0"),
+                &options,
+            )?;
+            assert_eq!(out.height(), 0);
+            let schema = out
+                .get_columns()
+                .iter()
+                .map(|s| (s.name(), s.dtype().clone()))
+                .collect::<Vec<_>>();
+            assert_eq!(schema, expected);
+            assert_eq!(report.inserted_rows, 0);
+        }
+
+        // Only nulls in the index column.
+        let time = Series::new("time", &[None::<NaiveDateTime>, None]);
+        let df = df!("time" => time, "values" => &[1, 2])?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |options: &UpsampleOptions| {
+            df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0"),
+                options,
+            )
+        };
+        assert!(upsample(&Default::default()).is_err());
+        let options = UpsampleOptions {
+            empty_on_all_null: true,
+            ..Default::default()
+        };
+        let out = upsample(&options)?;
+        assert_eq!(out.shape(), (0, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_every_finer_than_time_unit() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 0) + chrono::Duration::milliseconds(4)],
+            "values" => &[1, 2],
+        )?;
+        df.try_apply("time", |s| {
+            s.cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+        })?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |every, options: &UpsampleOptions| {
+            df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse(every),
+                Duration::parse("0"),
+                options,
+            )
+        };
+
+        let err = upsample("1ns", &Default::default()).unwrap_err();
+        assert!(err.to_string().contains("1ns"));
+        assert!(err.to_string().contains("(ms)"));
+        assert!(upsample("1500us", &Default::default()).is_err());
+        // Whole time units are fine.
+        assert_eq!(upsample("2000us", &Default::default())?.height(), 3);
+
+        let options = UpsampleOptions {
+            grid: UpsampleGridOptions {
+                round_every: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        // Rounded up to 1ms and 2ms.
+        assert_eq!(upsample("1ns", &options)?.height(), 5);
+        assert_eq!(upsample("1500us", &options)?.height(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_negative_offset() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(2, 0), hm(3, 0), hm(4, 0)],
+            "values" => &[1, 2, 3],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |offset| {
+            df.upsample::<[String; 0]>([], "time", Duration::parse("1h"), Duration::parse(offset))
+        };
+
+        let out = upsample("-1h")?;
+        let expected = df!(
+            "time" => &[hm(1, 0), hm(2, 0), hm(3, 0), hm(4, 0)],
+            "values" => &[None, Some(1), Some(2), Some(3)],
+        )?;
+        assert!(out.equals_missing(&expected));
+
+        // The range starts at the grid point before the offset, so all rows are kept.
+        let out = upsample("-90m")?;
+        let expected = df!(
+            "time" => &[hm(0, 0), hm(1, 0), hm(2, 0), hm(3, 0), hm(4, 0)],
+            "values" => &[None, None, Some(1), Some(2), Some(3)],
+        )?;
+        assert!(out.equals_missing(&expected));
+
+        // Also for an integer index.
+        let df = df!("idx" => &[10i64, 12], "values" => &[1, 2])?
+            .with_sorted_flag("idx", IsSorted::Ascending)
+            .unwrap();
+        let out =
+            df.upsample::<[String; 0]>([], "idx", Duration::parse("2i"), Duration::parse("-3i"))?;
+        assert_eq!(
+            Vec::from(out.column("idx")?.i64()?),
+            &[Some(6), Some(8), Some(10), Some(12)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_negative_offset_by() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(2, 0), hm(3, 0), hm(2, 0)],
+            "groups" => &["a", "a", "b"],
+            "values" => &[1, 2, 3],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let options = UpsampleOptions {
+            maintain_order: true,
+            ..Default::default()
+        };
+        let out = df.upsample_with_options(
+            ["groups"],
+            "time",
+            Duration::parse("1h"),
+            Duration::parse("-1h"),
+            &options,
+        )?;
+        // The rows before the first row of a group get the key of the group.
+        let expected = df!(
+            "time" => &[hm(1, 0), hm(2, 0), hm(3, 0), hm(1, 0), hm(2, 0)],
+            "groups" => &["a", "a", "a", "b", "b"],
+            "values" => &[None, Some(1), Some(2), None, Some(3)],
+        )?;
+        assert!(out.equals_missing(&expected));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_unaligned() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 40), hm(1, 0), hm(2, 0)],
+            "values" => &[1, 2, 3, 4],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |on_unaligned| {
+            let options = UpsampleOptions {
+                fill: UpsampleFillOptions {
+                    strategy: UpsampleFill::Forward,
+                    ..Default::default()
+                },
+                join: UpsampleJoinOptions {
+                    on_unaligned,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("30m"),
+                Duration::parse("0"),
+                &options,
+            )
+        };
+
+        let out = upsample(UpsampleUnaligned::Drop)?;
+        let expected = df!(
+            "time" => &[hm(0, 0), hm(0, 30), hm(1, 0), hm(1, 30), hm(2, 0)],
+            "values" => &[1, 1, 3, 3, 4],
+        )?;
+        assert!(out.equals(&expected));
+
+        let err = upsample(UpsampleUnaligned::Error).unwrap_err();
+        assert!(err.to_string().contains("00:40:00"));
+
+        // The unaligned row is kept and used to fill the next inserted row.
+        let out = upsample(UpsampleUnaligned::OuterJoin)?;
+        let expected = df!(
+            "time" => &[hm(0, 0), hm(0, 30), hm(0, 40), hm(1, 0), hm(1, 30), hm(2, 0)],
+            "values" => &[1, 1, 2, 3, 3, 4],
+        )?;
+        assert!(out.equals(&expected));
+
+        // Rows on the range are never unaligned.
+        let df = df
+            .slice(2, 2)
+            .with_sorted_flag("time", IsSorted::Ascending)
+            .unwrap();
+        let options = UpsampleOptions {
+            join: UpsampleJoinOptions {
+                on_unaligned: UpsampleUnaligned::Error,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let out = df.upsample_with_options::<[String; 0]>(
+            [],
+            "time",
+            Duration::parse("30m"),
+            Duration::parse("0"),
+            &options,
+        )?;
+        assert_eq!(out.height(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_auto() -> PolarsResult<()> {
+        let upsample = |times: &[NaiveDateTime]| {
+            let df = df!("time" => times, "values" => (0..times.len() as i32).collect::<Vec<_>>())?;
+            df.with_sorted_flag("time", IsSorted::Ascending)
+                .unwrap()
+                .upsample_auto::<[String; 0]>([], "time")
+        };
+
+        let times = [hm(0, 0), hm(0, 15), hm(0, 30), hm(0, 45)];
+        assert_eq!(upsample(&times)?.height(), 4);
+
+        // A gap is filled at the common spacing.
+        let times = [
+            hm(0, 0),
+            hm(0, 15),
+            hm(0, 30),
+            hm(1, 15),
+            hm(1, 30),
+            hm(1, 45),
+        ];
+        let out = upsample(&times)?;
+        assert_eq!(out.height(), 8);
+        assert_eq!(out.column("values")?.null_count(), 2);
+
+        // 15m is the most common delta, but the deltas vary too much.
+        let times = [
+            hm(0, 0),
+            hm(0, 15),
+            hm(0, 30),
+            hm(0, 45),
+            hm(5, 45),
+            hm(15, 45),
+        ];
+        assert!(upsample(&times).is_err());
+
+        // 15m and 30m are equally common.
+        let times = [hm(0, 0), hm(0, 15), hm(0, 45), hm(1, 0), hm(1, 30)];
+        assert!(upsample(&times).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_descending() -> PolarsResult<()> {
+        let mut df = df!(
+            "time" => &[hm(1, 0), hm(0, 30), hm(0, 0)],
+            "values" => &[3, 2, 1],
+        )?
+        .with_sorted_flag("time", IsSorted::Descending)?;
+
+        let options = UpsampleOptions {
+            fill: UpsampleFillOptions {
+                strategy: UpsampleFill::Forward,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let out = df.upsample_with_options::<[String; 0]>(
+            [],
+            "time",
+            Duration::parse("15m"),
+            Duration::parse("0"),
+            &options,
+        )?;
+        let expected = df!(
+            "time" => &[hm(1, 0), hm(0, 45), hm(0, 30), hm(0, 15), hm(0, 0)],
+            // Forward fills in time, not in the order of the rows.
+            "values" => &[3, 2, 2, 1, 1],
+        )?;
+        assert!(out.equals(&expected));
+        assert_eq!(out.column("time")?.is_sorted_flag(), IsSorted::Descending);
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_skeleton() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(1, 0), hm(0, 30), hm(2, 0), hm(2, 15)],
+            "groups" => &["a", "a", "b", "b", "b"],
+            "values" => &[1, 2, 3, 4, 5],
+        )?;
+        // The groups are sorted.
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |df: &DataFrame, by: &[&str]| -> PolarsResult<_> {
+            let by = by.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+            let every = Duration::parse("15m");
+            let offset = Duration::parse("0");
+            let skeleton = df.upsample_skeleton(by.clone(), "time", every, offset)?;
+            let full = df.upsample_stable(by, "time", every, offset)?;
+            Ok((skeleton, full))
+        };
+
+        let (skeleton, full) = upsample(&df, &["groups"])?;
+        assert_eq!(skeleton.get_column_names(), &["time", "groups"]);
+        assert_eq!(skeleton.height(), full.height());
+
+        let df = df
+            .filter(&df.column("groups")?.equal("b")?)?
+            .with_sorted_flag("time", IsSorted::Ascending)
+            .unwrap();
+        let (skeleton, full) = upsample(&df, &[])?;
+        assert_eq!(skeleton.get_column_names(), &["time"]);
+        assert!(skeleton.column("time")?.equals(full.column("time")?));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_sorted_merge_join() -> PolarsResult<()> {
+        let times = [
+            Some(hm(0, 0)),
+            Some(hm(0, 20)),
+            Some(hm(1, 0)),
+            None,
+            Some(hm(0, 10)),
+            Some(hm(0, 30)),
+            Some(hm(2, 30)),
+        ];
+        let df = df!(
+            "time" => &times,
+            "groups" => &["a", "a", "a", "b", "b", "b", "b"],
+            "values" => &[1, 2, 3, 4, 5, 6, 7],
+        )?;
+        // The groups are sorted.
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |join, on_unaligned| {
+            let options = UpsampleOptions {
+                fill: UpsampleFillOptions {
+                    strategy: UpsampleFill::Forward,
+                    ..Default::default()
+                },
+                join: UpsampleJoinOptions {
+                    on_unaligned,
+                    algorithm: join,
+                    ..Default::default()
+                },
+                maintain_order: true,
+                ..Default::default()
+            };
+            df.upsample_with_options(
+                ["groups"],
+                "time",
+                Duration::parse("10m"),
+                Duration::parse("0"),
+                &options,
+            )
+        };
+        for on_unaligned in [UpsampleUnaligned::Drop, UpsampleUnaligned::OuterJoin] {
+            let hash = upsample(UpsampleJoin::Hash, on_unaligned)?;
+            let merge = upsample(UpsampleJoin::SortedMerge, on_unaligned)?;
+            assert!(hash.equals_missing(&merge));
+        }
+
+        // A range that is not strictly increasing falls back to the hash join.
+        let source = df.slice(0, 3).drop("groups")?;
+        let range = source.column("time")?.reverse();
+        let hash = super::join_range(range.clone(), &source, UpsampleJoin::Hash)?;
+        let merge = super::join_range(range, &source, UpsampleJoin::SortedMerge)?;
+        assert!(hash.equals(&merge));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_column_order() -> PolarsResult<()> {
+        let df = df!(
+            "values" => &[1, 2, 3],
+            "time" => &[hm(0, 0), hm(1, 0), hm(0, 30)],
+            "labels" => &["x", "y", "z"],
+            "groups" => &["a", "a", "b"],
+        )?;
+        // The groups are sorted.
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let every = Duration::parse("15m");
+        let offset = Duration::parse("0");
+
+        let out = df.upsample(["groups"], "time", every, offset)?;
+        assert_eq!(out.get_column_names(), df.get_column_names());
+        let out = df.upsample::<[String; 0]>([], "time", every, offset)?;
+        assert_eq!(out.get_column_names(), df.get_column_names());
+        let out = df.clear().upsample(["groups"], "time", every, offset)?;
+        assert_eq!(out.get_column_names(), df.get_column_names());
+        let out = df.upsample_skeleton(["groups"], "time", every, offset)?;
+        assert_eq!(out.get_column_names(), &["time", "groups"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_schema() -> PolarsResult<()> {
+        let df = df!(
+            "values" => &[1, 2, 3],
+            "time" => &[hm(0, 0), hm(0, 0), hm(1, 0)],
+            "floats" => &[1.0f32, 2.0, 3.0],
+            "labels" => &["x", "y", "z"],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        for (fill, duplicates, indicator) in [
+            (UpsampleFill::Forward, UpsampleDuplicates::First, false),
+            (UpsampleFill::Linear, UpsampleDuplicates::First, true),
+            (UpsampleFill::Null, UpsampleDuplicates::Mean, false),
+        ] {
+            let options = UpsampleOptions {
+                fill: UpsampleFillOptions {
+                    strategy: fill,
+                    ..Default::default()
+                },
+                duplicates,
+                indicator,
+                ..Default::default()
+            };
+            let out = df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0"),
+                &options,
+            )?;
+            let schema = upsample_schema(&df.schema(), &[], "time", &options)?;
+            assert_eq!(out.schema(), schema);
+            // Also without duplicates to average.
+            let out = df
+                .slice(1, 2)
+                .with_sorted_flag("time", IsSorted::Ascending)
+                .unwrap()
+                .upsample_with_options::<[String; 0]>(
+                    [],
+                    "time",
+                    Duration::parse("15m"),
+                    Duration::parse("0"),
+                    &options,
+                )?;
+            assert_eq!(out.schema(), schema);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_by_freq() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(1, 0), hm(0, 0), hm(2, 0), hm(0, 0), hm(1, 0)],
+            "groups" => &["a", "a", "b", "b", "c", "c"],
+            "values" => &[1, 2, 3, 4, 5, 6],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let freq =
+            PlHashMap::from_iter([("a", Duration::parse("15m")), ("b", Duration::parse("1h"))]);
+        let every = |group: &DataFrame| {
+            let key = group.column("groups").ok()?.utf8().ok()?.get(0)?;
+            freq.get(key).copied()
+        };
+
+        let out = df.upsample_by_freq(
+            ["groups"],
+            "time",
+            every,
+            Duration::parse("30m"),
+            Duration::parse("0"),
+            &UpsampleOptions {
+                maintain_order: true,
+                ..Default::default()
+            },
+        )?;
+        let expected = df!(
+            "time" => &[
+                hm(0, 0), hm(0, 15), hm(0, 30), hm(0, 45), hm(1, 0),
+                hm(0, 0), hm(1, 0), hm(2, 0),
+                hm(0, 0), hm(0, 30), hm(1, 0),
+            ],
+            "groups" => &["a", "a", "a", "a", "a", "b", "b", "b", "c", "c", "c"],
+            "values" => &[
+                Some(1), None, None, None, Some(2),
+                Some(3), None, Some(4),
+                Some(5), None, Some(6),
+            ],
+        )?;
+        assert!(out.equals_missing(&expected));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_batched() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(1, 0), hm(0, 0), hm(0, 30), hm(2, 0)],
+            "groups" => &["a", "a", "b", "b", "b"],
+            "values" => &[1, 2, 3, 4, 5],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let every = Duration::parse("15m");
+        let offset = Duration::parse("0");
+        let options = UpsampleOptions {
+            maintain_order: true,
+            ..Default::default()
+        };
+
+        // Every call only gets the rows of a single group.
+        let mut batches = vec![];
+        df.upsample_batched(["groups"], "time", every, offset, &options, |batch| {
+            assert_eq!(batch.column("groups")?.n_unique()?, 1);
+            batches.push(batch);
+            Ok(())
+        })?;
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].height(), 5);
+        assert_eq!(batches[1].height(), 9);
+        let expected = df.upsample_with_options(["groups"], "time", every, offset, &options)?;
+        let out = polars_core::utils::accumulate_dataframes_vertical(batches)?;
+        assert!(out.equals_missing(&expected));
+
+        // The groups are processed lazily, an error stops the iteration.
+        let mut calls = 0;
+        let result = df.upsample_batched(["groups"], "time", every, offset, &options, |_| {
+            calls += 1;
+            polars_bail!(ComputeError: "stop")
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_regular() -> PolarsResult<()> {
+        let every = Duration::parse("30m");
+        let offset = Duration::parse("0");
+        let time = |times: &[NaiveDateTime]| {
+            DatetimeChunked::from_naive_datetime(
+                "time",
+                times.iter().copied(),
+                TimeUnit::Milliseconds,
+            )
+            .into_series()
+        };
+
+        let regular = time(&[hm(0, 0), hm(0, 30), hm(1, 0), hm(1, 30)]);
+        assert!(is_regular(&regular, every, offset)?);
+        assert!(!is_regular(&regular, Duration::parse("15m"), offset)?);
+        let extra = time(&[hm(0, 0), hm(0, 30), hm(0, 45), hm(1, 0), hm(1, 30)]);
+        assert!(!is_regular(&extra, every, offset)?);
+        assert!(is_regular(&regular.clear(), every, offset)?);
+
+        // spring forward in New York: 00:00 EST up to 05:00 EDT is hourly, there is no 02:00
+        #[cfg(feature = "timezones")]
+        {
+            let start = NaiveDate::from_ymd_opt(2021, 3, 14)
+                .unwrap()
+                .and_hms_opt(5, 0, 0)
+                .unwrap()
+                .timestamp_millis();
+            let hourly = |hours: &[i64]| {
+                let times = hours
+                    .iter()
+                    .map(|h| start + h * 3_600_000)
+                    .collect::<Vec<_>>();
+                Int64Chunked::from_vec("time", times)
+                    .into_datetime(TimeUnit::Milliseconds, Some("America/New_York".to_string()))
+                    .into_series()
+            };
+            let every = Duration::parse("1h");
+            assert!(is_regular(&hourly(&[0, 1, 2, 3, 4]), every, offset)?);
+            assert!(!is_regular(&hourly(&[0, 1, 3, 4]), every, offset)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_time() -> PolarsResult<()> {
+        let time = |times: &[(i64, i64)]| {
+            let ns = times
+                .iter()
+                .map(|(hour, min)| (hour * 60 + min) * 60_000_000_000)
+                .collect::<Vec<_>>();
+            Int64Chunked::from_vec("time", ns).into_time().into_series()
+        };
+        let df = df!(
+            "time" => time(&[(9, 0), (12, 0), (16, 0)]),
+            "values" => &[1, 2, 3],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |every: &str, offset: &str| {
+            df.upsample::<[String; 0]>([], "time", Duration::parse(every), Duration::parse(offset))
+        };
+
+        let out = upsample("30m", "0")?;
+        let expected = (0..=14).map(|i| (9, i * 30)).collect::<Vec<_>>();
+        assert!(out.column("time")?.equals(&time(&expected)));
+        let values = out.column("values")?;
+        assert_eq!(values.null_count(), 12);
+        assert_eq!(Vec::from(values.i32()?)[6], Some(2));
+
+        // calendar durations and ranges beyond midnight are not allowed
+        assert!(upsample("1d", "0").is_err());
+        assert!(upsample("30m", "-10h").is_err());
+        assert!(upsample("9h", "0").is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_drop_empty() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 30), hm(1, 0), hm(1, 15), hm(1, 30)],
+            "values" => &[Some(1.0), None, Some(4.0), Some(5.0)],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |fill| {
+            let options = UpsampleOptions {
+                grid: UpsampleGridOptions {
+                    end: Some(hm(2, 0).timestamp_millis()),
+                    ..Default::default()
+                },
+                fill: UpsampleFillOptions {
+                    strategy: fill,
+                    drop_empty: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("-30m"),
+                &options,
+            )
+        };
+
+        // The linear fill rescues the interior rows, the leading and trailing rows are
+        // dropped. The null of the source is kept.
+        let out = upsample(UpsampleFill::Linear)?;
+        let expected = df!(
+            "time" => &[hm(0, 30), hm(0, 45), hm(1, 0), hm(1, 15), hm(1, 30)],
+            "values" => &[Some(1.0), Some(2.0), None, Some(4.0), Some(5.0)],
+        )?;
+        assert!(out.equals_missing(&expected));
+
+        // Without a fill all inserted rows are empty.
+        let out = upsample(UpsampleFill::Null)?;
+        assert!(out.equals_missing(&df));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "dtype-struct")]
+    fn test_upsample_struct() -> PolarsResult<()> {
+        let ohlc = StructChunked::new(
+            "ohlc",
+            &[
+                Series::new("open", &[1, 2, 10, 20]),
+                Series::new("close", &[None, Some(3), Some(11), Some(21)]),
+            ],
+        )?;
+        let key = StructChunked::new(
+            "key",
+            &[
+                Series::new("market", &["a", "a", "b", "b"]),
+                Series::new("venue", &[None, None, Some(1), Some(1)]),
+            ],
+        )?;
+        let df = DataFrame::new(vec![
+            Series::new("time", &[hm(0, 0), hm(0, 30), hm(0, 0), hm(0, 30)]),
+            ohlc.into_series(),
+            key.into_series(),
+        ])?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let options = UpsampleOptions {
+            fill: UpsampleFillOptions {
+                strategy: UpsampleFill::Forward,
+                ..Default::default()
+            },
+            maintain_order: true,
+            ..Default::default()
+        };
+        // A struct with some null fields is a known value, it is filled forward as a whole.
+        let out = df.upsample_with_options(
+            ["key"],
+            "time",
+            Duration::parse("15m"),
+            Duration::parse("0m"),
+            &options,
+        )?;
+
+        let ohlc = StructChunked::new(
+            "ohlc",
+            &[
+                Series::new("open", &[1, 1, 2, 10, 10, 20]),
+                Series::new(
+                    "close",
+                    &[None, None, Some(3), Some(11), Some(11), Some(21)],
+                ),
+            ],
+        )?;
+        let key = StructChunked::new(
+            "key",
+            &[
+                Series::new("market", &["a", "a", "a", "b", "b", "b"]),
+                Series::new("venue", &[None, None, None, Some(1), Some(1), Some(1)]),
+            ],
+        )?;
+        let expected = DataFrame::new(vec![
+            Series::new(
+                "time",
+                &[
+                    hm(0, 0),
+                    hm(0, 15),
+                    hm(0, 30),
+                    hm(0, 0),
+                    hm(0, 15),
+                    hm(0, 30),
+                ],
+            ),
+            ohlc.into_series(),
+            key.into_series(),
+        ])?;
+        assert!(out.equals_missing(&expected));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_preserve_endpoints() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 30)],
+            "values" => &[1, 2],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |preserve_endpoints| {
+            let options = UpsampleOptions {
+                grid: UpsampleGridOptions {
+                    preserve_endpoints,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("5m"),
+                &options,
+            )
+        };
+
+        // The offset moves the range off both rows of the source.
+        let out = upsample(false)?;
+        let expected = df!(
+            "time" => &[hm(0, 5), hm(0, 20)],
+            "values" => &[None::<i32>, None],
+        )?;
+        assert!(out.equals_missing(&expected));
+
+        let out = upsample(true)?;
+        let expected = df!(
+            "time" => &[hm(0, 0), hm(0, 5), hm(0, 20), hm(0, 30)],
+            "values" => &[Some(1), None, None, Some(2)],
+        )?;
+        assert!(out.equals_missing(&expected));
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_upsampled() -> PolarsResult<()> {
+        let a = df!(
+            "time" => &[hm(0, 0), hm(1, 0), hm(2, 0)],
+            "a" => &[1, 2, 3],
+            "shared" => &[Some(10), None, Some(30)],
+        )?;
+        let b = df!(
+            "time" => &[hm(1, 0), hm(2, 0), hm(3, 0)],
+            "shared" => &[20, 21, 31],
+            "b" => &[4, 5, 6],
+        )?;
+        let frames = [
+            a.with_sorted_flag("time", IsSorted::Ascending).unwrap(),
+            b.with_sorted_flag("time", IsSorted::Ascending).unwrap(),
+        ];
+        let out = merge_upsampled(
+            &frames,
+            "time",
+            Duration::parse("1h"),
+            Duration::parse("0m"),
+        )?;
+        let expected = df!(
+            "time" => &[hm(0, 0), hm(1, 0), hm(2, 0), hm(3, 0)],
+            "a" => &[Some(1), Some(2), Some(3), None],
+            "shared" => &[10, 20, 30, 31],
+            "b" => &[None, Some(4), Some(5), Some(6)],
+        )?;
+        assert!(out.equals_missing(&expected));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "dtype-decimal")]
+    fn test_upsample_decimal() -> PolarsResult<()> {
+        let price = Int128Chunked::from_slice("price", &[10_000, 20_000])
+            .into_decimal_unchecked(Some(38), 4)
+            .into_series();
+        let df = DataFrame::new(vec![Series::new("time", &[hm(0, 0), hm(0, 30)]), price])?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let out = upsample_fill(&df, "10m", UpsampleFill::Linear);
+
+        let price = out.column("price")?;
+        assert_eq!(price.dtype(), &DataType::Decimal(Some(38), Some(4)));
+        // 1.0000, 1.3333, 1.6667 and 2.0000
+        assert_eq!(
+            Vec::from(&price.decimal()?.0),
+            &[Some(10_000), Some(13_333), Some(16_667), Some(20_000)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "dtype-categorical")]
+    fn test_upsample_categorical_key() -> PolarsResult<()> {
+        let groups =
+            Series::new("groups", &["b", "a", "b", "a"]).cast(&DataType::Categorical(None))?;
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 0), hm(0, 30), hm(1, 0)],
+            "values" => &[1, 2, 3, 4],
+        )?;
+        let mut df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        df.with_column(groups)?;
+        for by_fill in [UpsampleByFill::Forward, UpsampleByFill::GroupKey] {
+            let options = UpsampleOptions {
+                fill: UpsampleFillOptions {
+                    by: by_fill,
+                    ..Default::default()
+                },
+                maintain_order: true,
+                ..Default::default()
+            };
+            let out = df.upsample_with_options(
+                ["groups"],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0m"),
+                &options,
+            )?;
+            let groups = out.column("groups")?;
+            assert!(matches!(groups.dtype(), DataType::Categorical(Some(_))));
+            assert_eq!(groups.null_count(), 0);
+            let labels = groups.cast(&DataType::Utf8)?;
+            assert_eq!(
+                Vec::from(labels.utf8()?),
+                [["b"; 3].as_slice(), &["a"; 5]]
+                    .concat()
+                    .into_iter()
+                    .map(Some)
+                    .collect::<Vec<_>>(),
+                "{by_fill:?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_sort_groups() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 0), hm(0, 15), hm(0, 30), hm(0, 30), hm(1, 0)],
+            "groups" => &[Some("b"), Some("c"), None, Some("a"), Some("b"), Some("a")],
+            "values" => &[1, 2, 3, 4, 5, 6],
+        )?;
+        for descending in [false, true] {
+            let mut df = df.clone();
+            if descending {
+                df = df.reverse();
+            }
+            let sorted = if descending {
+                IsSorted::Descending
+            } else {
+                IsSorted::Ascending
+            };
+            let df = df.with_sorted_flag("time", sorted)?;
+            let options = UpsampleOptions {
+                fill: UpsampleFillOptions {
+                    by: UpsampleByFill::GroupKey,
+                    ..Default::default()
+                },
+                sort_groups: true,
+                ..Default::default()
+            };
+            let out = df.upsample_with_options(
+                ["groups"],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0m"),
+                &options,
+            )?;
+            assert_eq!(
+                Vec::from(out.column("groups")?.utf8()?),
+                &[
+                    None,
+                    Some("a"),
+                    Some("a"),
+                    Some("a"),
+                    Some("b"),
+                    Some("b"),
+                    Some("b"),
+                    Some("c")
+                ],
+                "descending: {descending}"
+            );
+            let mut values = Vec::from(out.column("values")?.i32()?);
+            if descending {
+                // The groups are in key order, the rows within them in the source order.
+                values[1..4].reverse();
+                values[4..7].reverse();
+            }
+            assert_eq!(
+                values,
+                &[
+                    Some(3),
+                    Some(4),
+                    None,
+                    Some(6),
+                    Some(1),
+                    None,
+                    Some(5),
+                    Some(2)
+                ]
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_validate() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(1, 0)],
+            "date" => [NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(); 2],
+            "groups" => &["a", "b"],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let validate = |df: &DataFrame, by: &[&str], time_column, every| {
+            let by = by.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+            upsample_validate(
+                df,
+                by,
+                time_column,
+                Duration::parse(every),
+                Duration::parse("0"),
+            )
+        };
+
+        validate(&df, &["groups"], "time", "15m")?;
+        assert!(matches!(
+            validate(&df, &[], "missing", "15m"),
+            Err(PolarsError::ColumnNotFound(_))
+        ));
+        assert!(matches!(
+            validate(&df, &["missing"], "time", "15m"),
+            Err(PolarsError::ColumnNotFound(_))
+        ));
+        assert!(matches!(
+            validate(&df, &[], "groups", "15m"),
+            Err(PolarsError::ComputeError(_))
+        ));
+        // Not flagged as sorted.
+        assert!(matches!(
+            validate(&df, &[], "date", "1d"),
+            Err(PolarsError::InvalidOperation(_))
+        ));
+        let df = df.with_sorted_flag("date", IsSorted::Ascending).unwrap();
+        assert!(matches!(
+            validate(&df, &[], "date", "12h"),
+            Err(PolarsError::ComputeError(_))
+        ));
+        assert!(matches!(
+            validate(&df, &[], "time", "-15m"),
+            Err(PolarsError::ComputeError(_))
+        ));
+
+        // Several failures are reported at once.
+        let err = validate(&df, &["missing"], "date", "12h").unwrap_err();
+        assert!(matches!(err, PolarsError::ComputeError(_)));
+        let msg = err.to_string();
+        assert!(msg.contains("whole number of days"), "{msg}");
+        assert!(msg.contains("missing"), "{msg}");
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "timezones")]
+    fn test_upsample_validate_time_zone() -> PolarsResult<()> {
+        let validate = |tz: &str| {
+            let time = Int64Chunked::from_slice("time", &[0, 1])
+                .into_datetime(TimeUnit::Milliseconds, Some(tz.to_string()));
+            let df = df!("time" => time.into_series())?
+                .with_sorted_flag("time", IsSorted::Ascending)
+                .unwrap();
+            upsample_validate::<[String; 0]>(
+                &df,
+                [],
+                "time",
+                Duration::parse("1h"),
+                Duration::parse("0"),
+            )
+        };
+        validate("Europe/Amsterdam")?;
+        validate("+05:30")?;
+        assert!(matches!(
+            validate("Mars/Olympus_Mons"),
+            Err(PolarsError::ComputeError(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_estimate_upsample_rows() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(1, 0), hm(0, 10), hm(0, 40)],
+            "group" => &["a", "a", "b", "b"],
+            "values" => &[1, 2, 3, 4],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        for (every, offset) in [("15m", "0m"), ("15m", "5m"), ("7m", "-10m")] {
+            let (every, offset) = (Duration::parse(every), Duration::parse(offset));
+            let out = df.upsample(["group"], "time", every, offset)?;
+            let estimate = estimate_upsample_rows(&df, ["group"], "time", every, offset)?;
+            assert_eq!(estimate, out.height());
+        }
+
+        let day = |month, day| {
+            NaiveDate::from_ymd_opt(2021, month, day)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        };
+        let df = df!(
+            "time" => &[day(1, 31), day(7, 15)],
+            "values" => &[1, 2],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let every = Duration::parse("1mo");
+        let out = df.upsample::<[String; 0]>([], "time", every, Duration::parse("0"))?;
+        let estimate =
+            estimate_upsample_rows::<[String; 0]>(&df, [], "time", every, Duration::parse("0"))?;
+        assert_eq!(estimate, out.height());
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_columns() -> PolarsResult<()> {
+        let mut df = df!(
+            "time" => &[hm(0, 0), hm(0, 30), hm(0, 0)],
+            "groups" => &["a", "a", "b"],
+        )?;
+        for i in 0..50 {
+            df.with_column(Series::new(&format!("v{i}"), &[i, i + 1, i + 2]))?;
+        }
+        let df = df.sort(["time"], false, true)?;
+        let options = UpsampleOptions {
+            fill: UpsampleFillOptions {
+                strategy: UpsampleFill::Forward,
+                ..Default::default()
+            },
+            columns: Some(vec!["v7".to_string(), "v3".to_string(), "v42".to_string()]),
+            ..Default::default()
+        };
+        let out = df.upsample_with_options(
+            ["groups"],
+            "time",
+            Duration::parse("15m"),
+            Duration::parse("0m"),
+            &options,
+        )?;
+        // The requested columns are kept in the order of the source.
+        assert_eq!(
+            out.get_column_names(),
+            &["time", "groups", "v3", "v7", "v42"]
+        );
+        let out = out.sort(["groups", "time"], vec![false, false], true)?;
+        assert_eq!(
+            Vec::from(out.column("v42")?.i32()?),
+            &[Some(42), Some(42), Some(43), Some(44)]
+        );
+        let schema = upsample_schema(&df.schema(), &["groups".to_string()], "time", &options)?;
+        assert_eq!(schema, out.schema());
+
+        let options = UpsampleOptions {
+            columns: Some(vec!["missing".to_string()]),
+            ..Default::default()
+        };
+        assert!(matches!(
+            df.upsample_with_options(
+                ["groups"],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0m"),
+                &options,
+            ),
+            Err(PolarsError::ColumnNotFound(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_monotonic() -> PolarsResult<()> {
+        // A running total with gaps and a missing reading.
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 15), hm(1, 0), hm(1, 15), hm(2, 0)],
+            "total" => &[Some(0), Some(10), None, Some(40), Some(100)],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let out = upsample_fill(&df, "15m", UpsampleFill::Monotonic { descending: false });
+        let total = Vec::from(out.column("total")?.f64()?);
+        assert_eq!(
+            total,
+            &[
+                Some(0.0),
+                Some(10.0),
+                Some(17.5),
+                Some(25.0),
+                None,
+                Some(40.0),
+                Some(60.0),
+                Some(80.0),
+                Some(100.0)
+            ]
+        );
+        let known = total.iter().flatten().collect::<Vec<_>>();
+        assert!(known.windows(2).all(|w| w[0] <= w[1]));
+
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 30), hm(1, 0)],
+            "total" => &[3, 1, 2],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |descending| {
+            let options = UpsampleOptions {
+                fill: UpsampleFillOptions {
+                    strategy: UpsampleFill::Monotonic { descending },
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0m"),
+                &options,
+            )
+        };
+        let err = upsample(false).unwrap_err();
+        assert!(err.to_string().contains("non-decreasing"), "{err}");
+        let err = upsample(true).unwrap_err();
+        assert!(err.to_string().contains("non-increasing"), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_fill_limit() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(1, 0)],
+            "values" => &[1.0, 7.0],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |fill, fill_limit| {
+            let options = UpsampleOptions {
+                fill: UpsampleFillOptions {
+                    strategy: fill,
+                    limit: fill_limit,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let out = df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("10m"),
+                Duration::parse("0m"),
+                &options,
+            )?;
+            Ok::<_, PolarsError>(Vec::from(out.column("values")?.f64()?))
+        };
+
+        assert_eq!(
+            upsample(UpsampleFill::Forward, Some(2))?,
+            &[Some(1.0), Some(1.0), Some(1.0), None, None, None, Some(7.0)]
+        );
+        assert_eq!(
+            upsample(UpsampleFill::Backward, Some(2))?,
+            &[Some(1.0), None, None, None, Some(7.0), Some(7.0), Some(7.0)]
+        );
+        assert_eq!(
+            upsample(UpsampleFill::Nearest, Some(2))?,
+            &[
+                Some(1.0),
+                Some(1.0),
+                Some(1.0),
+                None,
+                Some(7.0),
+                Some(7.0),
+                Some(7.0)
+            ]
+        );
+        // The gap of 5 inserted rows is too large to interpolate.
+        assert_eq!(
+            upsample(UpsampleFill::Linear, Some(2))?,
+            &[Some(1.0), None, None, None, None, None, Some(7.0)]
+        );
+        assert_eq!(
+            upsample(UpsampleFill::Linear, Some(5))?,
+            (1..=7).map(|v| Some(v as f64)).collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_anchor() -> PolarsResult<()> {
+        let day = |month, day| {
+            NaiveDate::from_ymd_opt(2021, month, day)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        };
+        let df = df!(
+            "time" => &[day(1, 15), day(2, 1), day(4, 1)],
+            "values" => &[1, 2, 3],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |df: &DataFrame, every, anchor| {
+            let options = UpsampleOptions {
+                grid: UpsampleGridOptions {
+                    anchor,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse(every),
+                Duration::parse("0"),
+                &options,
+            )
+        };
+
+        // The mid-month first timestamp is not on the range anchored to the month start.
+        let out = upsample(&df, "1mo", Some(CalendarAnchor::MonthStart))?;
+        let expected = df!(
+            "time" => &[day(1, 1), day(2, 1), day(3, 1), day(4, 1)],
+            "values" => &[None, Some(2), None, Some(3)],
+        )?;
+        assert!(out.equals_missing(&expected));
+        let out = upsample(&df, "1mo", None)?;
+        let first = out.column("time")?.datetime()?.as_datetime_iter().next();
+        assert_eq!(first, Some(Some(day(1, 15))));
+
+        // Date index columns, snapped to the start of the quarter and to a Sunday.
+        let date = |month, day| NaiveDate::from_ymd_opt(2021, month, day).unwrap();
+        let df = df!(
+            "time" => &[date(5, 20), date(7, 1)],
+            "values" => &[1, 2],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let out = upsample(&df, "1mo", Some(CalendarAnchor::QuarterStart))?;
+        let expected = df!(
+            "time" => &[date(4, 1), date(5, 1), date(6, 1), date(7, 1)],
+            "values" => &[None, None, None, Some(2)],
+        )?;
+        assert!(out.equals_missing(&expected));
+        // 2021-05-20 is a Thursday.
+        let out = upsample(&df, "1w", Some(CalendarAnchor::WeekStart(Weekday::Sun)))?;
+        let first = out.column("time")?.date()?.as_date_iter().next();
+        assert_eq!(first, Some(Some(date(5, 16))));
+
+        let df = df!("time" => &[1i64, 2], "values" => &[1, 2])?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        assert!(upsample(&df, "1i", Some(CalendarAnchor::MonthStart)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_sorted_flag() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 30), hm(1, 0)],
+            "values" => &[1, 2, 3],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let every = Duration::parse("15m");
+        let offset = Duration::parse("0");
+
+        let out = df.upsample::<[String; 0]>([], "time", every, offset)?;
+        assert_eq!(out.column("time")?.is_sorted_flag(), IsSorted::Ascending);
+        let descending = df.reverse();
+        assert_eq!(
+            descending.column("time")?.is_sorted_flag(),
+            IsSorted::Descending
+        );
+        let out = descending.upsample::<[String; 0]>([], "time", every, offset)?;
+        assert_eq!(out.column("time")?.is_sorted_flag(), IsSorted::Descending);
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_bounds() {
+        let s = DatetimeChunked::new("time", &[hm(0, 10), hm(0, 30), hm(2, 0)]).into_series();
+        let (first, last, tu, tz) =
+            upsample_bounds(&s, Duration::parse("15m"), Duration::parse("5m")).unwrap();
+        assert_eq!(tu, TimeUnit::Milliseconds);
+        assert_eq!(tz, None);
+        assert_eq!(first, hm(0, 15).timestamp_millis());
+        assert_eq!(last, hm(2, 0).timestamp_millis());
+    }
+
+    #[test]
+    #[cfg(feature = "timezones")]
+    fn test_upsample_bounds_invalid_time_zone() {
+        let s = Int64Chunked::from_slice("time", &[0, 3_600_000])
+            .into_datetime(TimeUnit::Milliseconds, Some("Not/A_Zone".to_string()))
+            .into_series();
+        let err = upsample_bounds(&s, Duration::parse("15m"), Duration::parse("0")).unwrap_err();
+        assert!(err.to_string().contains("Not/A_Zone"));
+
+        let df = DataFrame::new(vec![s])
+            .unwrap()
+            .with_sorted_flag("time", IsSorted::Ascending)
+            .unwrap();
+        assert!(df
+            .upsample::<[String; 0]>([], "time", Duration::parse("15m"), Duration::parse("0"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_upsample_with_report() {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(1, 0), hm(0, 0), hm(0, 30)],
+            "groups" => &["a", "a", "b", "b"],
+            "values" => &[1, 2, 3, 4],
+        )
+        .unwrap();
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+
+        let options = UpsampleOptions {
+            maintain_order: true,
+            ..Default::default()
+        };
+        let (out, report) = df
+            .upsample_with_report::<[String; 1]>(
+                [String::from("groups")],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0"),
+                &options,
+            )
+            .unwrap();
+        assert_eq!(out.height(), 8);
+        assert_eq!(report.original_rows, 4);
+        assert_eq!(report.inserted_rows, 4);
+        let expected = df!(
+            "groups" => &["a", "b"],
+            "original_rows" => &[2 as IdxSize, 2],
+            "inserted_rows" => &[3 as IdxSize, 1],
+        )
+        .unwrap();
+        assert_eq!(report.per_group.unwrap(), expected);
+
+        let (_, report) = df
+            .upsample_with_report::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0"),
+                &options,
+            )
+            .unwrap();
+        assert!(report.per_group.is_none());
+    }
+
+    #[test]
+    fn test_upsample_parallel_many_groups() {
+        let n_groups = 1000;
+        let time = (0..n_groups)
+            .flat_map(|_| [hm(0, 0), hm(0, 30), hm(2, 0)])
+            .collect::<Vec<_>>();
+        let groups = (0..n_groups).flat_map(|g| [g, g, g]).collect::<Vec<i32>>();
+        let values = (0..3 * n_groups).collect::<Vec<i32>>();
+        let df = df!(
+            "time" => time,
+            "groups" => groups,
+            "values" => values,
+        )
+        .unwrap();
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+
+        let upsample = |parallel| {
+            let options = UpsampleOptions {
+                maintain_order: true,
+                parallel,
+                ..Default::default()
+            };
+            df.upsample_with_options::<[String; 1]>(
+                [String::from("groups")],
+                "time",
+                Duration::parse("15m"),
+                Duration::parse("0"),
+                &options,
+            )
+            .unwrap()
+        };
+        let parallel = upsample(true);
+        let serial = upsample(false);
+        assert_eq!(parallel.height(), n_groups as usize * 9);
+        assert!(parallel.equals_missing(&serial));
+    }
+
+    #[test]
+    fn test_upsample_index_count() -> PolarsResult<()> {
+        // Irregular gaps of 10, 30 and 1 minutes, and a duplicate.
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 10), hm(0, 10), hm(0, 40), hm(0, 41)],
+            "values" => &[1, 2, 3, 4, 5],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let options = UpsampleOptions {
+            duplicates: UpsampleDuplicates::First,
+            ..Default::default()
+        };
+        let out = df.upsample_with_options::<[String; 0]>(
+            [],
+            "time",
+            Duration::parse("2i"),
+            Duration::parse("0"),
+            &options,
+        )?;
+        let expected = df!(
+            "time" => &[
+                hm(0, 0), hm(0, 5), hm(0, 10), hm(0, 25), hm(0, 40),
+                NaiveDate::from_ymd_opt(2021, 12, 16)
+                    .unwrap()
+                    .and_hms_opt(0, 40, 30)
+                    .unwrap(),
+                hm(0, 41),
+            ],
+            "values" => &[Some(1), None, Some(2), None, Some(4), None, Some(5)],
+        )?;
+        assert!(out.equals_missing(&expected));
+
+        let out = df.upsample_with_options::<[String; 0]>(
+            [],
+            "time",
+            Duration::parse("3i"),
+            Duration::parse("0"),
+            &options,
+        )?;
+        assert_eq!(out.height(), 10);
+
+        let err = df
+            .upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("2i"),
+                Duration::parse("1i"),
+                &options,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("requires a zero `offset`"));
+
+        let err = df
+            .upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::new(i64::MAX),
+                Duration::parse("0"),
+                &options,
+            )
+            .unwrap_err();
+        assert!(matches!(err, PolarsError::ComputeError(_)));
+        assert!(err.to_string().contains("overflows"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_window_bounds() -> PolarsResult<()> {
+        let day = |m, d| {
+            NaiveDate::from_ymd_opt(2021, m, d)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        };
+        let df = df!(
+            "time" => &[day(1, 1), day(4, 1)],
+            "values" => &[1, 4],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let options = UpsampleOptions {
+            emit_window_bounds: true,
+            ..Default::default()
+        };
+        let out = df.upsample_with_options::<[String; 0]>(
+            [],
+            "time",
+            Duration::parse("1mo"),
+            Duration::parse("0"),
+            &options,
+        )?;
+        let schema = upsample_schema(&df.schema(), &[], "time", &options)?;
+        assert_eq!(out.schema(), schema);
+
+        let values = |name| -> PolarsResult<Vec<i64>> {
+            Ok(out.column(name)?.datetime()?.into_no_null_iter().collect())
+        };
+        let start = values("time_start")?;
+        assert_eq!(start, values("time")?);
+        // The intervals have 31, 28, 31 and 30 days.
+        let end = values("time_end")?;
+        assert_eq!(end[..3], start[1..]);
+        assert_eq!(end[3], day(5, 1).timestamp_millis());
+
+        let err = df
+            .upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("2i"),
+                Duration::parse("0"),
+                &options,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("emit_window_bounds"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_tolerance() -> PolarsResult<()> {
+        let jitter = |h, m, ms| hm(h, m) + chrono::Duration::milliseconds(ms);
+        let df = df!(
+            "time" => &[
+                hm(0, 0),
+                jitter(0, 10, 2),
+                jitter(0, 20, -2),
+                jitter(0, 40, 1),
+                jitter(0, 50, 20),
+            ],
+            "values" => &[1, 2, 3, 4, 5],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |tolerance: Option<&str>| {
+            let options = UpsampleOptions {
+                join: UpsampleJoinOptions {
+                    tolerance: tolerance.map(Duration::parse),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("10m"),
+                Duration::parse("0"),
+                &options,
+            )
+        };
+        let expected = |values: &[Option<i32>]| {
+            df!(
+                "time" => (0..6).map(|i| hm(0, i * 10)).collect::<Vec<_>>(),
+                "values" => values,
+            )
+        };
+
+        // The exact join only matches the first row.
+        let out = upsample(None)?;
+        assert!(out.equals_missing(&expected(&[Some(1), None, None, None, None, None])?));
+        // The last row is beyond the tolerance.
+        let out = upsample(Some("5ms"))?;
+        assert!(out.equals_missing(&expected(&[
+            Some(1),
+            Some(2),
+            Some(3),
+            None,
+            Some(4),
+            None
+        ])?));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_on_collision() -> PolarsResult<()> {
+        let jitter = |m, ms| hm(0, m) + chrono::Duration::milliseconds(ms);
+        // Three rows are within the tolerance of 00:10.
+        let df = df!(
+            "time" => &[hm(0, 0), jitter(10, -4), jitter(10, -1), jitter(10, 3), hm(0, 20)],
+            "values" => &[1, 2, 3, 4, 5],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        for (on_collision, value) in [
+            (CollisionPolicy::Closest, 3),
+            (CollisionPolicy::First, 2),
+            (CollisionPolicy::Last, 4),
+        ] {
+            let options = UpsampleOptions {
+                join: UpsampleJoinOptions {
+                    tolerance: Some(Duration::parse("5ms")),
+                    on_collision,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let out = df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("10m"),
+                Duration::parse("0"),
+                &options,
+            )?;
+            let expected = df!(
+                "time" => &[hm(0, 0), hm(0, 10), hm(0, 20)],
+                "values" => &[1, value, 5],
+            )?;
+            assert!(out.equals_missing(&expected), "{on_collision:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_business_days() -> PolarsResult<()> {
+        let day = |d| hm(9, 0) + chrono::Duration::days(d);
+        // Thursday, the next Monday and the Thursday after
+        let df = df!(
+            "time" => &[day(0), day(4), day(7)],
+            "values" => &[1, 2, 3],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let epoch_days =
+            |d| (day(d).date() - NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()).num_days();
+        for (holidays, offset, expected) in [
+            (vec![], "0", vec![0, 1, 4, 5, 6, 7]),
+            (vec![epoch_days(6) as i32], "1d", vec![1, 4, 5, 7]),
+        ] {
+            let options = UpsampleOptions {
+                grid: UpsampleGridOptions {
+                    business_calendar: BusinessCalendar {
+                        holidays,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let out = df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("1bd"),
+                Duration::parse(offset),
+                &options,
+            )?;
+            let time = out
+                .column("time")?
+                .datetime()?
+                .as_datetime_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            assert!(time
+                .iter()
+                .all(|t| !matches!(t.weekday(), Weekday::Sat | Weekday::Sun)));
+            assert_eq!(time, expected.into_iter().map(day).collect::<Vec<_>>());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_column_fill() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 30)],
+            "events" => &[1, 5],
+            "temperature" => &[1.0, 4.0],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let options = UpsampleOptions {
+            fill: UpsampleFillOptions {
+                strategy: UpsampleFill::Linear,
+                columns: [("events".to_string(), UpsampleFill::Forward)].into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let upsample = |options: &UpsampleOptions| {
+            df.upsample_with_options::<[String; 0]>(
+                [],
+                "time",
+                Duration::parse("10m"),
+                Duration::parse("0m"),
+                options,
+            )
+        };
+        let out = upsample(&options)?;
+        // The counter keeps its integer dtype, as it isn't interpolated.
+        assert_eq!(
+            Vec::from(out.column("events")?.i32()?),
+            &[Some(1), Some(1), Some(1), Some(5)]
+        );
+        assert_eq!(
+            Vec::from(out.column("temperature")?.f64()?),
+            &[Some(1.0), Some(2.0), Some(3.0), Some(4.0)]
+        );
+        let schema = upsample_schema(&df.schema(), &[], "time", &options)?;
+        assert_eq!(schema, out.schema());
+
+        let options = UpsampleOptions {
+            fill: UpsampleFillOptions {
+                columns: [("missing".to_string(), UpsampleFill::Forward)].into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(upsample(&options).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_partitioned() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(1, 0), hm(0, 0), hm(0, 30), hm(2, 0), hm(0, 0)],
+            "groups" => &[Some("a"), Some("a"), Some("b"), Some("b"), Some("b"), None],
+            "values" => &[1, 2, 3, 4, 5, 6],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let every = Duration::parse("15m");
+        let offset = Duration::parse("0");
+        let options = UpsampleOptions {
+            maintain_order: true,
+            ..Default::default()
+        };
+
+        let partitions = df.upsample_partitioned(["groups"], "time", every, offset, &options)?;
+        assert_eq!(partitions.len(), df.column("groups")?.n_unique()?);
+        let keys = partitions
+            .iter()
+            .map(|(key, _)| key.0.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            [
+                vec![AnyValue::Utf8Owned("a".into())],
+                vec![AnyValue::Utf8Owned("b".into())],
+                vec![AnyValue::Null],
+            ]
+        );
+        let heights = partitions
+            .iter()
+            .map(|(_, df)| df.height())
+            .collect::<Vec<_>>();
+        assert_eq!(heights, [5, 9, 1]);
+
+        // Without `by`, the frame is a single partition.
+        let partitions = df.slice(0, 2).upsample_partitioned::<[String; 0]>(
+            [],
+            "time",
+            every,
+            offset,
+            &options,
+        )?;
+        assert_eq!(partitions.len(), 1);
+        assert!(partitions[0].0 .0.is_empty());
+        assert_eq!(partitions[0].1.height(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_shared_grid() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 15), hm(0, 30), hm(0, 0), hm(0, 45)],
+            "groups" => &["a", "a", "b", "b"],
+            "values" => &[1, 2, 3, 4],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let options = UpsampleOptions {
+            grid: UpsampleGridOptions {
+                shared: true,
+                ..Default::default()
+            },
+            maintain_order: true,
+            ..Default::default()
+        };
+        let out = df.upsample_with_options(
+            ["groups"],
+            "time",
+            Duration::parse("15m"),
+            Duration::parse("0"),
+            &options,
+        )?;
+        let grid = [hm(0, 0), hm(0, 15), hm(0, 30), hm(0, 45)];
+        let expected = df!(
+            "time" => grid.iter().chain(&grid).copied().collect::<Vec<_>>(),
+            "groups" => &["a", "a", "a", "a", "b", "b", "b", "b"],
+            "values" => &[None, Some(1), Some(2), None, Some(3), None, None, Some(4)],
+        )?;
+        assert!(out.equals_missing(&expected));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_max_gap() -> PolarsResult<()> {
+        // A gap of 10 minutes and an outage of 3 hours.
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 10), hm(3, 10)],
+            "values" => &[0.0, 10.0, 40.0],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let options = UpsampleOptions {
+            fill: UpsampleFillOptions {
+                strategy: UpsampleFill::Linear,
+                max_gap: Some(Duration::parse("1h")),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let out = df.upsample_with_options::<[String; 0]>(
+            [],
+            "time",
+            Duration::parse("5m"),
+            Duration::parse("0m"),
+            &options,
+        )?;
+        assert_eq!(out.height(), 39);
+        let values = Vec::from(out.column("values")?.f64()?);
+        assert_eq!(values[..3], [Some(0.0), Some(5.0), Some(10.0)]);
+        assert!(values[3..38].iter().all(|v| v.is_none()));
+        assert_eq!(values[38], Some(40.0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_with_source_index() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 30), hm(0, 0), hm(0, 10)],
+            "groups" => &["a", "a", "b", "b"],
+            "values" => &[1, 2, 3, 4],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let options = UpsampleOptions {
+            maintain_order: true,
+            with_source_index: true,
+            ..Default::default()
+        };
+        let out = df.upsample_with_options(
+            ["groups"],
+            "time",
+            Duration::parse("10m"),
+            Duration::parse("0"),
+            &options,
+        )?;
+        let source_index = out.column("_source_index")?.idx()?;
+        assert_eq!(
+            Vec::from(source_index),
+            &[Some(0), None, None, Some(1), Some(2), Some(3)]
+        );
+        // The real rows carry the values of their source row.
+        let values = out.column("values")?.i32()?;
+        for (idx, value) in source_index.into_iter().zip(values) {
+            if let Some(idx) = idx {
+                assert_eq!(value, df.column("values")?.i32()?.get(idx as usize));
+            } else {
+                assert_eq!(value, None);
+            }
+        }
+        let schema = upsample_schema(&df.schema(), &["groups".into()], "time", &options)?;
+        assert_eq!(schema, out.schema());
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsample_asof_forward() -> PolarsResult<()> {
+        let df = df!(
+            "time" => &[hm(0, 0), hm(0, 30), hm(0, 0), hm(0, 20)],
+            "groups" => &["a", "a", "b", "b"],
+            "values" => &[1, 2, 3, 4],
+        )?;
+        let df = df.with_sorted_flag("time", IsSorted::Ascending).unwrap();
+        let upsample = |fill| {
+            let options = UpsampleOptions {
+                fill: UpsampleFillOptions {
+                    strategy: fill,
+                    ..Default::default()
+                },
+                maintain_order: true,
+                with_source_index: true,
+                ..Default::default()
+            };
+            df.upsample_with_options(
+                ["groups"],
+                "time",
+                Duration::parse("10m"),
+                Duration::parse("0"),
+                &options,
+            )
+        };
+        let out = upsample(UpsampleFill::AsOfForward)?;
+        assert!(out.equals_missing(&upsample(UpsampleFill::Forward)?));
+        assert_eq!(
+            Vec::from(out.column("values")?.i32()?),
+            [1, 1, 1, 2, 3, 3, 4].map(Some)
+        );
+        // The filled rows are still marked as inserted.
+        assert_eq!(out.column("_source_index")?.null_count(), 3);
+        Ok(())
+    }
+}
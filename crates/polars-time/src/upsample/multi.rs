@@ -0,0 +1,91 @@
+use polars_core::prelude::*;
+use polars_core::series::IsSorted;
+use polars_ops::prelude::*;
+
+use super::{upsample_range, UpsampleFill, UpsampleOptions};
+use crate::prelude::*;
+
+pub(super) fn upsample_multi_impl(
+    source: &DataFrame,
+    by: Vec<String>,
+    time_columns: &[&str],
+    every: &[Duration],
+    offset: &[Duration],
+    options: &UpsampleOptions,
+) -> PolarsResult<DataFrame> {
+    polars_ensure!(
+        !time_columns.is_empty()
+            && time_columns.len() == every.len()
+            && time_columns.len() == offset.len(),
+        ComputeError: "upsample_multi requires an `every` and an `offset` for every time column"
+    );
+    polars_ensure!(
+        matches!(options.fill.strategy, UpsampleFill::Null)
+            && options.fill.columns.is_empty()
+            && options.grid.end.is_none()
+            && !options.emit_window_bounds
+            && !options.with_source_index,
+        ComputeError: "upsample_multi doesn't support the `fill`, `end`, `emit_window_bounds` and `with_source_index` options"
+    );
+
+    if by.is_empty() {
+        upsample_multi_single_impl(source, time_columns, every, offset, options)
+    } else {
+        let gb = if options.maintain_order {
+            source.group_by_stable(&by)
+        } else {
+            source.group_by(&by)
+        };
+        gb?.apply(|df| {
+            let mut upsampled_df =
+                upsample_multi_single_impl(&df, time_columns, every, offset, options)?;
+            // All rows of the group have the same key.
+            let height = upsampled_df.height();
+            for column in &by {
+                let key = df.column(column)?.new_from_index(0, height);
+                upsampled_df.with_column(key)?;
+            }
+            Ok(upsampled_df)
+        })
+    }
+}
+
+/// Left join the source onto the cartesian product of the ranges of all time columns.
+fn upsample_multi_single_impl(
+    source: &DataFrame,
+    time_columns: &[&str],
+    every: &[Duration],
+    offset: &[Duration],
+    options: &UpsampleOptions,
+) -> PolarsResult<DataFrame> {
+    let mut grid: Option<DataFrame> = None;
+    let mut cells: usize = 1;
+    for ((name, every), offset) in time_columns.iter().zip(every).zip(offset) {
+        let s = source.column(name)?;
+        // The range only depends on the boundaries, so the column doesn't have to be sorted.
+        let mut bounds = s.min_as_series();
+        bounds.append(&s.max_as_series())?;
+        bounds.set_sorted_flag(IsSorted::Ascending);
+        let range = upsample_range(&bounds, *every, *offset, options)?.into_frame();
+
+        cells = cells
+            .checked_mul(range.height())
+            .filter(|cells| *cells <= options.max_cells)
+            .ok_or_else(|| {
+                polars_err!(
+                    ComputeError: "upsample_multi grid exceeds the maximum of {} cells",
+                    options.max_cells
+                )
+            })?;
+        grid = Some(match grid {
+            None => range,
+            Some(grid) => grid.cross_join(&range, None, None)?,
+        });
+    }
+    grid.unwrap().join(
+        source,
+        time_columns,
+        time_columns,
+        JoinArgs::new(JoinType::Left),
+    )
+}
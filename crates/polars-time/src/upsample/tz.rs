@@ -0,0 +1,227 @@
+#[cfg(feature = "timezones")]
+use arrow::temporal_conversions::{
+    timestamp_ms_to_datetime, timestamp_ns_to_datetime, timestamp_us_to_datetime,
+};
+#[cfg(feature = "timezones")]
+use chrono::{FixedOffset, LocalResult, TimeZone as _};
+#[cfg(feature = "timezones")]
+use chrono_tz::Tz;
+use polars_core::prelude::*;
+#[cfg(feature = "timezones")]
+use polars_core::series::IsSorted;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "timezones")]
+use super::{extend_to_end, parse_time_zone, range_start, stepped_range, UpsampleOptions};
+#[cfg(feature = "timezones")]
+use crate::prelude::*;
+#[cfg(feature = "timezones")]
+use crate::utils::unlocalize_timestamp;
+
+/// How the local times of an upsample range that are ambiguous in the time zone, e.g. due
+/// to a daylight saving time transition, are handled.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UpsampleAmbiguous {
+    /// Use the earliest of the possible times.
+    #[default]
+    Earliest,
+    /// Use the latest of the possible times.
+    Latest,
+    /// Raise an error.
+    Raise,
+}
+
+/// How the local times of an upsample range that don't exist in the time zone, e.g. due
+/// to a daylight saving time transition, are handled.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UpsampleNonExistent {
+    /// Leave them out of the range.
+    #[default]
+    Skip,
+    /// Raise an error.
+    Raise,
+}
+
+/// Create the upsample range of a `Datetime` index column with a time zone.
+///
+/// A constant `every` is stepped in UTC, so the range never contains a nonexistent or
+/// a duplicated local time. A calendar `every` is stepped in local time, after which the
+/// local times are localized according to the `ambiguous` and `non_existent` options of
+/// `options.grid`.
+/// An `every` with calendar and clock parts is stepped with [`mixed_range`].
+#[cfg(feature = "timezones")]
+pub(super) fn upsample_range_tz(
+    index_column: &Series,
+    every: Duration,
+    offset: Duration,
+    options: &UpsampleOptions,
+    tu: TimeUnit,
+    tz: &TimeZone,
+) -> PolarsResult<Series> {
+    polars_ensure!(
+        !every.negative && !every.is_zero(),
+        ComputeError: "`every` must be positive"
+    );
+    let parsed_tz = parse_time_zone(tz)?;
+    let add: fn(&Duration, i64, Option<&Tz>) -> PolarsResult<i64> = match tu {
+        TimeUnit::Nanoseconds => Duration::add_ns,
+        TimeUnit::Microseconds => Duration::add_us,
+        TimeUnit::Milliseconds => Duration::add_ms,
+    };
+
+    let s = index_column.to_physical_repr();
+    let ca = s.i64()?;
+    let (Some(first), Some(last)) = (
+        ca.into_iter().flatten().next(),
+        ca.into_iter().flatten().next_back(),
+    ) else {
+        polars_bail!(ComputeError: "cannot determine upsample boundaries: all elements are null")
+    };
+    let first = match options.grid.anchor {
+        Some(anchor) => anchor.snap(first, tu, Some(&parsed_tz))?,
+        None => first,
+    };
+    let first = range_start(first, every, offset, |d, t| add(d, t, Some(&parsed_tz)))?;
+    let last = extend_to_end(last, options.grid.end)?;
+
+    let values = if every.is_constant_duration() {
+        let step = match tu {
+            TimeUnit::Nanoseconds => every.duration_ns(),
+            TimeUnit::Microseconds => every.duration_us(),
+            TimeUnit::Milliseconds => every.duration_ms(),
+        };
+        stepped_range(first, last, step, options.grid.closed)
+    } else if every.nanoseconds() != 0 {
+        mixed_range(first, last, every, add, tu, &parsed_tz, options)?
+    } else {
+        let local_first = unlocalize_timestamp(first, tu, parsed_tz);
+        let local_last = unlocalize_timestamp(last, tu, parsed_tz);
+        let skip_first = matches!(
+            options.grid.closed,
+            ClosedWindow::Right | ClosedWindow::None
+        );
+        let mut values = vec![];
+        for i in 0.. {
+            let t = add(&(every * i), local_first, None)?;
+            let in_range = match options.grid.closed {
+                ClosedWindow::Both | ClosedWindow::Right => t <= local_last,
+                ClosedWindow::Left | ClosedWindow::None => t < local_last,
+            };
+            if !in_range {
+                break;
+            }
+            if i == 0 && skip_first {
+                continue;
+            }
+            if let Some(t) = localize(t, tu, &parsed_tz, options)? {
+                values.push(t)
+            }
+        }
+        values
+    };
+
+    let mut range = Int64Chunked::from_vec(index_column.name(), values);
+    range.set_sorted_flag(IsSorted::Ascending);
+    Ok(range.into_datetime(tu, Some(tz.clone())).into_series())
+}
+
+/// The shift from UTC to local time in `tu` of a fixed offset time zone, e.g. `+05:30`.
+/// Returns `None` for other time zones, which are parsed as a named IANA time zone.
+#[cfg(feature = "timezones")]
+pub(super) fn fixed_offset_shift(tz: &str, tu: TimeUnit) -> Option<i64> {
+    if !tz.starts_with(['+', '-']) {
+        return None;
+    }
+    let offset = tz.parse::<FixedOffset>().ok()?;
+    let per_second = match tu {
+        TimeUnit::Nanoseconds => 1_000_000_000,
+        TimeUnit::Microseconds => 1_000_000,
+        TimeUnit::Milliseconds => 1_000,
+    };
+    Some(offset.local_minus_utc() as i64 * per_second)
+}
+
+/// Create the range of an `every` with calendar and clock parts, e.g. `1d12h`, from `first`
+/// up to `last` in UTC.
+///
+/// Every step first adds the calendar part in local time and then the clock part in elapsed
+/// time. Errors if the calendar part lands on a non-existent local time, or if the clock part
+/// crosses a change of the UTC offset, as adding it in local time would give another point.
+#[cfg(feature = "timezones")]
+fn mixed_range(
+    first: i64,
+    last: i64,
+    every: Duration,
+    add: fn(&Duration, i64, Option<&Tz>) -> PolarsResult<i64>,
+    tu: TimeUnit,
+    tz: &Tz,
+    options: &UpsampleOptions,
+) -> PolarsResult<Vec<i64>> {
+    let calendar = every.with_nanoseconds(0);
+    let clock = Duration::from_nsecs(every.nanoseconds());
+    let utc_offset = |t| unlocalize_timestamp(t, tu, *tz) - t;
+    let in_range = |t| match options.grid.closed {
+        ClosedWindow::Both | ClosedWindow::Right => t <= last,
+        ClosedWindow::Left | ClosedWindow::None => t < last,
+    };
+    let mut values = vec![];
+    let mut t = first;
+    if in_range(t) && matches!(options.grid.closed, ClosedWindow::Both | ClosedWindow::Left) {
+        values.push(t);
+    }
+    while in_range(t) {
+        let local = add(&calendar, unlocalize_timestamp(t, tu, *tz), None)?;
+        let Some(stepped) = localize(local, tu, tz, options)? else {
+            polars_bail!(
+                ComputeError: "the calendar part of upsample `every` lands on a non-existent datetime in time zone '{}'; \
+                use an `every` of only calendar or only clock units", tz
+            )
+        };
+        t = add(&clock, stepped, None)?;
+        if !in_range(t) {
+            break;
+        }
+        polars_ensure!(
+            utc_offset(stepped) == utc_offset(t),
+            ComputeError: "the clock part of upsample `every` crosses a daylight saving time transition in time zone '{}', \
+            so it is ambiguous whether it is added in local or in elapsed time; \
+            use an `every` of only calendar or only clock units", tz
+        );
+        values.push(t);
+    }
+    Ok(values)
+}
+
+/// Convert a local time to UTC. Returns `None` if it is skipped.
+#[cfg(feature = "timezones")]
+fn localize(t: i64, tu: TimeUnit, tz: &Tz, options: &UpsampleOptions) -> PolarsResult<Option<i64>> {
+    let ndt = match tu {
+        TimeUnit::Nanoseconds => timestamp_ns_to_datetime(t),
+        TimeUnit::Microseconds => timestamp_us_to_datetime(t),
+        TimeUnit::Milliseconds => timestamp_ms_to_datetime(t),
+    };
+    let ndt = match tz.from_local_datetime(&ndt) {
+        LocalResult::Single(dt) => dt.naive_utc(),
+        LocalResult::Ambiguous(earliest, latest) => match options.grid.ambiguous {
+            UpsampleAmbiguous::Earliest => earliest.naive_utc(),
+            UpsampleAmbiguous::Latest => latest.naive_utc(),
+            UpsampleAmbiguous::Raise => polars_bail!(
+                ComputeError: "datetime '{}' of the upsample range is ambiguous in time zone '{}'", ndt, tz
+            ),
+        },
+        LocalResult::None => match options.grid.non_existent {
+            UpsampleNonExistent::Skip => return Ok(None),
+            UpsampleNonExistent::Raise => polars_bail!(
+                ComputeError: "datetime '{}' of the upsample range is non-existent in time zone '{}'", ndt, tz
+            ),
+        },
+    };
+    Ok(Some(match tu {
+        TimeUnit::Nanoseconds => ndt.timestamp_nanos_opt().unwrap(),
+        TimeUnit::Microseconds => ndt.timestamp_micros(),
+        TimeUnit::Milliseconds => ndt.timestamp_millis(),
+    }))
+}
@@ -40,6 +40,9 @@ pub struct Duration {
     pub(crate) negative: bool,
     // indicates if an integer string was passed. e.g. "2i"
     pub parsed_int: bool,
+    // indicates if business days were passed, e.g. "2bd"; `days` then counts business days
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub business_days: bool,
 }
 
 impl PartialOrd<Self> for Duration {
@@ -64,6 +67,7 @@ impl Duration {
             nsecs: fixed_slots.abs(),
             negative: fixed_slots < 0,
             parsed_int: true,
+            business_days: false,
         }
     }
 
@@ -75,9 +79,14 @@ impl Duration {
     /// * `"1y"`: 1 year
     /// * `"-1w2d"`: negative 1 week, 2 days (i.e. -9 days)
     /// * `"3d12h4m25s"`: 3 days, 12 hours, 4 minutes, and 25 seconds
+    /// * `"2.5h"`: 2 hours and 30 minutes
     ///
-    /// Aside from a leading minus sign, strings may not contain any characters other than numbers and letters
-    /// (including whitespace).
+    /// The clock units (`ns` up to and including `h`) may have a decimal number, which is
+    /// rounded to the nearest nanosecond. The other units must have an integer, as a fraction
+    /// of e.g. a calendar month is ambiguous.
+    ///
+    /// Aside from a leading minus sign and decimal points, strings may not contain any characters other than
+    /// numbers and letters (including whitespace).
     ///
     /// The available units, in ascending order of magnitude, are as follows:
     ///
@@ -93,6 +102,8 @@ impl Duration {
     /// * `q`: calendar quarter
     /// * `y`:  calendar year
     /// * `i`:  index value (only for {Int32, Int64} dtypes)
+    /// * `bd`: business day, only supported by the upsample and not combinable with other
+    ///   units
     ///
     /// By "calendar day", we mean the corresponding time on the next
     /// day (which may not be 24 hours, depending on daylight savings).
@@ -125,13 +136,20 @@ impl Duration {
         }
 
         let mut parsed_int = false;
+        let mut business_days = false;
+        let mut bdays = 0;
 
         let mut unit = String::with_capacity(2);
         while let Some((i, mut ch)) = iter.next() {
-            if !ch.is_ascii_digit() {
-                let n = duration[start..i]
-                    .parse::<i64>()
-                    .expect("expected an integer in the duration string");
+            if !ch.is_ascii_digit() && ch != '.' {
+                let number = &duration[start..i];
+                let decimal = number.contains('.').then_some(number);
+                let n = match decimal {
+                    Some(_) => 0,
+                    None => number
+                        .parse::<i64>()
+                        .expect("expected an integer in the duration string"),
+                };
 
                 loop {
                     if ch.is_ascii_alphabetic() {
@@ -152,6 +170,20 @@ impl Duration {
                 if unit.is_empty() {
                     panic!("expected a unit in the duration string")
                 }
+                if let Some(number) = decimal {
+                    let unit_ns = match &*unit {
+                        "ns" => 1,
+                        "us" => NS_MICROSECOND,
+                        "ms" => NS_MILLISECOND,
+                        "s" => NS_SECOND,
+                        "m" => NS_MINUTE,
+                        "h" => NS_HOUR,
+                        unit => panic!("unit: '{unit}' does not support a decimal number, only 'ns', 'us', 'ms', 's', 'm' and 'h' do"),
+                    };
+                    nsecs += decimal_to_ns(number, unit_ns);
+                    unit.clear();
+                    continue;
+                }
 
                 match &*unit {
                     "ns" => nsecs += n,
@@ -172,11 +204,21 @@ impl Duration {
                         nsecs += n;
                         parsed_int = true;
                     }
-                    unit => panic!("unit: '{unit}' not supported. Available units are: 'ns', 'us', 'ms', 's', 'm', 'h', 'd', 'w', 'q', 'mo', 'y', 'i'"),
+                    "bd" => {
+                        bdays += n;
+                        business_days = true;
+                    }
+                    unit => panic!("unit: '{unit}' not supported. Available units are: 'ns', 'us', 'ms', 's', 'm', 'h', 'd', 'w', 'q', 'mo', 'y', 'i', 'bd'"),
                 }
                 unit.clear();
             }
         }
+        if business_days {
+            if nsecs != 0 || days != 0 || weeks != 0 || months != 0 || parsed_int {
+                panic!("business days 'bd' cannot be combined with other units")
+            }
+            days = bdays;
+        }
         Duration {
             nsecs: nsecs.abs(),
             days: days.abs(),
@@ -184,6 +226,7 @@ impl Duration {
             months: months.abs(),
             negative,
             parsed_int,
+            business_days,
         }
     }
 
@@ -250,6 +293,7 @@ impl Duration {
             nsecs,
             negative,
             parsed_int: false,
+            business_days: false,
         }
     }
 
@@ -263,6 +307,7 @@ impl Duration {
             nsecs: 0,
             negative,
             parsed_int: false,
+            business_days: false,
         }
     }
 
@@ -276,6 +321,7 @@ impl Duration {
             nsecs: 0,
             negative,
             parsed_int: false,
+            business_days: false,
         }
     }
 
@@ -289,9 +335,15 @@ impl Duration {
             nsecs: 0,
             negative,
             parsed_int: false,
+            business_days: false,
         }
     }
 
+    /// Returns this [`Duration`] with the nanoseconds part replaced by `nsecs`.
+    pub(crate) fn with_nanoseconds(self, nsecs: i64) -> Self {
+        Self { nsecs, ..self }
+    }
+
     /// `true` if zero duration.
     pub fn is_zero(&self) -> bool {
         self.months == 0 && self.weeks == 0 && self.days == 0 && self.nsecs == 0
@@ -586,6 +638,7 @@ impl Duration {
         G: Fn(i64) -> NaiveDateTime,
         J: Fn(NaiveDateTime) -> i64,
     {
+        self.ensure_calendar_days()?;
         match (self.months, self.weeks, self.days, self.nsecs) {
             (0, 0, 0, 0) => polars_bail!(ComputeError: "duration cannot be zero"),
             // truncate by ns/us/ms
@@ -660,6 +713,16 @@ impl Duration {
         )
     }
 
+    /// Business days are only understood by the upsample, so raise rather than treating them
+    /// as calendar days.
+    fn ensure_calendar_days(&self) -> PolarsResult<()> {
+        polars_ensure!(
+            !self.business_days,
+            ComputeError: "a duration in business days ('bd') is only supported as the `every` of an upsample"
+        );
+        Ok(())
+    }
+
     fn add_impl_month_week_or_day<F, G, J>(
         &self,
         t: i64,
@@ -673,6 +736,7 @@ impl Duration {
         G: Fn(i64) -> NaiveDateTime,
         J: Fn(NaiveDateTime) -> i64,
     {
+        self.ensure_calendar_days()?;
         let d = self;
         let mut new_t = t;
 
@@ -799,10 +863,51 @@ fn new_datetime(
     Some(NaiveDateTime::new(date, time))
 }
 
+/// Convert a decimal `number` of a unit of `unit_ns` nanoseconds to nanoseconds, rounded to
+/// the nearest nanosecond.
+///
+/// The integer part and the fraction are scaled separately in integer math, as a float
+/// loses precision for large durations.
+fn decimal_to_ns(number: &str, unit_ns: i64) -> i64 {
+    let (int, frac) = number.split_once('.').unwrap();
+    if frac.contains('.') {
+        panic!("a number in the duration string can only have a single '.', got '{number}'")
+    }
+    if int.is_empty() && frac.is_empty() {
+        panic!("expected a number in the duration string")
+    }
+    let int = match int {
+        "" => 0,
+        int => int
+            .parse::<i64>()
+            .expect("expected a number in the duration string"),
+    };
+    // The units are at most an hour, so digits after the 18th are far below a nanosecond.
+    let frac = &frac[..frac.len().min(18)];
+    let frac_ns = match frac {
+        "" => 0,
+        frac => {
+            let scale = 10i128.pow(frac.len() as u32);
+            let digits = frac.parse::<i128>().unwrap();
+            ((digits * unit_ns as i128 + scale / 2) / scale) as i64
+        },
+    };
+    int.checked_mul(unit_ns)
+        .and_then(|ns| ns.checked_add(frac_ns))
+        .unwrap_or_else(|| panic!("the duration '{number}' is out of range"))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_business_days_are_not_calendar_days() {
+        let bd = Duration::parse("2bd");
+        assert!(bd.add_ns(0, None).is_err());
+        assert!(bd.truncate_ms(0, None).is_err());
+    }
+
     #[test]
     fn test_parse() {
         let out = Duration::parse("1ns");
@@ -820,6 +925,52 @@ mod test {
         assert_eq!(out.weeks(), 5);
     }
 
+    #[test]
+    fn test_parse_decimal() {
+        let out = Duration::parse("2.5h");
+        assert_eq!(out.nsecs, 2 * NS_HOUR + 30 * NS_MINUTE);
+        assert!(out.is_constant_duration());
+        let out = Duration::parse("-1d0.5s");
+        assert!(out.negative);
+        assert_eq!(out.days(), 1);
+        assert_eq!(out.nsecs, NS_SECOND / 2);
+        // Rounded to the nearest nanosecond.
+        assert_eq!(Duration::parse("0.0000000016s").nsecs, 2);
+    }
+
+    #[test]
+    fn test_parse_decimal_precision() {
+        // A float would round these to a multiple of 1024ns.
+        assert_eq!(
+            Duration::parse("9007199254.740993s").nsecs,
+            9_007_199_254_740_993_000
+        );
+        assert_eq!(
+            Duration::parse("2562047.788015215h").nsecs,
+            2_562_047 * NS_HOUR + 2_836_854_774_000
+        );
+        assert_eq!(Duration::parse(".25s").nsecs, NS_SECOND / 4);
+        assert_eq!(Duration::parse("3.h").nsecs, 3 * NS_HOUR);
+    }
+
+    #[test]
+    #[should_panic(expected = "can only have a single '.'")]
+    fn test_parse_decimal_two_points() {
+        Duration::parse("1.2.3h");
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_parse_decimal_overflow() {
+        Duration::parse("2562048.5h");
+    }
+
+    #[test]
+    #[should_panic(expected = "does not support a decimal number")]
+    fn test_parse_decimal_calendar() {
+        Duration::parse("1.5mo");
+    }
+
     #[test]
     fn test_add_ns() {
         let t = 1;
@@ -267,6 +267,7 @@ impl PyExpr {
                 nulls_last,
                 multithreaded: true,
                 maintain_order: false,
+                ..Default::default()
             })
             .into()
     }
@@ -279,6 +280,7 @@ impl PyExpr {
                 nulls_last,
                 multithreaded: true,
                 maintain_order: false,
+                ..Default::default()
             })
             .into()
     }
@@ -423,6 +423,7 @@ impl PyLazyFrame {
                 nulls_last,
                 multithreaded: true,
                 maintain_order,
+                ..Default::default()
             },
         )
         .into()
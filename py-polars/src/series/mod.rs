@@ -671,6 +671,7 @@ impl PySeries {
             nulls_last: descending,
             multithreaded: true,
             maintain_order: false,
+            ..Default::default()
         };
         Ok(self.series.is_sorted(options).map_err(PyPolarsErr::from)?)
     }